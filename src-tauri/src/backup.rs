@@ -1,10 +1,14 @@
 use crate::db::Database;
-use crate::models::BackupManifest;
+use crate::jobs::{spawn_job, JobContext};
+use crate::migrations;
+use crate::models::{BackupManifest, PartialDate};
 use chrono::Utc;
-use rusqlite::params;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter};
 use std::fs::{self, File};
 use std::io::{Read, Write};
 use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
 use walkdir::WalkDir;
 use zip::write::SimpleFileOptions;
 use zip::{ZipArchive, ZipWriter};
@@ -12,23 +16,60 @@ use zip::{ZipArchive, ZipWriter};
 const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 const BACKUP_VERSION: &str = "1.0";
 
+/// Family columns copied from a backup, besides family_id
+const FAMILY_COLUMNS: [&str; 20] = [
+    "name",
+    "mailing_name",
+    "address",
+    "city",
+    "state",
+    "zip",
+    "phone",
+    "email",
+    "photo_path",
+    "notes",
+    "children",
+    "alt_address",
+    "alt_city",
+    "alt_state",
+    "alt_zip",
+    "directory_adults",
+    "directory_children",
+    "sort_order",
+    "include_photo_in_directory",
+    "photo_crop",
+];
+
+/// Start writing a backup archive as a background job and return its job id.
+/// Reports "files" progress; the job's result is the BackupManifest.
 #[tauri::command]
-pub fn create_backup(
-    app_handle: AppHandle,
-    db: State<'_, Database>,
-    output_path: String,
-) -> Result<BackupManifest, String> {
+pub fn create_backup(app_handle: AppHandle, output_path: String) -> Result<String, String> {
+    spawn_job(&app_handle, move |job| {
+        let result = write_backup(job, &output_path);
+        if result.is_err() {
+            // Don't leave a partial archive behind
+            let _ = fs::remove_file(&output_path);
+        }
+        result
+    })
+}
+
+fn write_backup(job: &JobContext, output_path: &str) -> Result<BackupManifest, String> {
+    let app_handle = job.app_handle();
+    let db = app_handle.state::<Database>();
     let app_data_dir = app_handle
         .path()
         .app_data_dir()
         .map_err(|e| e.to_string())?;
 
-    let db_path = app_data_dir.join("directory.db");
     let photos_dir = app_data_dir.join("photos");
 
+    // Edits can land while the backup runs, so archive a consistent copy of
+    // the database taken under the connection lock rather than the live file
+    let snapshot_path = std::env::temp_dir().join(format!("directory-backup-{}.db", Uuid::new_v4()));
     let family_count: i64;
     let member_count: i64;
-    {
+    let db_contents = {
         let conn = db.conn.lock().map_err(|e| e.to_string())?;
         family_count = conn
             .query_row("SELECT COUNT(*) FROM families", [], |row| row.get(0))
@@ -36,7 +77,12 @@ pub fn create_backup(
         member_count = conn
             .query_row("SELECT COUNT(*) FROM members", [], |row| row.get(0))
             .map_err(|e| e.to_string())?;
+        conn.execute("VACUUM INTO ?", params![snapshot_path.to_string_lossy()])
+            .map_err(|e| format!("Failed to snapshot the database: {}", e))
     }
+    .and_then(|_| fs::read(&snapshot_path).map_err(|e| e.to_string()));
+    let _ = fs::remove_file(&snapshot_path);
+    let db_contents = db_contents?;
 
     let mut photo_count = 0;
     if photos_dir.exists() {
@@ -56,7 +102,11 @@ pub fn create_backup(
         photo_count,
     };
 
-    let file = File::create(&output_path).map_err(|e| e.to_string())?;
+    // The database plus every photo
+    let total_files = photo_count as u64 + 1;
+    let mut files_written = 0;
+
+    let file = File::create(output_path).map_err(|e| e.to_string())?;
     let mut zip = ZipWriter::new(file);
     let options = SimpleFileOptions::default()
         .compression_method(zip::CompressionMethod::Deflated)
@@ -68,20 +118,15 @@ pub fn create_backup(
     zip.write_all(manifest_json.as_bytes())
         .map_err(|e| e.to_string())?;
 
-    if db_path.exists() {
-        let mut db_file = File::open(&db_path).map_err(|e| e.to_string())?;
-        let mut db_contents = Vec::new();
-        db_file
-            .read_to_end(&mut db_contents)
-            .map_err(|e| e.to_string())?;
-
-        zip.start_file("directory.db", options)
-            .map_err(|e| e.to_string())?;
-        zip.write_all(&db_contents).map_err(|e| e.to_string())?;
-    }
+    zip.start_file("directory.db", options)
+        .map_err(|e| e.to_string())?;
+    zip.write_all(&db_contents).map_err(|e| e.to_string())?;
+    files_written += 1;
+    job.progress("files", files_written, Some(total_files));
 
     if photos_dir.exists() {
         for entry in WalkDir::new(&photos_dir).into_iter().filter_map(|e| e.ok()) {
+            job.check_cancelled()?;
             let path = entry.path();
             if path.is_file() {
                 let relative_path = path
@@ -96,6 +141,9 @@ pub fn create_backup(
                 zip.start_file(&archive_path, options)
                     .map_err(|e| e.to_string())?;
                 zip.write_all(&contents).map_err(|e| e.to_string())?;
+
+                files_written += 1;
+                job.progress("files", files_written, Some(total_files));
             }
         }
     }
@@ -179,42 +227,28 @@ pub fn restore_backup(
             fs::write(&temp_db_path, &contents).map_err(|e| e.to_string())?;
 
             {
-                let source_conn =
-                    rusqlite::Connection::open(&temp_db_path).map_err(|e| e.to_string())?;
+                // Bring an older backup up to the current schema before reading it
+                let mut source_conn =
+                    Database::open_connection(&temp_db_path).map_err(|e| e.to_string())?;
+                migrations::migrate(&mut source_conn).map_err(|e| e.to_string())?;
                 let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
                 let mut stmt = source_conn
-                    .prepare(
-                        "SELECT family_id, name, mailing_name, address, city, state, zip, phone, email, photo_path, notes, children, alt_address, alt_city, alt_state, alt_zip FROM families",
-                    )
+                    .prepare(&format!("SELECT family_id, {} FROM families", FAMILY_COLUMNS.join(", ")))
                     .map_err(|e| e.to_string())?;
 
                 let families = stmt
                     .query_map([], |row| {
-                        Ok((
-                            row.get::<_, String>(0)?,       // family_id
-                            row.get::<_, String>(1)?,       // name
-                            row.get::<_, Option<String>>(2)?,  // mailing_name
-                            row.get::<_, Option<String>>(3)?,  // address
-                            row.get::<_, Option<String>>(4)?,  // city
-                            row.get::<_, Option<String>>(5)?,  // state
-                            row.get::<_, Option<String>>(6)?,  // zip
-                            row.get::<_, Option<String>>(7)?,  // phone
-                            row.get::<_, Option<String>>(8)?,  // email
-                            row.get::<_, Option<String>>(9)?,  // photo_path
-                            row.get::<_, Option<String>>(10)?, // notes
-                            row.get::<_, Option<String>>(11)?, // children
-                            row.get::<_, Option<String>>(12)?, // alt_address
-                            row.get::<_, Option<String>>(13)?, // alt_city
-                            row.get::<_, Option<String>>(14)?, // alt_state
-                            row.get::<_, Option<String>>(15)?, // alt_zip
-                        ))
+                        let family_id: String = row.get(0)?;
+                        let values = (1..=FAMILY_COLUMNS.len())
+                            .map(|idx| row.get::<_, Value>(idx))
+                            .collect::<rusqlite::Result<Vec<_>>>()?;
+                        Ok((family_id, values))
                     })
                     .map_err(|e| e.to_string())?;
 
                 for family_result in families {
-                    let (family_id, name, mailing_name, address, city, state, zip, phone, email, photo_path, notes, children, alt_address, alt_city, alt_state, alt_zip) =
-                        family_result.map_err(|e| e.to_string())?;
+                    let (family_id, values) = family_result.map_err(|e| e.to_string())?;
 
                     let existing: Option<i64> = conn
                         .query_row(
@@ -226,16 +260,25 @@ pub fn restore_backup(
 
                     let db_family_id = if let Some(id) = existing {
                         if replace_existing {
+                            let assignments: Vec<String> =
+                                FAMILY_COLUMNS.iter().map(|column| format!("{} = ?", column)).collect();
                             conn.execute(
-                                "UPDATE families SET name = ?, mailing_name = ?, address = ?, city = ?, state = ?, zip = ?, phone = ?, email = ?, photo_path = ?, notes = ?, children = ?, alt_address = ?, alt_city = ?, alt_state = ?, alt_zip = ?, updated_at = datetime('now') WHERE id = ?",
-                                params![name, mailing_name, address, city, state, zip, phone, email, photo_path, notes, children, alt_address, alt_city, alt_state, alt_zip, id],
+                                &format!(
+                                    "UPDATE families SET {}, updated_at = datetime('now') WHERE id = ?",
+                                    assignments.join(", ")
+                                ),
+                                params_from_iter(values.iter().chain([&Value::Integer(id)])),
                             ).map_err(|e| e.to_string())?;
                         }
                         id
                     } else {
                         conn.execute(
-                            "INSERT INTO families (family_id, name, mailing_name, address, city, state, zip, phone, email, photo_path, notes, children, alt_address, alt_city, alt_state, alt_zip) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                            params![family_id, name, mailing_name, address, city, state, zip, phone, email, photo_path, notes, children, alt_address, alt_city, alt_state, alt_zip],
+                            &format!(
+                                "INSERT INTO families (family_id, {}) VALUES (?{})",
+                                FAMILY_COLUMNS.join(", "),
+                                ", ?".repeat(FAMILY_COLUMNS.len())
+                            ),
+                            params_from_iter([&Value::Text(family_id.clone())].into_iter().chain(&values)),
                         ).map_err(|e| e.to_string())?;
                         conn.last_insert_rowid()
                     };
@@ -250,7 +293,9 @@ pub fn restore_backup(
 
                     let mut member_stmt = source_conn
                         .prepare(
-                            "SELECT first_name, last_name, role, birth_date, wedding_date, phone, email, photo_path, notes, sort_order FROM members WHERE family_id = ?",
+                            "SELECT first_name, last_name, role, phone, email, photo_path, notes, sort_order,
+                                    birth_month, birth_day, birth_year, wedding_month, wedding_day, wedding_year
+                             FROM members WHERE family_id = ?",
                         )
                         .map_err(|e| e.to_string())?;
 
@@ -264,15 +309,15 @@ pub fn restore_backup(
                                 row.get::<_, Option<String>>(4)?,
                                 row.get::<_, Option<String>>(5)?,
                                 row.get::<_, Option<String>>(6)?,
-                                row.get::<_, Option<String>>(7)?,
-                                row.get::<_, Option<String>>(8)?,
-                                row.get::<_, i32>(9)?,
+                                row.get::<_, i32>(7)?,
+                                PartialDate::from_row(row, 8)?,
+                                PartialDate::from_row(row, 11)?,
                             ))
                         })
                         .map_err(|e| e.to_string())?;
 
                     for member_result in members {
-                        let (first_name, last_name, role, birth_date, wedding_date, phone, email, photo_path, notes, sort_order) =
+                        let (first_name, last_name, role, phone, email, photo_path, notes, sort_order, birth, wedding) =
                             member_result.map_err(|e| e.to_string())?;
 
                         let existing_member: Option<i64> = conn
@@ -286,14 +331,44 @@ pub fn restore_backup(
                         if let Some(member_id) = existing_member {
                             if replace_existing {
                                 conn.execute(
-                                    "UPDATE members SET role = ?, birth_date = ?, wedding_date = ?, phone = ?, email = ?, photo_path = ?, notes = ?, sort_order = ?, updated_at = datetime('now') WHERE id = ?",
-                                    params![role, birth_date, wedding_date, phone, email, photo_path, notes, sort_order, member_id],
+                                    "UPDATE members SET role = ?, birth_month = ?, birth_day = ?, birth_year = ?, wedding_month = ?, wedding_day = ?, wedding_year = ?, phone = ?, email = ?, photo_path = ?, notes = ?, sort_order = ?, updated_at = datetime('now') WHERE id = ?",
+                                    params![
+                                        role,
+                                        birth.map(|d| d.month),
+                                        birth.map(|d| d.day),
+                                        birth.and_then(|d| d.year),
+                                        wedding.map(|d| d.month),
+                                        wedding.map(|d| d.day),
+                                        wedding.and_then(|d| d.year),
+                                        phone,
+                                        email,
+                                        photo_path,
+                                        notes,
+                                        sort_order,
+                                        member_id,
+                                    ],
                                 ).map_err(|e| e.to_string())?;
                             }
                         } else {
                             conn.execute(
-                                "INSERT INTO members (family_id, first_name, last_name, role, birth_date, wedding_date, phone, email, photo_path, notes, sort_order) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                                params![db_family_id, first_name, last_name, role, birth_date, wedding_date, phone, email, photo_path, notes, sort_order],
+                                "INSERT INTO members (family_id, first_name, last_name, role, birth_month, birth_day, birth_year, wedding_month, wedding_day, wedding_year, phone, email, photo_path, notes, sort_order) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                                params![
+                                    db_family_id,
+                                    first_name,
+                                    last_name,
+                                    role,
+                                    birth.map(|d| d.month),
+                                    birth.map(|d| d.day),
+                                    birth.and_then(|d| d.year),
+                                    wedding.map(|d| d.month),
+                                    wedding.map(|d| d.day),
+                                    wedding.and_then(|d| d.year),
+                                    phone,
+                                    email,
+                                    photo_path,
+                                    notes,
+                                    sort_order,
+                                ],
                             ).map_err(|e| e.to_string())?;
                         }
                     }
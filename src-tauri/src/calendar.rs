@@ -0,0 +1,228 @@
+//! iCalendar export of the birthdays and anniversaries on the celebrations pages
+
+use crate::db::Database;
+use crate::jobs::{spawn_job, JobContext};
+use crate::locale::{Locale, Text};
+use crate::models::{PartialDate, PdfOptions};
+use crate::pdf::{gather_celebrations, load_families_with_members, CelebrationEntry, CelebrationMonth};
+use chrono::Utc;
+use tauri::{AppHandle, Manager};
+
+/// Domain part of every event UID
+const UID_DOMAIN: &str = "churchdirectory.app";
+
+/// Longest content line allowed before folding, in bytes (RFC 5545 3.1)
+const MAX_LINE_BYTES: usize = 75;
+
+/// Start writing the birthdays and anniversaries the celebrations pages list
+/// to an iCalendar file as a background job and return its job id. The
+/// job's result is the number of events written. Event UIDs come from member
+/// ids, so importing a newer export updates the events already in a calendar
+/// instead of duplicating them.
+#[tauri::command]
+pub fn export_celebrations_ics(
+    app_handle: AppHandle,
+    options: PdfOptions,
+    output_path: String,
+) -> Result<String, String> {
+    spawn_job(&app_handle, move |job| {
+        let result = write_ics(job, &options, &output_path);
+        if result.is_err() {
+            let _ = std::fs::remove_file(&output_path);
+        }
+        result
+    })
+}
+
+fn write_ics(job: &JobContext, options: &PdfOptions, output_path: &str) -> Result<usize, String> {
+    let db = job.app_handle().state::<Database>();
+    let (families, locale) = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        (load_families_with_members(&conn)?, Locale::load(&conn))
+    };
+    let months = gather_celebrations(&families, options, locale);
+    job.check_cancelled()?;
+
+    let title = locale.text(Text::BirthdaysAndAnniversaries);
+    let calendar_name = if options.church_name.trim().is_empty() {
+        title.to_string()
+    } else {
+        format!("{} {}", options.church_name.trim(), title)
+    };
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let (contents, count) = celebrations_calendar(&months, &calendar_name, locale, &stamp);
+
+    std::fs::write(output_path, contents).map_err(|e| e.to_string())?;
+    Ok(count)
+}
+
+/// The iCalendar text for every birthday and anniversary, and how many
+/// events it holds
+fn celebrations_calendar(months: &[CelebrationMonth], name: &str, locale: Locale, stamp: &str) -> (String, usize) {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Church Directory//Celebrations//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(name)),
+    ];
+    let mut count = 0;
+    for month in months {
+        for entry in &month.birthdays {
+            push_event(&mut lines, entry, "birthday", &locale.format(Text::BirthdayOf, &entry.name), stamp);
+            count += 1;
+        }
+        for entry in &month.anniversaries {
+            push_event(&mut lines, entry, "anniversary", &locale.format(Text::AnniversaryOf, &entry.name), stamp);
+            count += 1;
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut contents = String::new();
+    for line in &lines {
+        fold_line(line, &mut contents);
+    }
+    (contents, count)
+}
+
+/// A yearly all-day event starting on the original date, or in 2000 when the
+/// year isn't known. February 29 falls back to February 28 in other years.
+fn push_event(lines: &mut Vec<String>, entry: &CelebrationEntry, kind: &str, summary: &str, stamp: &str) {
+    let mut ids = entry.member_ids.clone();
+    ids.sort_unstable();
+    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    let PartialDate { month, day, year } = entry.date;
+    let rule = if month == 2 && day == 29 {
+        "RRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=-1"
+    } else {
+        "RRULE:FREQ=YEARLY"
+    };
+
+    lines.extend([
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}-{}@{}", kind, ids.join("-"), UID_DOMAIN),
+        format!("DTSTAMP:{}", stamp),
+        format!("DTSTART;VALUE=DATE:{:04}{:02}{:02}", year.unwrap_or(2000), month, day),
+        rule.to_string(),
+        format!("SUMMARY:{}", escape_text(summary)),
+        format!("CATEGORIES:{}", kind.to_uppercase()),
+        "TRANSP:TRANSPARENT".to_string(),
+        "END:VEVENT".to_string(),
+    ]);
+}
+
+/// Escape a TEXT value (RFC 5545 3.3.11)
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Append a content line, folding it onto continuation lines that start with
+/// a space whenever it runs past 75 bytes. Never splits a character.
+fn fold_line(line: &str, out: &mut String) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_BYTES {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FamilyWithMembers;
+    use serde_json::json;
+
+    fn member(id: i64, first_name: &str, role: &str, birth: Option<&str>, wedding: Option<&str>) -> serde_json::Value {
+        json!({
+            "id": id, "family_id": 1, "first_name": first_name, "last_name": "Smith", "role": role,
+            "birth_date": birth, "wedding_date": wedding, "sort_order": id,
+            "created_at": "", "updated_at": "",
+        })
+    }
+
+    fn calendar(members: Vec<serde_json::Value>) -> String {
+        let family: FamilyWithMembers = serde_json::from_value(json!({
+            "id": 1, "family_id": "F001", "name": "Smith", "include_photo_in_directory": true,
+            "created_at": "", "updated_at": "", "members": members,
+        }))
+        .unwrap();
+        let options: PdfOptions = serde_json::from_value(json!({ "church_name": "First Church" })).unwrap();
+        let months = gather_celebrations(&[family], &options, Locale::English);
+        celebrations_calendar(&months, "First Church", Locale::English, "20260101T000000Z").0
+    }
+
+    fn uids(calendar: &str) -> Vec<&str> {
+        calendar.lines().filter(|line| line.starts_with("UID:")).collect()
+    }
+
+    #[test]
+    fn escapes_text_values() {
+        assert_eq!(escape_text("Smith, John; Jr.\\Sr\nNext\r"), "Smith\\, John\\; Jr.\\\\Sr\\nNext");
+    }
+
+    #[test]
+    fn folds_long_lines_without_splitting_characters() {
+        let line = format!("SUMMARY:{}", "결혼기념일 ".repeat(12));
+        let mut folded = String::new();
+        fold_line(&line, &mut folded);
+
+        let physical: Vec<&str> = folded.strip_suffix("\r\n").unwrap().split("\r\n").collect();
+        assert!(physical.len() > 1);
+        assert!(physical.iter().all(|l| l.len() <= MAX_LINE_BYTES));
+        assert!(physical[1..].iter().all(|l| l.starts_with(' ')));
+        let unfolded = folded.replace("\r\n ", "");
+        assert_eq!(unfolded, format!("{}\r\n", line));
+    }
+
+    #[test]
+    fn leap_day_recurs_on_the_last_day_of_february() {
+        let ics = calendar(vec![
+            member(1, "Leap", "Head", Some("02-29"), None),
+            member(2, "Known", "Spouse", Some("1952-02-29"), None),
+        ]);
+        assert!(ics.contains("DTSTART;VALUE=DATE:20000229\r\nRRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=-1\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:19520229\r\nRRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=-1\r\n"));
+    }
+
+    #[test]
+    fn uids_stay_the_same_when_members_are_reordered() {
+        let john = member(12, "John", "Head", Some("1950-03-14"), Some("1975-06-14"));
+        let mary = member(13, "Mary", "Spouse", Some("03-05"), Some("1975-06-14"));
+        let ics = calendar(vec![john.clone(), mary.clone()]);
+        assert_eq!(
+            uids(&ics),
+            [
+                "UID:birthday-13@churchdirectory.app",
+                "UID:birthday-12@churchdirectory.app",
+                "UID:anniversary-12-13@churchdirectory.app",
+            ]
+        );
+
+        let mut reordered = vec![mary, john];
+        reordered[0]["sort_order"] = json!(0);
+        let reordered_ics = calendar(reordered);
+        let mut before = uids(&ics);
+        let mut after = uids(&reordered_ics);
+        before.sort_unstable();
+        after.sort_unstable();
+        assert_eq!(before, after);
+    }
+}
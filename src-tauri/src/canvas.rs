@@ -0,0 +1,450 @@
+use crate::fonts::{EmbeddedFonts, LoadedFont, RasterFonts};
+use crate::images::PreparedImage;
+use ab_glyph::{point, Font, PxScale, ScaleFont};
+use printpdf::*;
+use std::cell::{Cell, RefCell};
+
+/// A drawing surface for one booklet page or sheet. Coordinates are in mm with
+/// the origin at the bottom-left, matching PDF user space, and colors are theme
+/// "#RRGGBB" strings. Fill and outline state persists between calls like a PDF
+/// graphics state.
+pub trait PageCanvas {
+    fn set_fill_color(&self, hex: &str);
+    fn set_outline_color(&self, hex: &str);
+    /// Stroke width in points
+    fn set_outline_thickness(&self, thickness: f32);
+    /// Draw text with its baseline starting at (x, y). font_size is in points.
+    fn text(&self, text: &str, font_size: f32, x: f32, y: f32, font: &LoadedFont);
+    fn line(&self, x1: f32, y1: f32, x2: f32, y2: f32);
+    /// Stroke the outline of a rectangle with rounded corners
+    fn rounded_rect(&self, x: f32, y: f32, w: f32, h: f32, radius: f32);
+    /// Place an image with its bottom-left corner at (x, y), sized by `dpi`
+    fn image(&self, img: &PreparedImage, x: f32, y: f32, dpi: f32);
+    /// Make the rectangle with its bottom-left corner at (x, y) open `url` when
+    /// clicked. Surfaces that aren't interactive ignore it.
+    fn link(&self, x: f32, y: f32, w: f32, h: f32, url: &str);
+}
+
+/// Draws onto a layer of the printed PDF. Prepared images are embedded as
+/// JPEG streams, or as CMYK pixels for PDF/X output.
+pub struct PdfCanvas<'a> {
+    layer: PdfLayerReference,
+    fonts: &'a EmbeddedFonts,
+    origin_x: f32,
+    origin_y: f32,
+    scale: f32,
+    pdf_x: bool,
+}
+
+impl<'a> PdfCanvas<'a> {
+    pub fn new(layer: PdfLayerReference, fonts: &'a EmbeddedFonts) -> Self {
+        PdfCanvas { layer, fonts, origin_x: 0.0, origin_y: 0.0, scale: 1.0, pdf_x: false }
+    }
+
+    /// Offset all drawing by (x, y) mm, so callers can work in trim coordinates
+    /// on a sheet whose media box includes bleed and printer's marks
+    pub fn with_origin(mut self, x: f32, y: f32) -> Self {
+        self.origin_x = x;
+        self.origin_y = y;
+        self
+    }
+
+    /// Shrink everything drawn by `scale`, for placing pages in smaller slots
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Draw only what PDF/X-3 allows: colors and color photos are converted
+    /// to DeviceCMYK, and links are left out
+    pub fn with_pdf_x(mut self, pdf_x: bool) -> Self {
+        self.pdf_x = pdf_x;
+        self
+    }
+
+    fn point(&self, x: f32, y: f32) -> Point {
+        Point::new(
+            Mm(self.origin_x + x * self.scale),
+            Mm(self.origin_y + y * self.scale),
+        )
+    }
+}
+
+impl PageCanvas for PdfCanvas<'_> {
+    fn set_fill_color(&self, hex: &str) {
+        self.layer.set_fill_color(pdf_color(hex, self.pdf_x));
+    }
+
+    fn set_outline_color(&self, hex: &str) {
+        self.layer.set_outline_color(pdf_color(hex, self.pdf_x));
+    }
+
+    fn set_outline_thickness(&self, thickness: f32) {
+        self.layer.set_outline_thickness(thickness * self.scale);
+    }
+
+    fn text(&self, text: &str, font_size: f32, x: f32, y: f32, font: &LoadedFont) {
+        self.layer.use_text(
+            text,
+            font_size * self.scale,
+            Mm(self.origin_x + x * self.scale),
+            Mm(self.origin_y + y * self.scale),
+            self.fonts.get(font.variant),
+        );
+    }
+
+    fn line(&self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        self.layer.add_line(Line {
+            points: vec![
+                (self.point(x1, y1), false),
+                (self.point(x2, y2), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    fn rounded_rect(&self, x: f32, y: f32, w: f32, h: f32, radius: f32) {
+        // Kappa for cubic Bezier approximation of quarter circle
+        let k = radius * 0.5523;
+
+        let points = vec![
+            // Start at bottom-left, just above the corner
+            (self.point(x, y + radius), false),
+            // Bottom-left corner
+            (self.point(x, y + radius - k), true),
+            (self.point(x + radius - k, y), true),
+            (self.point(x + radius, y), false),
+            // Bottom edge to bottom-right corner
+            (self.point(x + w - radius, y), false),
+            // Bottom-right corner
+            (self.point(x + w - radius + k, y), true),
+            (self.point(x + w, y + radius - k), true),
+            (self.point(x + w, y + radius), false),
+            // Right edge to top-right corner
+            (self.point(x + w, y + h - radius), false),
+            // Top-right corner
+            (self.point(x + w, y + h - radius + k), true),
+            (self.point(x + w - radius + k, y + h), true),
+            (self.point(x + w - radius, y + h), false),
+            // Top edge to top-left corner
+            (self.point(x + radius, y + h), false),
+            // Top-left corner
+            (self.point(x + radius - k, y + h), true),
+            (self.point(x, y + h - radius + k), true),
+            (self.point(x, y + h - radius), false),
+        ];
+
+        self.layer.add_line(Line {
+            points,
+            is_closed: true,
+        });
+    }
+
+    fn image(&self, img: &PreparedImage, x: f32, y: f32, dpi: f32) {
+        let (color_space, image_data, image_filter) = if img.grayscale {
+            (ColorSpace::Greyscale, img.jpeg.clone(), Some(ImageFilter::DCT))
+        } else if self.pdf_x {
+            match cmyk_pixels(&img.jpeg) {
+                Ok(pixels) => (ColorSpace::Cmyk, pixels, None),
+                Err(e) => {
+                    log::warn!("Failed to convert prepared image to CMYK: {}", e);
+                    return;
+                }
+            }
+        } else {
+            (ColorSpace::Rgb, img.jpeg.clone(), Some(ImageFilter::DCT))
+        };
+        let xobject = ImageXObject {
+            width: Px(img.width as usize),
+            height: Px(img.height as usize),
+            color_space,
+            bits_per_component: ColorBits::Bit8,
+            interpolate: true,
+            image_data,
+            image_filter,
+            clipping_bbox: None,
+            smask: None,
+        };
+        Image::from(xobject).add_to_layer(
+            self.layer.clone(),
+            ImageTransform {
+                translate_x: Some(Mm(self.origin_x + x * self.scale)),
+                translate_y: Some(Mm(self.origin_y + y * self.scale)),
+                dpi: Some(dpi / self.scale),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn link(&self, x: f32, y: f32, w: f32, h: f32, url: &str) {
+        // PDF/X doesn't allow annotations inside the bleed box
+        if self.pdf_x {
+            return;
+        }
+        let Point { x: llx, y: lly } = self.point(x, y);
+        let Point { x: urx, y: ury } = self.point(x + w, y + h);
+        self.layer.add_link_annotation(LinkAnnotation::new(
+            Rect::new(llx.into(), lly.into(), urx.into(), ury.into()),
+            // printpdf draws a visible border by default
+            Some(BorderArray::Solid([0.0, 0.0, 0.0])),
+            None,
+            Actions::uri(url.to_string()),
+            None,
+        ));
+    }
+}
+
+/// Convert a theme "#RRGGBB" color to a PDF fill or outline color, in
+/// DeviceCMYK when `cmyk` is set
+fn pdf_color(hex: &str, cmyk: bool) -> Color {
+    let (r, g, b) = parse_hex_color(hex);
+    if cmyk {
+        let [c, m, y, k] = rgb_to_cmyk(r, g, b);
+        Color::Cmyk(Cmyk::new(c, m, y, k, None))
+    } else {
+        Color::Rgb(Rgb::new(r, g, b, None))
+    }
+}
+
+/// Uncalibrated RGB to CMYK with full black generation, so grays and black
+/// text print on the black plate only
+fn rgb_to_cmyk(r: f32, g: f32, b: f32) -> [f32; 4] {
+    let k = 1.0 - r.max(g).max(b);
+    if k >= 1.0 {
+        return [0.0, 0.0, 0.0, 1.0];
+    }
+    let ink = |channel: f32| (1.0 - channel - k) / (1.0 - k);
+    [ink(r), ink(g), ink(b), k]
+}
+
+/// Decode a prepared JPEG into 8-bit CMYK pixels
+fn cmyk_pixels(jpeg: &[u8]) -> Result<Vec<u8>, String> {
+    let decoded = image_crate::load_from_memory_with_format(jpeg, image_crate::ImageFormat::Jpeg)
+        .map_err(|e| e.to_string())?;
+    let rgb = decoded.to_rgb8();
+    let mut pixels = Vec::with_capacity(rgb.as_raw().len() / 3 * 4);
+    for pixel in rgb.pixels() {
+        let [r, g, b] = pixel.0.map(|v| v as f32 / 255.0);
+        pixels.extend(rgb_to_cmyk(r, g, b).map(|v| (v * 255.0).round() as u8));
+    }
+    Ok(pixels)
+}
+
+/// Draws nothing. Layout code runs against it to find how much room content
+/// will take before placing it on a page.
+pub struct MeasureCanvas;
+
+impl PageCanvas for MeasureCanvas {
+    fn set_fill_color(&self, _hex: &str) {}
+    fn set_outline_color(&self, _hex: &str) {}
+    fn set_outline_thickness(&self, _thickness: f32) {}
+    fn text(&self, _text: &str, _font_size: f32, _x: f32, _y: f32, _font: &LoadedFont) {}
+    fn line(&self, _x1: f32, _y1: f32, _x2: f32, _y2: f32) {}
+    fn rounded_rect(&self, _x: f32, _y: f32, _w: f32, _h: f32, _radius: f32) {}
+    fn image(&self, _img: &PreparedImage, _x: f32, _y: f32, _dpi: f32) {}
+    fn link(&self, _x: f32, _y: f32, _w: f32, _h: f32, _url: &str) {}
+}
+
+pub fn parse_hex_color(hex: &str) -> (f32, f32, f32) {
+    let hex = hex.trim_start_matches('#');
+    if hex.len() >= 6 {
+        let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or(255) as f32 / 255.0;
+        let g = u8::from_str_radix(&hex[2..4], 16).unwrap_or(255) as f32 / 255.0;
+        let b = u8::from_str_radix(&hex[4..6], 16).unwrap_or(255) as f32 / 255.0;
+        (r, g, b)
+    } else {
+        (1.0, 1.0, 1.0) // default white
+    }
+}
+
+/// Draws a single page into an in-memory RGB image for on-screen previews.
+/// Text is rasterized from the same font files the PDF embeds, so line breaks
+/// and positions match the printed booklet.
+pub struct RasterCanvas<'a> {
+    pixels: RefCell<image_crate::RgbImage>,
+    px_per_mm: f32,
+    height_mm: f32,
+    fonts: &'a RasterFonts<'a>,
+    fill: Cell<[u8; 3]>,
+    outline: Cell<[u8; 3]>,
+    thickness: Cell<f32>,
+}
+
+impl<'a> RasterCanvas<'a> {
+    pub fn new(width_mm: f32, height_mm: f32, dpi: f32, fonts: &'a RasterFonts<'a>) -> Self {
+        let px_per_mm = dpi / 25.4;
+        let width = (width_mm * px_per_mm).round().max(1.0) as u32;
+        let height = (height_mm * px_per_mm).round().max(1.0) as u32;
+        RasterCanvas {
+            pixels: RefCell::new(image_crate::RgbImage::from_pixel(
+                width,
+                height,
+                image_crate::Rgb([255, 255, 255]),
+            )),
+            px_per_mm,
+            height_mm,
+            fonts,
+            fill: Cell::new([0, 0, 0]),
+            outline: Cell::new([0, 0, 0]),
+            thickness: Cell::new(1.0),
+        }
+    }
+
+    pub fn into_image(self) -> image_crate::RgbImage {
+        self.pixels.into_inner()
+    }
+
+    /// Convert page mm coordinates to pixel coordinates (origin top-left)
+    fn to_px(&self, x: f32, y: f32) -> (f32, f32) {
+        (x * self.px_per_mm, (self.height_mm - y) * self.px_per_mm)
+    }
+
+    /// Blend `color` into the pixel at (x, y) with the given coverage
+    fn blend(pixels: &mut image_crate::RgbImage, x: i64, y: i64, color: [u8; 3], coverage: f32) {
+        if x < 0 || y < 0 || x >= pixels.width() as i64 || y >= pixels.height() as i64 {
+            return;
+        }
+        let coverage = coverage.clamp(0.0, 1.0);
+        let pixel = pixels.get_pixel_mut(x as u32, y as u32);
+        for (channel, target) in pixel.0.iter_mut().zip(color) {
+            *channel = (*channel as f32 * (1.0 - coverage) + target as f32 * coverage).round() as u8;
+        }
+    }
+
+    /// Stroke a polyline given in pixel coordinates with the current outline state
+    fn stroke_px(&self, points: &[(f32, f32)]) {
+        let color = self.outline.get();
+        // Outline thickness is in points; keep hairlines visible at low resolution
+        let half = (self.thickness.get() * 25.4 / 72.0 * self.px_per_mm / 2.0).max(0.5);
+        let mut pixels = self.pixels.borrow_mut();
+
+        for segment in points.windows(2) {
+            let ((x1, y1), (x2, y2)) = (segment[0], segment[1]);
+            let min_x = (x1.min(x2) - half).floor() as i64;
+            let max_x = (x1.max(x2) + half).ceil() as i64;
+            let min_y = (y1.min(y2) - half).floor() as i64;
+            let max_y = (y1.max(y2) + half).ceil() as i64;
+            let (dx, dy) = (x2 - x1, y2 - y1);
+            let len_sq = (dx * dx + dy * dy).max(f32::EPSILON);
+
+            for py in min_y..=max_y {
+                for px in min_x..=max_x {
+                    // Distance from the pixel center to the segment
+                    let (cx, cy) = (px as f32 + 0.5, py as f32 + 0.5);
+                    let t = (((cx - x1) * dx + (cy - y1) * dy) / len_sq).clamp(0.0, 1.0);
+                    let (nx, ny) = (x1 + t * dx - cx, y1 + t * dy - cy);
+                    let dist = (nx * nx + ny * ny).sqrt();
+                    let coverage = half + 0.5 - dist;
+                    if coverage > 0.0 {
+                        Self::blend(&mut pixels, px, py, color, coverage);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl PageCanvas for RasterCanvas<'_> {
+    fn set_fill_color(&self, hex: &str) {
+        self.fill.set(rgb_bytes(hex));
+    }
+
+    fn set_outline_color(&self, hex: &str) {
+        self.outline.set(rgb_bytes(hex));
+    }
+
+    fn set_outline_thickness(&self, thickness: f32) {
+        self.thickness.set(thickness);
+    }
+
+    fn text(&self, text: &str, font_size: f32, x: f32, y: f32, font: &LoadedFont) {
+        let face = self.fonts.get(font.variant);
+        let units_per_em = face.units_per_em().unwrap_or(1000.0);
+        // PxScale is the ascent-to-descent height; size the em box to font_size
+        let em_px = font_size * 25.4 / 72.0 * self.px_per_mm;
+        let scale = PxScale::from(em_px * face.height_unscaled() / units_per_em);
+        let scaled = face.as_scaled(scale);
+
+        let color = self.fill.get();
+        let (mut pen_x, baseline) = self.to_px(x, y);
+        let mut pixels = self.pixels.borrow_mut();
+
+        for c in text.chars() {
+            let glyph_id = face.glyph_id(c);
+            let glyph = glyph_id.with_scale_and_position(scale, point(pen_x, baseline));
+            if let Some(outlined) = face.outline_glyph(glyph) {
+                let bounds = outlined.px_bounds();
+                outlined.draw(|gx, gy, coverage| {
+                    Self::blend(
+                        &mut pixels,
+                        bounds.min.x as i64 + gx as i64,
+                        bounds.min.y as i64 + gy as i64,
+                        color,
+                        coverage,
+                    );
+                });
+            }
+            pen_x += scaled.h_advance(glyph_id);
+        }
+    }
+
+    fn line(&self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        self.stroke_px(&[self.to_px(x1, y1), self.to_px(x2, y2)]);
+    }
+
+    fn rounded_rect(&self, x: f32, y: f32, w: f32, h: f32, radius: f32) {
+        // Walk the outline counter-clockwise from the bottom-left, sampling
+        // each quarter-circle corner as a short polyline
+        const ARC_STEPS: usize = 8;
+        let corners = [
+            (x + radius, y + radius, 180.0f32),
+            (x + w - radius, y + radius, 270.0),
+            (x + w - radius, y + h - radius, 0.0),
+            (x + radius, y + h - radius, 90.0),
+        ];
+        let mut points = Vec::with_capacity(corners.len() * (ARC_STEPS + 1) + 1);
+        for (cx, cy, start) in corners {
+            for step in 0..=ARC_STEPS {
+                let angle = (start + 90.0 * step as f32 / ARC_STEPS as f32).to_radians();
+                points.push(self.to_px(cx + radius * angle.cos(), cy + radius * angle.sin()));
+            }
+        }
+        points.push(points[0]);
+        self.stroke_px(&points);
+    }
+
+    fn image(&self, img: &PreparedImage, x: f32, y: f32, dpi: f32) {
+        let decoded = match image_crate::load_from_memory_with_format(&img.jpeg, image_crate::ImageFormat::Jpeg) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                log::warn!("Failed to decode prepared image for preview: {}", e);
+                return;
+            }
+        };
+        let w_mm = img.width as f32 * 25.4 / dpi;
+        let h_mm = img.height as f32 * 25.4 / dpi;
+        let (left, top) = self.to_px(x, y + h_mm);
+        let w_px = (w_mm * self.px_per_mm).round().max(1.0) as u32;
+        let h_px = (h_mm * self.px_per_mm).round().max(1.0) as u32;
+
+        let resized = image_crate::imageops::resize(
+            &decoded.to_rgb8(),
+            w_px,
+            h_px,
+            image_crate::imageops::FilterType::Triangle,
+        );
+        image_crate::imageops::overlay(
+            &mut *self.pixels.borrow_mut(),
+            &resized,
+            left.round() as i64,
+            top.round() as i64,
+        );
+    }
+
+    fn link(&self, _x: f32, _y: f32, _w: f32, _h: f32, _url: &str) {}
+}
+
+fn rgb_bytes(hex: &str) -> [u8; 3] {
+    let (r, g, b) = parse_hex_color(hex);
+    [(r * 255.0).round() as u8, (g * 255.0).round() as u8, (b * 255.0).round() as u8]
+}
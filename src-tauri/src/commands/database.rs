@@ -0,0 +1,48 @@
+use crate::db::Database;
+use crate::models::{DatabaseIntegrityReport, ForeignKeyViolation};
+use tauri::State;
+
+/// Run SQLite's integrity and foreign key checks over the whole database
+#[tauri::command]
+pub fn check_database_integrity(db: State<'_, Database>) -> Result<DatabaseIntegrityReport, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let foreign_keys_enabled: bool = conn
+        .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    // A healthy database reports a single "ok" row
+    let mut stmt = conn
+        .prepare("PRAGMA integrity_check")
+        .map_err(|e| e.to_string())?;
+    let integrity_errors: Vec<String> = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|message| message != "ok")
+        .collect();
+
+    let mut stmt = conn
+        .prepare("PRAGMA foreign_key_check")
+        .map_err(|e| e.to_string())?;
+    let foreign_key_violations = stmt
+        .query_map([], |row| {
+            Ok(ForeignKeyViolation {
+                table: row.get(0)?,
+                row_id: row.get(1)?,
+                parent_table: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(DatabaseIntegrityReport {
+        ok: foreign_keys_enabled && integrity_errors.is_empty() && foreign_key_violations.is_empty(),
+        foreign_keys_enabled,
+        integrity_errors,
+        foreign_key_violations,
+    })
+}
@@ -1,5 +1,5 @@
 use crate::db::Database;
-use crate::models::{Family, FamilyInput, FamilyUpdate, FamilyWithMembers, Member};
+use crate::models::{Family, FamilyInput, FamilyUpdate, FamilyWithMembers, Member, PartialDate};
 use rusqlite::params;
 use tauri::State;
 
@@ -11,7 +11,7 @@ pub fn get_families(db: State<'_, Database>) -> Result<Vec<Family>, String> {
         .prepare(
             "SELECT id, family_id, name, mailing_name, address, city, state, zip, phone,
                     photo_path, notes, children, alt_address, alt_city, alt_state, alt_zip,
-                    directory_adults, directory_children, include_photo_in_directory, created_at, updated_at
+                    directory_adults, directory_children, include_photo_in_directory, photo_crop, created_at, updated_at
              FROM families ORDER BY name",
         )
         .map_err(|e| e.to_string())?;
@@ -38,8 +38,9 @@ pub fn get_families(db: State<'_, Database>) -> Result<Vec<Family>, String> {
                 directory_adults: row.get(16)?,
                 directory_children: row.get(17)?,
                 include_photo_in_directory: row.get(18)?,
-                created_at: row.get(19)?,
-                updated_at: row.get(20)?,
+                photo_crop: row.get(19)?,
+                created_at: row.get(20)?,
+                updated_at: row.get(21)?,
             })
         })
         .map_err(|e| e.to_string())?
@@ -57,7 +58,7 @@ pub fn get_family(db: State<'_, Database>, id: i64) -> Result<FamilyWithMembers,
         .query_row(
             "SELECT id, family_id, name, mailing_name, address, city, state, zip, phone,
                     photo_path, notes, children, alt_address, alt_city, alt_state, alt_zip,
-                    directory_adults, directory_children, include_photo_in_directory, created_at, updated_at
+                    directory_adults, directory_children, include_photo_in_directory, photo_crop, created_at, updated_at
              FROM families WHERE id = ?",
             params![id],
             |row| {
@@ -81,8 +82,9 @@ pub fn get_family(db: State<'_, Database>, id: i64) -> Result<FamilyWithMembers,
                     directory_adults: row.get(16)?,
                     directory_children: row.get(17)?,
                     include_photo_in_directory: row.get(18)?,
-                    created_at: row.get(19)?,
-                    updated_at: row.get(20)?,
+                    photo_crop: row.get(19)?,
+                    created_at: row.get(20)?,
+                    updated_at: row.get(21)?,
                 })
             },
         )
@@ -90,8 +92,9 @@ pub fn get_family(db: State<'_, Database>, id: i64) -> Result<FamilyWithMembers,
 
     let mut stmt = conn
         .prepare(
-            "SELECT id, family_id, first_name, last_name, role, birth_date, wedding_date, phone, email,
-                    photo_path, notes, sort_order, created_at, updated_at
+            "SELECT id, family_id, first_name, last_name, role, phone, email,
+                    photo_path, notes, sort_order, created_at, updated_at,
+                    birth_month, birth_day, birth_year, wedding_month, wedding_day, wedding_year
              FROM members WHERE family_id = ? ORDER BY sort_order, last_name, first_name",
         )
         .map_err(|e| e.to_string())?;
@@ -104,15 +107,15 @@ pub fn get_family(db: State<'_, Database>, id: i64) -> Result<FamilyWithMembers,
                 first_name: row.get(2)?,
                 last_name: row.get(3)?,
                 role: row.get(4)?,
-                birth_date: row.get(5)?,
-                wedding_date: row.get(6)?,
-                phone: row.get(7)?,
-                email: row.get(8)?,
-                photo_path: row.get(9)?,
-                notes: row.get(10)?,
-                sort_order: row.get(11)?,
-                created_at: row.get(12)?,
-                updated_at: row.get(13)?,
+                birth_date: PartialDate::from_row(row, 12)?,
+                wedding_date: PartialDate::from_row(row, 15)?,
+                phone: row.get(5)?,
+                email: row.get(6)?,
+                photo_path: row.get(7)?,
+                notes: row.get(8)?,
+                sort_order: row.get(9)?,
+                created_at: row.get(10)?,
+                updated_at: row.get(11)?,
             })
         })
         .map_err(|e| e.to_string())?
@@ -198,6 +201,13 @@ pub fn update_family(db: State<'_, Database>, id: i64, family: FamilyUpdate) ->
         updates.push("photo_path = ?");
         values.push(Box::new(v.clone()));
     }
+    if let Some(v) = family.photo_crop {
+        updates.push("photo_crop = ?");
+        values.push(Box::new(v));
+    } else if family.photo_path.is_some() {
+        // Framing belongs to the old photo
+        updates.push("photo_crop = NULL");
+    }
 
     if let Some(ref v) = family.notes {
         updates.push("notes = ?");
@@ -258,9 +268,7 @@ pub fn update_family(db: State<'_, Database>, id: i64, family: FamilyUpdate) ->
 pub fn delete_family(db: State<'_, Database>, id: i64) -> Result<(), String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
-    conn.execute("DELETE FROM members WHERE family_id = ?", params![id])
-        .map_err(|e| e.to_string())?;
-
+    // Members go with the family through ON DELETE CASCADE
     conn.execute("DELETE FROM families WHERE id = ?", params![id])
         .map_err(|e| e.to_string())?;
 
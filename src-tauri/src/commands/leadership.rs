@@ -1,6 +1,6 @@
 use crate::db::Database;
-use crate::models::{Leadership, LeadershipInput};
-use rusqlite::params;
+use crate::models::{Leadership, LeadershipInput, LeadershipPerson};
+use rusqlite::{params, Connection};
 use tauri::State;
 
 #[tauri::command]
@@ -8,28 +8,16 @@ pub fn get_leadership(db: State<'_, Database>) -> Result<Vec<Leadership>, String
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
     let mut stmt = conn
-        .prepare(
-            "SELECT id, ministry, names, sort_order, created_at, updated_at
-             FROM leadership ORDER BY ministry COLLATE NOCASE",
-        )
+        .prepare("SELECT id FROM leadership ORDER BY sort_order, ministry COLLATE NOCASE")
         .map_err(|e| e.to_string())?;
 
-    let entries = stmt
-        .query_map([], |row| {
-            Ok(Leadership {
-                id: row.get(0)?,
-                ministry: row.get(1)?,
-                names: row.get(2)?,
-                sort_order: row.get(3)?,
-                created_at: row.get(4)?,
-                updated_at: row.get(5)?,
-            })
-        })
+    let ids = stmt
+        .query_map([], |row| row.get::<_, i64>(0))
         .map_err(|e| e.to_string())?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
 
-    Ok(entries)
+    ids.into_iter().map(|id| load_leadership(&conn, id)).collect()
 }
 
 #[tauri::command]
@@ -38,34 +26,19 @@ pub fn create_leadership(
     input: LeadershipInput,
 ) -> Result<Leadership, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
 
-    conn.execute(
-        "INSERT INTO leadership (ministry, names, sort_order) VALUES (?, ?, ?)",
-        params![input.ministry, input.names, input.sort_order],
+    tx.execute(
+        "INSERT INTO leadership (ministry, contact, sort_order) VALUES (?, ?, ?)",
+        params![input.ministry, input.contact, input.sort_order],
     )
     .map_err(|e| e.to_string())?;
 
-    let id = conn.last_insert_rowid();
-
-    let entry = conn
-        .query_row(
-            "SELECT id, ministry, names, sort_order, created_at, updated_at
-             FROM leadership WHERE id = ?",
-            params![id],
-            |row| {
-                Ok(Leadership {
-                    id: row.get(0)?,
-                    ministry: row.get(1)?,
-                    names: row.get(2)?,
-                    sort_order: row.get(3)?,
-                    created_at: row.get(4)?,
-                    updated_at: row.get(5)?,
-                })
-            },
-        )
-        .map_err(|e| e.to_string())?;
+    let id = tx.last_insert_rowid();
+    save_people(&tx, id, &input.people)?;
+    tx.commit().map_err(|e| e.to_string())?;
 
-    Ok(entry)
+    load_leadership(&conn, id)
 }
 
 #[tauri::command]
@@ -75,23 +48,58 @@ pub fn update_leadership(
     input: LeadershipInput,
 ) -> Result<Leadership, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
 
-    conn.execute(
-        "UPDATE leadership SET ministry = ?, names = ?, sort_order = ?, updated_at = datetime('now') WHERE id = ?",
-        params![input.ministry, input.names, input.sort_order, id],
+    tx.execute(
+        "UPDATE leadership SET ministry = ?, contact = ?, sort_order = ?, updated_at = datetime('now') WHERE id = ?",
+        params![input.ministry, input.contact, input.sort_order, id],
     )
     .map_err(|e| e.to_string())?;
 
-    let entry = conn
+    save_people(&tx, id, &input.people)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    load_leadership(&conn, id)
+}
+
+#[tauri::command]
+pub fn delete_leadership(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    conn.execute("DELETE FROM leadership WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn reorder_leadership(db: State<'_, Database>, leadership_ids: Vec<i64>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    for (index, id) in leadership_ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE leadership SET sort_order = ?, updated_at = datetime('now') WHERE id = ?",
+            params![index as i32, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())
+}
+
+fn load_leadership(conn: &Connection, id: i64) -> Result<Leadership, String> {
+    let mut entry = conn
         .query_row(
-            "SELECT id, ministry, names, sort_order, created_at, updated_at
+            "SELECT id, ministry, contact, sort_order, created_at, updated_at
              FROM leadership WHERE id = ?",
             params![id],
             |row| {
                 Ok(Leadership {
                     id: row.get(0)?,
                     ministry: row.get(1)?,
-                    names: row.get(2)?,
+                    people: Vec::new(),
+                    contact: row.get(2)?,
                     sort_order: row.get(3)?,
                     created_at: row.get(4)?,
                     updated_at: row.get(5)?,
@@ -100,15 +108,53 @@ pub fn update_leadership(
         )
         .map_err(|e| e.to_string())?;
 
+    entry.people = leadership_people(conn, id)?;
     Ok(entry)
 }
 
-#[tauri::command]
-pub fn delete_leadership(db: State<'_, Database>, id: i64) -> Result<(), String> {
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+/// People serving in a ministry in order, with linked members under their
+/// current names
+pub fn leadership_people(conn: &Connection, leadership_id: i64) -> Result<Vec<LeadershipPerson>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.member_id, COALESCE(m.first_name || ' ' || m.last_name, p.name)
+             FROM leadership_people p
+             LEFT JOIN members m ON m.id = p.member_id
+             WHERE p.leadership_id = ?
+             ORDER BY p.sort_order, p.id",
+        )
+        .map_err(|e| e.to_string())?;
 
-    conn.execute("DELETE FROM leadership WHERE id = ?", params![id])
+    let people = stmt
+        .query_map(params![leadership_id], |row| {
+            Ok(LeadershipPerson {
+                member_id: row.get(0)?,
+                name: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(people)
+}
+
+/// Replace a ministry's people with `people`, in the given order
+fn save_people(conn: &Connection, leadership_id: i64, people: &[LeadershipPerson]) -> Result<(), String> {
+    conn.execute("DELETE FROM leadership_people WHERE leadership_id = ?", params![leadership_id])
+        .map_err(|e| e.to_string())?;
+
+    for (index, person) in people.iter().enumerate() {
+        let name = person.name.trim();
+        if name.is_empty() && person.member_id.is_none() {
+            continue;
+        }
+        conn.execute(
+            "INSERT INTO leadership_people (leadership_id, member_id, name, sort_order) VALUES (?, ?, ?, ?)",
+            params![leadership_id, person.member_id, name, index as i32],
+        )
         .map_err(|e| e.to_string())?;
+    }
 
     Ok(())
 }
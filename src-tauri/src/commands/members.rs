@@ -1,5 +1,5 @@
 use crate::db::Database;
-use crate::models::{Member, MemberInput, MemberUpdate};
+use crate::models::{Member, MemberInput, MemberUpdate, PartialDate};
 use rusqlite::params;
 use tauri::State;
 
@@ -9,8 +9,9 @@ pub fn get_members(db: State<'_, Database>, family_id: i64) -> Result<Vec<Member
 
     let mut stmt = conn
         .prepare(
-            "SELECT id, family_id, first_name, last_name, role, birth_date, wedding_date, phone, email,
-                    photo_path, notes, sort_order, created_at, updated_at
+            "SELECT id, family_id, first_name, last_name, role, phone, email,
+                    photo_path, notes, sort_order, created_at, updated_at,
+                    birth_month, birth_day, birth_year, wedding_month, wedding_day, wedding_year
              FROM members WHERE family_id = ? ORDER BY sort_order, last_name, first_name",
         )
         .map_err(|e| e.to_string())?;
@@ -23,15 +24,55 @@ pub fn get_members(db: State<'_, Database>, family_id: i64) -> Result<Vec<Member
                 first_name: row.get(2)?,
                 last_name: row.get(3)?,
                 role: row.get(4)?,
-                birth_date: row.get(5)?,
-                wedding_date: row.get(6)?,
-                phone: row.get(7)?,
-                email: row.get(8)?,
-                photo_path: row.get(9)?,
-                notes: row.get(10)?,
-                sort_order: row.get(11)?,
-                created_at: row.get(12)?,
-                updated_at: row.get(13)?,
+                birth_date: PartialDate::from_row(row, 12)?,
+                wedding_date: PartialDate::from_row(row, 15)?,
+                phone: row.get(5)?,
+                email: row.get(6)?,
+                photo_path: row.get(7)?,
+                notes: row.get(8)?,
+                sort_order: row.get(9)?,
+                created_at: row.get(10)?,
+                updated_at: row.get(11)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(members)
+}
+
+/// Every member in the directory, alphabetically, for picking people by name
+#[tauri::command]
+pub fn get_all_members(db: State<'_, Database>) -> Result<Vec<Member>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, family_id, first_name, last_name, role, phone, email,
+                    photo_path, notes, sort_order, created_at, updated_at,
+                    birth_month, birth_day, birth_year, wedding_month, wedding_day, wedding_year
+             FROM members ORDER BY last_name COLLATE NOCASE, first_name COLLATE NOCASE",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let members = stmt
+        .query_map([], |row| {
+            Ok(Member {
+                id: row.get(0)?,
+                family_id: row.get(1)?,
+                first_name: row.get(2)?,
+                last_name: row.get(3)?,
+                role: row.get(4)?,
+                birth_date: PartialDate::from_row(row, 12)?,
+                wedding_date: PartialDate::from_row(row, 15)?,
+                phone: row.get(5)?,
+                email: row.get(6)?,
+                photo_path: row.get(7)?,
+                notes: row.get(8)?,
+                sort_order: row.get(9)?,
+                created_at: row.get(10)?,
+                updated_at: row.get(11)?,
             })
         })
         .map_err(|e| e.to_string())?
@@ -46,8 +87,9 @@ pub fn get_member(db: State<'_, Database>, id: i64) -> Result<Member, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
     conn.query_row(
-        "SELECT id, family_id, first_name, last_name, role, birth_date, wedding_date, phone, email,
-                photo_path, notes, sort_order, created_at, updated_at
+        "SELECT id, family_id, first_name, last_name, role, phone, email,
+                photo_path, notes, sort_order, created_at, updated_at,
+                birth_month, birth_day, birth_year, wedding_month, wedding_day, wedding_year
          FROM members WHERE id = ?",
         params![id],
         |row| {
@@ -57,15 +99,15 @@ pub fn get_member(db: State<'_, Database>, id: i64) -> Result<Member, String> {
                 first_name: row.get(2)?,
                 last_name: row.get(3)?,
                 role: row.get(4)?,
-                birth_date: row.get(5)?,
-                wedding_date: row.get(6)?,
-                phone: row.get(7)?,
-                email: row.get(8)?,
-                photo_path: row.get(9)?,
-                notes: row.get(10)?,
-                sort_order: row.get(11)?,
-                created_at: row.get(12)?,
-                updated_at: row.get(13)?,
+                birth_date: PartialDate::from_row(row, 12)?,
+                wedding_date: PartialDate::from_row(row, 15)?,
+                phone: row.get(5)?,
+                email: row.get(6)?,
+                photo_path: row.get(7)?,
+                notes: row.get(8)?,
+                sort_order: row.get(9)?,
+                created_at: row.get(10)?,
+                updated_at: row.get(11)?,
             })
         },
     )
@@ -74,18 +116,25 @@ pub fn get_member(db: State<'_, Database>, id: i64) -> Result<Member, String> {
 
 #[tauri::command]
 pub fn create_member(db: State<'_, Database>, member: MemberInput) -> Result<i64, String> {
+    let birth = parse_member_date("Birth date", member.birth_date.as_deref())?;
+    let wedding = parse_member_date("Wedding date", member.wedding_date.as_deref())?;
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
     conn.execute(
-        "INSERT INTO members (family_id, first_name, last_name, role, birth_date, wedding_date, phone, email, photo_path, notes, sort_order)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        "INSERT INTO members (family_id, first_name, last_name, role, birth_month, birth_day, birth_year,
+                              wedding_month, wedding_day, wedding_year, phone, email, photo_path, notes, sort_order)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         params![
             member.family_id,
             member.first_name,
             member.last_name,
             member.role,
-            member.birth_date,
-            member.wedding_date,
+            birth.map(|d| d.month),
+            birth.map(|d| d.day),
+            birth.and_then(|d| d.year),
+            wedding.map(|d| d.month),
+            wedding.map(|d| d.day),
+            wedding.and_then(|d| d.year),
             member.phone,
             member.email,
             member.photo_path,
@@ -98,6 +147,16 @@ pub fn create_member(db: State<'_, Database>, member: MemberInput) -> Result<i64
     Ok(conn.last_insert_rowid())
 }
 
+/// A birth or wedding date as typed in the member form. Blank means no date.
+fn parse_member_date(label: &str, value: Option<&str>) -> Result<Option<PartialDate>, String> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => PartialDate::parse(v)
+            .map(Some)
+            .ok_or_else(|| format!("{} \"{}\" isn't a valid date. Use MM-DD or YYYY-MM-DD.", label, v)),
+        None => Ok(None),
+    }
+}
+
 #[tauri::command]
 pub fn update_member(db: State<'_, Database>, id: i64, member: MemberUpdate) -> Result<(), String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
@@ -118,12 +177,18 @@ pub fn update_member(db: State<'_, Database>, id: i64, member: MemberUpdate) ->
         values.push(Box::new(v.clone()));
     }
     if let Some(ref v) = member.birth_date {
-        updates.push("birth_date = ?");
-        values.push(Box::new(v.clone()));
+        let date = parse_member_date("Birth date", Some(v))?;
+        updates.push("birth_month = ?, birth_day = ?, birth_year = ?");
+        values.push(Box::new(date.map(|d| d.month)));
+        values.push(Box::new(date.map(|d| d.day)));
+        values.push(Box::new(date.and_then(|d| d.year)));
     }
     if let Some(ref v) = member.wedding_date {
-        updates.push("wedding_date = ?");
-        values.push(Box::new(v.clone()));
+        let date = parse_member_date("Wedding date", Some(v))?;
+        updates.push("wedding_month = ?, wedding_day = ?, wedding_year = ?");
+        values.push(Box::new(date.map(|d| d.month)));
+        values.push(Box::new(date.map(|d| d.day)));
+        values.push(Box::new(date.and_then(|d| d.year)));
     }
     if let Some(ref v) = member.phone {
         updates.push("phone = ?");
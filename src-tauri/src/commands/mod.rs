@@ -1,9 +1,11 @@
+pub mod database;
 pub mod families;
 pub mod leadership;
 pub mod members;
 pub mod settings;
 pub mod staff;
 
+pub use database::*;
 pub use families::*;
 pub use leadership::*;
 pub use members::*;
@@ -1,4 +1,5 @@
 use crate::db::Database;
+use crate::locale::Locale;
 use crate::models::Settings;
 use rusqlite::params;
 use tauri::State;
@@ -44,6 +45,10 @@ pub fn get_settings(db: State<'_, Database>) -> Result<Option<Settings>, String>
             "church_phone" => settings.church_phone = Some(value).filter(|s| !s.is_empty()),
             "church_email" => settings.church_email = Some(value).filter(|s| !s.is_empty()),
             "church_website" => settings.church_website = Some(value).filter(|s| !s.is_empty()),
+            "pdf_font_regular" => settings.pdf_font_regular = Some(value).filter(|s| !s.is_empty()),
+            "pdf_font_bold" => settings.pdf_font_bold = Some(value).filter(|s| !s.is_empty()),
+            "pdf_font_italic" => settings.pdf_font_italic = Some(value).filter(|s| !s.is_empty()),
+            "locale" => settings.locale = value,
             _ => {}
         }
     }
@@ -57,6 +62,9 @@ pub fn get_settings(db: State<'_, Database>) -> Result<Option<Settings>, String>
 
 #[tauri::command]
 pub fn save_settings(db: State<'_, Database>, settings: Settings) -> Result<(), String> {
+    if Locale::from_code(&settings.locale).is_none() {
+        return Err(format!("Unknown directory language: {}", settings.locale));
+    }
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
     let pairs = vec![
@@ -125,6 +133,19 @@ pub fn save_settings(db: State<'_, Database>, settings: Settings) -> Result<(),
             "church_website",
             settings.church_website.unwrap_or_default(),
         ),
+        (
+            "pdf_font_regular",
+            settings.pdf_font_regular.unwrap_or_default(),
+        ),
+        (
+            "pdf_font_bold",
+            settings.pdf_font_bold.unwrap_or_default(),
+        ),
+        (
+            "pdf_font_italic",
+            settings.pdf_font_italic.unwrap_or_default(),
+        ),
+        ("locale", settings.locale),
     ];
 
     for (key, value) in pairs {
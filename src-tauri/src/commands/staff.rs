@@ -1,6 +1,6 @@
 use crate::db::Database;
-use crate::models::{Staff, StaffInput};
-use rusqlite::params;
+use crate::models::{Staff, StaffCategory, StaffCategoryInput, StaffInput};
+use rusqlite::{params, Connection};
 use tauri::State;
 
 #[tauri::command]
@@ -8,25 +8,11 @@ pub fn get_staff(db: State<'_, Database>) -> Result<Vec<Staff>, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
     let mut stmt = conn
-        .prepare(
-            "SELECT id, name, title, role, photo_path, sort_order, created_at, updated_at
-             FROM staff ORDER BY sort_order, name",
-        )
+        .prepare(&format!("SELECT {} FROM staff ORDER BY sort_order, name", STAFF_COLUMNS))
         .map_err(|e| e.to_string())?;
 
     let entries = stmt
-        .query_map([], |row| {
-            Ok(Staff {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                title: row.get(2)?,
-                role: row.get(3)?,
-                photo_path: row.get(4)?,
-                sort_order: row.get(5)?,
-                created_at: row.get(6)?,
-                updated_at: row.get(7)?,
-            })
-        })
+        .query_map([], staff_from_row)
         .map_err(|e| e.to_string())?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
@@ -40,10 +26,21 @@ pub fn create_staff(
     input: StaffInput,
 ) -> Result<Staff, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    check_category_exists(&conn, &input.role)?;
 
     conn.execute(
-        "INSERT INTO staff (name, title, role, sort_order) VALUES (?, ?, ?, ?)",
-        params![input.name, input.title, input.role, input.sort_order],
+        "INSERT INTO staff (name, title, role, email, phone, office_hours, bio, sort_order)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            input.name,
+            input.title,
+            input.role,
+            input.email,
+            input.phone,
+            input.office_hours,
+            input.bio,
+            input.sort_order
+        ],
     )
     .map_err(|e| e.to_string())?;
 
@@ -58,10 +55,22 @@ pub fn update_staff(
     input: StaffInput,
 ) -> Result<Staff, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    check_category_exists(&conn, &input.role)?;
 
     conn.execute(
-        "UPDATE staff SET name = ?, title = ?, role = ?, sort_order = ?, updated_at = datetime('now') WHERE id = ?",
-        params![input.name, input.title, input.role, input.sort_order, id],
+        "UPDATE staff SET name = ?, title = ?, role = ?, email = ?, phone = ?, office_hours = ?, bio = ?,
+         sort_order = ?, updated_at = datetime('now') WHERE id = ?",
+        params![
+            input.name,
+            input.title,
+            input.role,
+            input.email,
+            input.phone,
+            input.office_hours,
+            input.bio,
+            input.sort_order,
+            id
+        ],
     )
     .map_err(|e| e.to_string())?;
 
@@ -101,23 +110,213 @@ pub fn save_staff_photo(
     Ok(saved_path)
 }
 
-fn query_staff_by_id(conn: &rusqlite::Connection, id: i64) -> Result<Staff, String> {
+const STAFF_COLUMNS: &str =
+    "id, name, title, role, photo_path, email, phone, office_hours, bio, sort_order, created_at, updated_at";
+
+fn query_staff_by_id(conn: &Connection, id: i64) -> Result<Staff, String> {
     conn.query_row(
-        "SELECT id, name, title, role, photo_path, sort_order, created_at, updated_at
-         FROM staff WHERE id = ?",
+        &format!("SELECT {} FROM staff WHERE id = ?", STAFF_COLUMNS),
         params![id],
-        |row| {
-            Ok(Staff {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                title: row.get(2)?,
-                role: row.get(3)?,
-                photo_path: row.get(4)?,
-                sort_order: row.get(5)?,
-                created_at: row.get(6)?,
-                updated_at: row.get(7)?,
-            })
-        },
+        staff_from_row,
     )
     .map_err(|e| e.to_string())
 }
+
+fn staff_from_row(row: &rusqlite::Row) -> rusqlite::Result<Staff> {
+    Ok(Staff {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        title: row.get(2)?,
+        role: row.get(3)?,
+        photo_path: row.get(4)?,
+        email: row.get(5)?,
+        phone: row.get(6)?,
+        office_hours: row.get(7)?,
+        bio: row.get(8)?,
+        sort_order: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
+const STAFF_CATEGORY_LAYOUTS: &[&str] = &["featured", "photo_grid", "list"];
+
+#[tauri::command]
+pub fn get_staff_categories(db: State<'_, Database>) -> Result<Vec<StaffCategory>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    query_staff_categories(&conn)
+}
+
+#[tauri::command]
+pub fn create_staff_category(
+    db: State<'_, Database>,
+    input: StaffCategoryInput,
+) -> Result<StaffCategory, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let title = validate_category_input(&input)?;
+    let key = unique_category_key(&conn, title)?;
+
+    conn.execute(
+        "INSERT INTO staff_categories (key, title, sort_order, photo_scale, layout) VALUES (?, ?, ?, ?, ?)",
+        params![key, title, input.sort_order, input.photo_scale, input.layout],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let id = conn.last_insert_rowid();
+    query_staff_category_by_id(&conn, id)
+}
+
+/// The key is left alone so staff stay in the category when it is renamed
+#[tauri::command]
+pub fn update_staff_category(
+    db: State<'_, Database>,
+    id: i64,
+    input: StaffCategoryInput,
+) -> Result<StaffCategory, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let title = validate_category_input(&input)?;
+
+    conn.execute(
+        "UPDATE staff_categories SET title = ?, sort_order = ?, photo_scale = ?, layout = ?, updated_at = datetime('now') WHERE id = ?",
+        params![title, input.sort_order, input.photo_scale, input.layout, id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    query_staff_category_by_id(&conn, id)
+}
+
+#[tauri::command]
+pub fn delete_staff_category(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    let in_use: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM staff WHERE role = (SELECT key FROM staff_categories WHERE id = ?)",
+            params![id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if in_use > 0 {
+        return Err(format!(
+            "This category still has {} staff {}. Move or delete them first.",
+            in_use,
+            if in_use == 1 { "member" } else { "members" }
+        ));
+    }
+
+    conn.execute("DELETE FROM staff_categories WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn reorder_staff_categories(db: State<'_, Database>, category_ids: Vec<i64>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    for (index, id) in category_ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE staff_categories SET sort_order = ?, updated_at = datetime('now') WHERE id = ?",
+            params![index as i32, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Staff categories in page order
+pub fn query_staff_categories(conn: &Connection) -> Result<Vec<StaffCategory>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, key, title, sort_order, photo_scale, layout, created_at, updated_at
+             FROM staff_categories ORDER BY sort_order, title COLLATE NOCASE",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let categories = stmt
+        .query_map([], staff_category_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(categories)
+}
+
+fn query_staff_category_by_id(conn: &Connection, id: i64) -> Result<StaffCategory, String> {
+    conn.query_row(
+        "SELECT id, key, title, sort_order, photo_scale, layout, created_at, updated_at
+         FROM staff_categories WHERE id = ?",
+        params![id],
+        staff_category_from_row,
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn staff_category_from_row(row: &rusqlite::Row) -> rusqlite::Result<StaffCategory> {
+    Ok(StaffCategory {
+        id: row.get(0)?,
+        key: row.get(1)?,
+        title: row.get(2)?,
+        sort_order: row.get(3)?,
+        photo_scale: row.get(4)?,
+        layout: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+/// Check a category's fields, returning its trimmed title
+fn validate_category_input(input: &StaffCategoryInput) -> Result<&str, String> {
+    let title = input.title.trim();
+    if title.is_empty() {
+        return Err("A staff category needs a title".to_string());
+    }
+    if !STAFF_CATEGORY_LAYOUTS.contains(&input.layout.as_str()) {
+        return Err(format!("Unknown staff category layout '{}'", input.layout));
+    }
+    if !(0.5..=2.0).contains(&input.photo_scale) {
+        return Err("Photo size must be between 50% and 200%".to_string());
+    }
+    Ok(title)
+}
+
+/// A key for a new category derived from its title: "Youth Pastors" becomes
+/// "youth_pastors", with a number added if that's taken
+fn unique_category_key(conn: &Connection, title: &str) -> Result<String, String> {
+    let mut base = String::new();
+    for c in title.chars() {
+        if c.is_alphanumeric() {
+            base.extend(c.to_lowercase());
+        } else if !base.is_empty() && !base.ends_with('_') {
+            base.push('_');
+        }
+    }
+    let base = match base.trim_end_matches('_') {
+        "" => "category".to_string(),
+        trimmed => trimmed.to_string(),
+    };
+
+    let mut key = base.clone();
+    let mut suffix = 2;
+    while key_taken(conn, &key)? {
+        key = format!("{}_{}", base, suffix);
+        suffix += 1;
+    }
+    Ok(key)
+}
+
+fn key_taken(conn: &Connection, key: &str) -> Result<bool, String> {
+    conn.prepare("SELECT 1 FROM staff_categories WHERE key = ?")
+        .and_then(|mut stmt| stmt.exists(params![key]))
+        .map_err(|e| e.to_string())
+}
+
+fn check_category_exists(conn: &Connection, key: &str) -> Result<(), String> {
+    if key_taken(conn, key)? {
+        Ok(())
+    } else {
+        Err(format!("Unknown staff category '{}'", key))
+    }
+}
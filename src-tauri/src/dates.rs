@@ -0,0 +1,224 @@
+//! Reading the birth and wedding dates found in imported spreadsheets
+
+use crate::models::{DateOrder, ImportDateOptions, PartialDate};
+use chrono::{Datelike, NaiveDate};
+
+/// What an imported date cell turned out to hold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportedDate {
+    Exact(PartialDate),
+    /// Read in the configured order, though swapping day and month also
+    /// makes a valid date
+    Ambiguous { value: PartialDate, alternative: PartialDate },
+    Invalid,
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june",
+    "july", "august", "september", "october", "november", "december",
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Token {
+    Number { value: u32, digits: usize },
+    Month(u32),
+}
+
+/// Read a date cell. Handles Excel serial numbers, ISO dates, numeric dates
+/// in the configured order with or without a year ("3/5", "03/05/80"), and
+/// dates with month names ("March 5", "5-Mar-1980", "Mar 5th, 1980").
+pub fn parse_import_date(raw: &str, options: &ImportDateOptions) -> ImportedDate {
+    let raw = raw.trim();
+
+    // Excel serial date: days since 1899-12-30. A bare four-digit number is
+    // a year on its own, which isn't enough for a celebration date.
+    let is_year = raw.len() == 4 && raw.bytes().all(|b| b.is_ascii_digit());
+    if let Ok(serial) = raw.parse::<f64>() {
+        let days = serial as i64;
+        if is_year || days <= 0 || days >= 100000 {
+            return ImportedDate::Invalid;
+        }
+        return NaiveDate::from_ymd_opt(1899, 12, 30)
+            .and_then(|base| base.checked_add_signed(chrono::TimeDelta::days(days)))
+            .and_then(|date| PartialDate::new(date.month(), date.day(), Some(date.year())))
+            .map(ImportedDate::Exact)
+            .unwrap_or(ImportedDate::Invalid);
+    }
+
+    let Some(tokens) = tokenize(raw) else {
+        return ImportedDate::Invalid;
+    };
+    let months: Vec<u32> = tokens
+        .iter()
+        .filter_map(|t| match t {
+            Token::Month(month) => Some(*month),
+            Token::Number { .. } => None,
+        })
+        .collect();
+    let numbers: Vec<(u32, usize)> = tokens
+        .iter()
+        .filter_map(|t| match t {
+            Token::Number { value, digits } => Some((*value, *digits)),
+            Token::Month(_) => None,
+        })
+        .collect();
+    let pivot = options.century_pivot;
+
+    let exact = |date: Option<PartialDate>| date.map_or(ImportedDate::Invalid, ImportedDate::Exact);
+    match (months.as_slice(), numbers.as_slice()) {
+        // "March 5", "5 Mar"
+        ([month], [(day, _)]) => exact(PartialDate::new(*month, *day, None)),
+        // "5-Mar-1980", "March 5, 1980", "1980 March 5"
+        ([month], [first, second]) => {
+            let ((day, _), year) = if first.1 > 2 { (*second, *first) } else { (*first, *second) };
+            exact(full_year(year, pivot).and_then(|year| PartialDate::new(*month, day, Some(year))))
+        }
+        // "1980-03-05"
+        ([], [year, (month, _), (day, _)]) if year.1 > 2 => {
+            exact(full_year(*year, pivot).and_then(|year| PartialDate::new(*month, *day, Some(year))))
+        }
+        ([], [(a, a_digits), (b, _)]) if *a_digits <= 2 => ordered(None, *a, *b, options.order),
+        ([], [(a, _), (b, _), year]) => match full_year(*year, pivot) {
+            Some(year) => ordered(Some(year), *a, *b, options.order),
+            None => ImportedDate::Invalid,
+        },
+        _ => ImportedDate::Invalid,
+    }
+}
+
+/// Split a date into numbers and month names, dropping ordinal suffixes.
+/// None if any part is neither.
+fn tokenize(raw: &str) -> Option<Vec<Token>> {
+    raw.split(|c: char| matches!(c, '/' | '-' | '.' | ',') || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let lower = part.to_lowercase();
+            let is_number = |s: &&str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+            let number = ["st", "nd", "rd", "th"]
+                .iter()
+                .find_map(|suffix| lower.strip_suffix(suffix))
+                .filter(is_number)
+                .unwrap_or(&lower);
+            if is_number(&number) {
+                let value = number.parse().ok()?;
+                return Some(Token::Number { value, digits: number.len() });
+            }
+            month_from_name(&lower).map(Token::Month)
+        })
+        .collect()
+}
+
+/// Month number for a full or abbreviated English month name ("Sep", "Sept")
+fn month_from_name(name: &str) -> Option<u32> {
+    if name.len() < 3 {
+        return None;
+    }
+    MONTH_NAMES
+        .iter()
+        .position(|month| month.starts_with(name))
+        .map(|idx| idx as u32 + 1)
+}
+
+/// Four-digit years as written; two-digit years go to the century the pivot
+/// picks
+fn full_year((value, digits): (u32, usize), pivot: u32) -> Option<i32> {
+    match digits {
+        1 | 2 if value <= pivot => Some(2000 + value as i32),
+        1 | 2 => Some(1900 + value as i32),
+        4 if value >= 1000 => Some(value as i32),
+        _ => None,
+    }
+}
+
+/// An all-numeric day and month read in the configured order. When only the
+/// swapped reading is a real date, that one is used.
+fn ordered(year: Option<i32>, a: u32, b: u32, order: DateOrder) -> ImportedDate {
+    let (month, day) = match order {
+        DateOrder::MonthFirst => (a, b),
+        DateOrder::DayFirst => (b, a),
+    };
+    let value = PartialDate::new(month, day, year);
+    let swapped = if a != b { PartialDate::new(day, month, year) } else { None };
+    match (value, swapped) {
+        (Some(value), Some(alternative)) => ImportedDate::Ambiguous { value, alternative },
+        (Some(value), None) | (None, Some(value)) => ImportedDate::Exact(value),
+        (None, None) => ImportedDate::Invalid,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(order: DateOrder) -> ImportDateOptions {
+        ImportDateOptions { order, century_pivot: 50 }
+    }
+
+    fn date(month: u32, day: u32, year: Option<i32>) -> PartialDate {
+        PartialDate::new(month, day, year).unwrap()
+    }
+
+    fn month_first(raw: &str) -> ImportedDate {
+        parse_import_date(raw, &options(DateOrder::MonthFirst))
+    }
+
+    #[test]
+    fn reads_numeric_dates_in_the_configured_order() {
+        assert_eq!(
+            parse_import_date("03/05/1980", &options(DateOrder::MonthFirst)),
+            ImportedDate::Ambiguous { value: date(3, 5, Some(1980)), alternative: date(5, 3, Some(1980)) }
+        );
+        assert_eq!(
+            parse_import_date("03/05/1980", &options(DateOrder::DayFirst)),
+            ImportedDate::Ambiguous { value: date(5, 3, Some(1980)), alternative: date(3, 5, Some(1980)) }
+        );
+        // Same day and month reads the same either way
+        assert_eq!(month_first("4/4"), ImportedDate::Exact(date(4, 4, None)));
+    }
+
+    #[test]
+    fn swaps_day_and_month_when_only_that_is_a_date() {
+        assert_eq!(month_first("25/12/1990"), ImportedDate::Exact(date(12, 25, Some(1990))));
+        assert_eq!(
+            parse_import_date("12/25", &options(DateOrder::DayFirst)),
+            ImportedDate::Exact(date(12, 25, None))
+        );
+        assert_eq!(month_first("13/13/1990"), ImportedDate::Invalid);
+    }
+
+    #[test]
+    fn two_digit_years_split_at_the_pivot() {
+        assert_eq!(month_first("1/15/50"), ImportedDate::Exact(date(1, 15, Some(2050))));
+        assert_eq!(month_first("1/15/51"), ImportedDate::Exact(date(1, 15, Some(1951))));
+        assert_eq!(month_first("1/15/05"), ImportedDate::Exact(date(1, 15, Some(2005))));
+        let late_pivot = ImportDateOptions { order: DateOrder::MonthFirst, century_pivot: 10 };
+        assert_eq!(parse_import_date("1/15/25", &late_pivot), ImportedDate::Exact(date(1, 15, Some(1925))));
+    }
+
+    #[test]
+    fn reads_month_names() {
+        assert_eq!(month_first("March 5"), ImportedDate::Exact(date(3, 5, None)));
+        assert_eq!(month_first("5 Mar"), ImportedDate::Exact(date(3, 5, None)));
+        assert_eq!(month_first("5-Mar-1980"), ImportedDate::Exact(date(3, 5, Some(1980))));
+        assert_eq!(month_first("Sept 21st, 1975"), ImportedDate::Exact(date(9, 21, Some(1975))));
+        assert_eq!(month_first("1980 March 5"), ImportedDate::Exact(date(3, 5, Some(1980))));
+        assert_eq!(month_first("Ma 5"), ImportedDate::Invalid);
+        assert_eq!(month_first("sometime in May"), ImportedDate::Invalid);
+    }
+
+    #[test]
+    fn reads_iso_dates_and_excel_serials() {
+        assert_eq!(month_first("1980-03-05"), ImportedDate::Exact(date(3, 5, Some(1980))));
+        assert_eq!(month_first("29000"), ImportedDate::Exact(date(5, 25, Some(1979))));
+        // A year on its own isn't a date
+        assert_eq!(month_first("1980"), ImportedDate::Invalid);
+    }
+
+    #[test]
+    fn february_29_needs_a_leap_year() {
+        assert_eq!(month_first("2/29/2000"), ImportedDate::Exact(date(2, 29, Some(2000))));
+        assert_eq!(month_first("2/29"), ImportedDate::Exact(date(2, 29, None)));
+        assert_eq!(month_first("2/29/1999"), ImportedDate::Invalid);
+        assert_eq!(month_first("29 Feb 1900"), ImportedDate::Invalid);
+    }
+}
@@ -0,0 +1,338 @@
+use crate::db::Database;
+use ab_glyph::FontRef;
+use printpdf::{IndirectFontRef, PdfDocumentReference};
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+// Bundled default typeface: DejaVu Sans covers Latin (including Vietnamese),
+// Greek, Cyrillic and typographic punctuation such as curly quotes and dashes.
+// Scripts it doesn't cover (e.g. Korean) need a user-selected font.
+const DEFAULT_REGULAR: &[u8] = include_bytes!("../fonts/DejaVuSans.ttf");
+const DEFAULT_BOLD: &[u8] = include_bytes!("../fonts/DejaVuSans-Bold.ttf");
+const DEFAULT_ITALIC: &[u8] = include_bytes!("../fonts/DejaVuSans-Oblique.ttf");
+
+/// The styles the PDF generator draws text with. Each one can be replaced by a
+/// user-selected font file stored in the app data `fonts/` directory.
+#[derive(Clone, Copy)]
+pub enum FontVariant {
+    Regular,
+    Bold,
+    Italic,
+}
+
+impl FontVariant {
+    fn parse(variant: &str) -> Result<Self, String> {
+        match variant {
+            "regular" => Ok(FontVariant::Regular),
+            "bold" => Ok(FontVariant::Bold),
+            "italic" => Ok(FontVariant::Italic),
+            _ => Err(format!("Unknown font variant: {}", variant)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            FontVariant::Regular => "regular",
+            FontVariant::Bold => "bold",
+            FontVariant::Italic => "italic",
+        }
+    }
+
+    fn settings_key(self) -> &'static str {
+        match self {
+            FontVariant::Regular => "pdf_font_regular",
+            FontVariant::Bold => "pdf_font_bold",
+            FontVariant::Italic => "pdf_font_italic",
+        }
+    }
+
+    fn bundled(self) -> &'static [u8] {
+        match self {
+            FontVariant::Regular => DEFAULT_REGULAR,
+            FontVariant::Bold => DEFAULT_BOLD,
+            FontVariant::Italic => DEFAULT_ITALIC,
+        }
+    }
+}
+
+/// Horizontal advance widths for every character a font maps, used to measure
+/// and wrap text before it is placed on the page.
+pub struct FontMetrics {
+    units_per_em: f32,
+    advances: HashMap<char, u16>,
+    /// Advance of the .notdef glyph, drawn for characters the font lacks
+    missing_advance: u16,
+}
+
+impl FontMetrics {
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        let face = ttf_parser::Face::parse(data, 0)
+            .map_err(|e| format!("Invalid font file: {}", e))?;
+
+        // printpdf embeds every external font as a TrueType program, so
+        // OpenType files with PostScript (CFF) outlines would render blank.
+        if face.tables().glyf.is_none() {
+            return Err(
+                "Unsupported font: OpenType fonts with PostScript outlines can't be embedded. Choose a TrueType-flavored .ttf or .otf file.".to_string(),
+            );
+        }
+
+        let mut advances = HashMap::new();
+        if let Some(cmap) = face.tables().cmap {
+            for subtable in cmap.subtables {
+                if !subtable.is_unicode() {
+                    continue;
+                }
+                subtable.codepoints(|code_point| {
+                    let Some(c) = char::from_u32(code_point) else { return };
+                    if let Some(advance) = subtable
+                        .glyph_index(code_point)
+                        .and_then(|glyph| face.glyph_hor_advance(glyph))
+                    {
+                        advances.entry(c).or_insert(advance);
+                    }
+                });
+            }
+        }
+
+        let units_per_em = face.units_per_em();
+        let missing_advance = face
+            .glyph_hor_advance(ttf_parser::GlyphId(0))
+            .unwrap_or(units_per_em / 2);
+
+        Ok(FontMetrics {
+            units_per_em: units_per_em as f32,
+            advances,
+            missing_advance,
+        })
+    }
+
+    /// Characters in `text` the font has no glyph for, in order of first
+    /// appearance. Whitespace is ignored.
+    pub fn missing_chars(&self, text: &str) -> Vec<char> {
+        let mut missing = Vec::new();
+        for c in text.chars() {
+            if !c.is_whitespace() && !self.advances.contains_key(&c) && !missing.contains(&c) {
+                missing.push(c);
+            }
+        }
+        missing
+    }
+
+    /// Measure text width in mm. font_size is in points.
+    pub fn text_width(&self, text: &str, font_size: f32) -> f32 {
+        let width_units: f32 = text
+            .chars()
+            .map(|c| *self.advances.get(&c).unwrap_or(&self.missing_advance) as f32)
+            .sum();
+        // 1 PDF point = 1/72 inch = 25.4/72 mm
+        width_units / self.units_per_em * font_size * 25.4 / 72.0
+    }
+}
+
+/// Raw font programs for each style, either user-selected or bundled.
+pub struct FontData {
+    pub regular: Vec<u8>,
+    pub bold: Vec<u8>,
+    pub italic: Vec<u8>,
+}
+
+impl FontData {
+    /// Read the user's selected fonts from settings, falling back to the
+    /// bundled defaults for any style that isn't set or can't be read.
+    pub fn load(conn: &Connection, fonts_dir: &Path) -> Self {
+        FontData {
+            regular: read_font_variant(conn, fonts_dir, FontVariant::Regular),
+            bold: read_font_variant(conn, fonts_dir, FontVariant::Bold),
+            italic: read_font_variant(conn, fonts_dir, FontVariant::Italic),
+        }
+    }
+}
+
+fn read_font_variant(conn: &Connection, fonts_dir: &Path, variant: FontVariant) -> Vec<u8> {
+    let filename: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?",
+            params![variant.settings_key()],
+            |row| row.get(0),
+        )
+        .ok()
+        .filter(|s: &String| !s.is_empty());
+
+    if let Some(ref fname) = filename {
+        match fs::read(fonts_dir.join(fname)) {
+            Ok(data) if FontMetrics::parse(&data).is_ok() => return data,
+            _ => log::warn!(
+                "Font {} for {} style is missing or unreadable, using bundled default",
+                fname,
+                variant.name()
+            ),
+        }
+    }
+
+    variant.bundled().to_vec()
+}
+
+/// One text style with the metrics used to lay it out. Page canvases look up
+/// their own copy of the font program by `variant` when drawing.
+pub struct LoadedFont {
+    pub variant: FontVariant,
+    pub metrics: FontMetrics,
+}
+
+impl LoadedFont {
+    /// Measure text width in mm. font_size is in points.
+    pub fn width(&self, text: &str, font_size: f32) -> f32 {
+        self.metrics.text_width(text, font_size)
+    }
+}
+
+/// The full set of fonts used by the page renderers.
+pub struct PdfFonts {
+    pub regular: LoadedFont,
+    pub bold: LoadedFont,
+    pub italic: LoadedFont,
+}
+
+impl PdfFonts {
+    pub fn load(data: &FontData) -> Result<Self, String> {
+        Ok(PdfFonts {
+            regular: LoadedFont { variant: FontVariant::Regular, metrics: FontMetrics::parse(&data.regular)? },
+            bold: LoadedFont { variant: FontVariant::Bold, metrics: FontMetrics::parse(&data.bold)? },
+            italic: LoadedFont { variant: FontVariant::Italic, metrics: FontMetrics::parse(&data.italic)? },
+        })
+    }
+}
+
+/// Font programs embedded in a PDF document, one per style.
+pub struct EmbeddedFonts {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    italic: IndirectFontRef,
+}
+
+impl EmbeddedFonts {
+    pub fn embed(doc: &PdfDocumentReference, data: &FontData) -> Result<Self, String> {
+        let embed = |bytes: &[u8]| {
+            doc.add_external_font(bytes)
+                .map_err(|e| format!("Font error: {:?}", e))
+        };
+        Ok(EmbeddedFonts {
+            regular: embed(&data.regular)?,
+            bold: embed(&data.bold)?,
+            italic: embed(&data.italic)?,
+        })
+    }
+
+    pub fn get(&self, variant: FontVariant) -> &IndirectFontRef {
+        match variant {
+            FontVariant::Regular => &self.regular,
+            FontVariant::Bold => &self.bold,
+            FontVariant::Italic => &self.italic,
+        }
+    }
+}
+
+/// Parsed outlines for rasterizing text into preview images.
+pub struct RasterFonts<'a> {
+    regular: FontRef<'a>,
+    bold: FontRef<'a>,
+    italic: FontRef<'a>,
+}
+
+impl<'a> RasterFonts<'a> {
+    pub fn parse(data: &'a FontData) -> Result<Self, String> {
+        let parse = |bytes: &'a [u8]| {
+            FontRef::try_from_slice(bytes).map_err(|e| format!("Font error: {}", e))
+        };
+        Ok(RasterFonts {
+            regular: parse(&data.regular)?,
+            bold: parse(&data.bold)?,
+            italic: parse(&data.italic)?,
+        })
+    }
+
+    pub fn get(&self, variant: FontVariant) -> &FontRef<'a> {
+        match variant {
+            FontVariant::Regular => &self.regular,
+            FontVariant::Bold => &self.bold,
+            FontVariant::Italic => &self.italic,
+        }
+    }
+}
+
+#[tauri::command]
+pub fn save_pdf_font(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    file_path: String,
+    variant: String,
+) -> Result<String, String> {
+    let variant = FontVariant::parse(&variant)?;
+    let data = fs::read(&file_path).map_err(|e| format!("Failed to read font file: {}", e))?;
+
+    // Validate before saving so a bad file never reaches generate_pdf
+    FontMetrics::parse(&data)?;
+
+    let ext = Path::new(&file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .filter(|e| e == "ttf" || e == "otf")
+        .unwrap_or_else(|| "ttf".to_string());
+
+    let fonts_dir = Database::get_fonts_dir(&app_handle);
+    let filename = format!("{}_{}.{}", variant.name(), Uuid::new_v4(), ext);
+    fs::write(fonts_dir.join(&filename), &data)
+        .map_err(|e| format!("Failed to save font file: {}", e))?;
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    remove_selected_font(&conn, &fonts_dir, variant);
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
+        params![variant.settings_key(), filename],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(filename)
+}
+
+#[tauri::command]
+pub fn clear_pdf_font(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    variant: String,
+) -> Result<(), String> {
+    let variant = FontVariant::parse(&variant)?;
+    let fonts_dir = Database::get_fonts_dir(&app_handle);
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    remove_selected_font(&conn, &fonts_dir, variant);
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?, '')",
+        params![variant.settings_key()],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Delete the font file currently selected for a style, if any.
+fn remove_selected_font(conn: &Connection, fonts_dir: &Path, variant: FontVariant) {
+    let previous: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?",
+            params![variant.settings_key()],
+            |row| row.get(0),
+        )
+        .ok()
+        .filter(|s: &String| !s.is_empty());
+
+    if let Some(fname) = previous {
+        let _ = fs::remove_file(fonts_dir.join(fname));
+    }
+}
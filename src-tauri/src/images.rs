@@ -0,0 +1,511 @@
+use crate::jobs::JobContext;
+use crate::models::PhotoCrop;
+use printpdf::image_crate::{self, DynamicImage, GenericImageView};
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+// Uniform aspect ratio for photo grid — matches Peters reference photo (6637 x 4427)
+pub const GRID_PHOTO_ASPECT: f32 = 6637.0 / 4427.0;
+
+// Without a family's framing, grid photos are centered horizontally and
+// anchored to the top to preserve heads
+const GRID_DEFAULT_FOCUS: (f32, f32) = (0.5, 0.0);
+
+// Staff portraits are center-cropped to 4:6
+pub const STAFF_PHOTO_ASPECT: f32 = 4.0 / 6.0;
+
+/// Pixel size for the circular crop (higher = better quality)
+const MEMBER_PHOTO_PIXELS: u32 = 192;
+
+// Prepared images are dropped wholesale once the cache grows past this
+const CACHE_MAX_BYTES: usize = 256 * 1024 * 1024;
+
+/// Resolution and compression for photos embedded in the PDF
+#[derive(Debug, Clone, Copy)]
+pub struct ImageQuality {
+    /// Photos with more pixels than this at their printed size are downsampled
+    pub max_dpi: f32,
+    pub jpeg_quality: u8,
+}
+
+impl ImageQuality {
+    /// Look up a `PdfOptions::image_quality` preset; defaults to "print"
+    pub fn preset(name: Option<&str>) -> Result<Self, String> {
+        match name.unwrap_or("print") {
+            "print" => Ok(ImageQuality { max_dpi: 300.0, jpeg_quality: 90 }),
+            "screen" => Ok(ImageQuality { max_dpi: 150.0, jpeg_quality: 80 }),
+            "email" => Ok(ImageQuality { max_dpi: 96.0, jpeg_quality: 70 }),
+            other => Err(format!("Unknown image quality preset: {}", other)),
+        }
+    }
+
+    /// Pixels needed to print `mm` at the preset's resolution
+    fn pixels_for(&self, mm: f32) -> u32 {
+        (mm / 25.4 * self.max_dpi).ceil().max(1.0) as u32
+    }
+}
+
+/// How a source photo is cropped and finished before it is placed on a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageTreatment {
+    /// Used as-is (cover and full-page images)
+    Full,
+    /// Center-cropped to fill an area with the given aspect ratio (full-bleed
+    /// images), stored as f32 bits so treatments can key a map
+    Fill { aspect_bits: u32 },
+    /// Transparent edges trimmed and alpha composited onto white
+    Flattened,
+    /// Cropped to GRID_PHOTO_ASPECT around the family's framing, or anchored
+    /// to the top without one
+    Grid { crop: Option<PhotoCrop> },
+    /// Center-cropped to 4:6 with rounded corners. The radius is a fraction
+    /// of the shorter side, stored as f32 bits so treatments can key a map.
+    Staff { corner_radius_bits: u32 },
+    /// Circular member avatar on a white background
+    Avatar,
+}
+
+impl ImageTreatment {
+    pub fn fill(aspect: f32) -> Self {
+        ImageTreatment::Fill { aspect_bits: aspect.to_bits() }
+    }
+
+    pub fn staff(corner_radius: f32) -> Self {
+        ImageTreatment::Staff { corner_radius_bits: corner_radius.to_bits() }
+    }
+
+    /// Apply the crop and finish. Returns None when the source is used unchanged.
+    fn apply(&self, img: &DynamicImage) -> Option<DynamicImage> {
+        match *self {
+            ImageTreatment::Full => None,
+            ImageTreatment::Flattened => Some(flatten_transparency(img)),
+            ImageTreatment::Fill { aspect_bits } => {
+                let target_aspect = f32::from_bits(aspect_bits);
+                let (img_width, img_height) = img.dimensions();
+                let img_aspect = img_width as f32 / img_height as f32;
+                if (img_aspect - target_aspect).abs() <= 0.001 {
+                    return None;
+                }
+                let (crop_w, crop_h) = if img_aspect > target_aspect {
+                    ((img_height as f32 * target_aspect) as u32, img_height)
+                } else {
+                    (img_width, (img_width as f32 / target_aspect) as u32)
+                };
+                let cx = (img_width - crop_w) / 2;
+                let cy = (img_height - crop_h) / 2;
+                Some(img.crop_imm(cx, cy, crop_w, crop_h))
+            }
+            ImageTreatment::Grid { crop } => {
+                let (img_width, img_height) = img.dimensions();
+                let src_aspect = img_width as f32 / img_height as f32;
+                if crop.is_none() && (src_aspect - GRID_PHOTO_ASPECT).abs() <= 0.001 {
+                    return None;
+                }
+                let (cx, cy, crop_w, crop_h) =
+                    crop_rect(img_width, img_height, GRID_PHOTO_ASPECT, crop, GRID_DEFAULT_FOCUS);
+                Some(img.crop_imm(cx, cy, crop_w, crop_h))
+            }
+            ImageTreatment::Staff { corner_radius_bits } => {
+                let (img_width, img_height) = img.dimensions();
+                let img_aspect = img_width as f32 / img_height as f32;
+                let cropped = if (img_aspect - STAFF_PHOTO_ASPECT).abs() > 0.01 {
+                    let (crop_w, crop_h) = if img_aspect > STAFF_PHOTO_ASPECT {
+                        ((img_height as f32 * STAFF_PHOTO_ASPECT) as u32, img_height)
+                    } else {
+                        (img_width, (img_width as f32 / STAFF_PHOTO_ASPECT) as u32)
+                    };
+                    let cx = (img_width - crop_w) / 2;
+                    let cy = (img_height - crop_h) / 2;
+                    img.crop_imm(cx, cy, crop_w, crop_h)
+                } else {
+                    img.clone()
+                };
+
+                // Round the corners on the image pixels
+                let mut rgb = cropped.to_rgb8();
+                round_image_corners(&mut rgb, f32::from_bits(corner_radius_bits));
+                Some(DynamicImage::ImageRgb8(rgb))
+            }
+            ImageTreatment::Avatar => Some(crop_to_circle(img, MEMBER_PHOTO_PIXELS)),
+        }
+    }
+}
+
+/// A photo to prepare, and the largest area in mm it will be printed at
+pub struct ImageRequest {
+    pub path: PathBuf,
+    pub treatment: ImageTreatment,
+    pub max_width_mm: f32,
+    pub max_height_mm: f32,
+}
+
+/// A cropped, downsampled and JPEG-encoded photo ready to be placed on a page
+pub struct PreparedImage {
+    pub width: u32,
+    pub height: u32,
+    pub grayscale: bool,
+    pub jpeg: Vec<u8>,
+}
+
+impl PreparedImage {
+    /// Resolution that prints this image `width_mm` wide
+    pub fn dpi_for_width(&self, width_mm: f32) -> f32 {
+        self.width as f32 * 25.4 / width_mm
+    }
+
+    pub fn aspect(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+}
+
+/// Prepared images for one render, looked up by source path and treatment
+#[derive(Default)]
+pub struct PreparedImages {
+    images: HashMap<(PathBuf, ImageTreatment), Arc<PreparedImage>>,
+}
+
+impl PreparedImages {
+    pub fn get(&self, path: &Path, treatment: ImageTreatment) -> Option<&PreparedImage> {
+        self.images
+            .get(&(path.to_path_buf(), treatment))
+            .map(|image| image.as_ref())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct CacheKey {
+    file_hash: u64,
+    treatment: ImageTreatment,
+    max_width: u32,
+    max_height: u32,
+    jpeg_quality: u8,
+}
+
+/// Prepared images kept across PDF generations and previews, keyed by a hash
+/// of the source file's contents and the target size, so a re-run after a
+/// text-only change skips decoding and re-encoding photos.
+#[derive(Default)]
+pub struct ImageCache {
+    entries: Mutex<CacheEntries>,
+}
+
+#[derive(Default)]
+struct CacheEntries {
+    images: HashMap<CacheKey, Arc<PreparedImage>>,
+    total_bytes: usize,
+}
+
+impl ImageCache {
+    fn get(&self, key: &CacheKey) -> Option<Arc<PreparedImage>> {
+        self.entries.lock().ok()?.images.get(key).cloned()
+    }
+
+    fn insert(&self, key: CacheKey, image: Arc<PreparedImage>) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        if entries.total_bytes + image.jpeg.len() > CACHE_MAX_BYTES {
+            entries.images.clear();
+            entries.total_bytes = 0;
+        }
+        entries.total_bytes += image.jpeg.len();
+        if let Some(replaced) = entries.images.insert(key, image) {
+            entries.total_bytes -= replaced.jpeg.len();
+        }
+    }
+}
+
+/// Decode, crop and encode every requested photo in parallel. Photos that
+/// can't be read are logged and left out, so renderers skip them. When run
+/// as part of a job, reports "images" progress and stops early on cancel.
+pub fn prepare_images(
+    requests: Vec<ImageRequest>,
+    quality: ImageQuality,
+    cache: &ImageCache,
+    job: Option<&JobContext>,
+) -> Result<PreparedImages, String> {
+    // The same photo can be requested by several pages
+    let mut unique: HashMap<(PathBuf, ImageTreatment), ImageRequest> = HashMap::new();
+    for request in requests {
+        unique
+            .entry((request.path.clone(), request.treatment))
+            .or_insert(request);
+    }
+
+    let total = unique.len() as u64;
+    let processed = AtomicU64::new(0);
+    let images = unique
+        .into_par_iter()
+        .filter_map(|(key, request)| {
+            if job.is_some_and(|job| job.is_cancelled()) {
+                return None;
+            }
+            let prepared = prepare_image(&request, quality, cache);
+            if let Some(job) = job {
+                job.progress("images", processed.fetch_add(1, Ordering::Relaxed) + 1, Some(total));
+            }
+            match prepared {
+                Ok(image) => Some((key, image)),
+                Err(e) => {
+                    log::warn!("Skipping image {}: {}", request.path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    if let Some(job) = job {
+        job.check_cancelled()?;
+    }
+    Ok(PreparedImages { images })
+}
+
+fn prepare_image(
+    request: &ImageRequest,
+    quality: ImageQuality,
+    cache: &ImageCache,
+) -> Result<Arc<PreparedImage>, String> {
+    let data = std::fs::read(&request.path).map_err(|e| e.to_string())?;
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    let key = CacheKey {
+        file_hash: hasher.finish(),
+        treatment: request.treatment,
+        max_width: quality.pixels_for(request.max_width_mm),
+        max_height: quality.pixels_for(request.max_height_mm),
+        jpeg_quality: quality.jpeg_quality,
+    };
+    if let Some(image) = cache.get(&key) {
+        return Ok(image);
+    }
+
+    let img = image_crate::load_from_memory(&data).map_err(|e| e.to_string())?;
+    let treated = request.treatment.apply(&img);
+    let image = Arc::new(finish_image(data, img, treated, &key)?);
+    cache.insert(key, image.clone());
+    Ok(image)
+}
+
+/// Downsample to the target size and JPEG-encode. An untreated JPEG that
+/// doesn't need downsampling keeps its original bytes, avoiding a second
+/// round of compression loss.
+fn finish_image(
+    data: Vec<u8>,
+    img: DynamicImage,
+    treated: Option<DynamicImage>,
+    key: &CacheKey,
+) -> Result<PreparedImage, String> {
+    let (width, height) = treated.as_ref().unwrap_or(&img).dimensions();
+    let scale = (key.max_width as f32 / width as f32).min(key.max_height as f32 / height as f32);
+    // Small overshoots aren't worth a resample
+    let oversized = scale * 1.05 < 1.0;
+
+    if treated.is_none() && !oversized {
+        match jpeg_components(&data) {
+            Some(1) => return Ok(PreparedImage { width, height, grayscale: true, jpeg: data }),
+            Some(3) => return Ok(PreparedImage { width, height, grayscale: false, jpeg: data }),
+            _ => {}
+        }
+    }
+
+    let source = treated.unwrap_or(img);
+    let source = if oversized {
+        let new_width = ((width as f32 * scale).round() as u32).max(1);
+        let new_height = ((height as f32 * scale).round() as u32).max(1);
+        source.resize_exact(new_width, new_height, image_crate::imageops::FilterType::CatmullRom)
+    } else {
+        source
+    };
+
+    // Pixels go through `to_rgb8` rather than printpdf 0.7's
+    // `from_dynamic_image`, which can swap R and B channels
+    let rgb = source.to_rgb8();
+    let (width, height) = (rgb.width(), rgb.height());
+    let mut jpeg = Vec::new();
+    image_crate::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, key.jpeg_quality)
+        .encode(rgb.as_raw(), width, height, image_crate::ColorType::Rgb8)
+        .map_err(|e| format!("JPEG encoding failed: {}", e))?;
+
+    Ok(PreparedImage { width, height, grayscale: false, jpeg })
+}
+
+/// Number of color components in a baseline or progressive JPEG, read from its
+/// start-of-frame header. Returns None if `data` isn't a JPEG.
+fn jpeg_components(data: &[u8]) -> Option<u8> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return None;
+        }
+        let marker = data[pos + 1];
+        let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        // SOF0-SOF15, excluding DHT (C4), JPG (C8) and DAC (CC)
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            // Segment: length (2), precision (1), height (2), width (2), components (1)
+            return data.get(pos + 9).copied();
+        }
+        pos += 2 + length;
+    }
+    None
+}
+
+/// Pixel size of a source photo once cropped to GRID_PHOTO_ASPECT
+pub fn grid_crop_size(img_width: u32, img_height: u32, crop: Option<PhotoCrop>) -> (u32, u32) {
+    let (_, _, crop_w, crop_h) =
+        crop_rect(img_width, img_height, GRID_PHOTO_ASPECT, crop, GRID_DEFAULT_FOCUS);
+    (crop_w, crop_h)
+}
+
+/// Pixel rect (x, y, width, height) of the largest `aspect` crop of an image,
+/// honoring a family's framing. Without one the crop is placed around
+/// `default_focus`, given in fractions of the image size.
+fn crop_rect(
+    img_width: u32,
+    img_height: u32,
+    aspect: f32,
+    crop: Option<PhotoCrop>,
+    default_focus: (f32, f32),
+) -> (u32, u32, u32, u32) {
+    let (w, h) = (img_width as f32, img_height as f32);
+
+    // Region of the photo to crop within, and the point to center on
+    let ((rx, ry, rw, rh), (fx, fy)) = match crop {
+        Some(PhotoCrop::Rect { x, y, width, height }) => {
+            let x = (x.clamp(0.0, 1.0) * w).min(w - 1.0);
+            let y = (y.clamp(0.0, 1.0) * h).min(h - 1.0);
+            let rw = (width * w).clamp(1.0, w - x);
+            let rh = (height * h).clamp(1.0, h - y);
+            ((x, y, rw, rh), (x + rw / 2.0, y + rh / 2.0))
+        }
+        Some(PhotoCrop::Focus { x, y }) => ((0.0, 0.0, w, h), (x * w, y * h)),
+        None => ((0.0, 0.0, w, h), (default_focus.0 * w, default_focus.1 * h)),
+    };
+
+    let (crop_w, crop_h) = if rw / rh > aspect {
+        // Region is wider — crop width
+        (rh * aspect, rh)
+    } else {
+        // Region is taller — crop height
+        (rw, rw / aspect)
+    };
+    let cx = (fx - crop_w / 2.0).clamp(rx, rx + rw - crop_w);
+    let cy = (fy - crop_h / 2.0).clamp(ry, ry + rh - crop_h);
+
+    let crop_w = (crop_w as u32).clamp(1, img_width);
+    let crop_h = (crop_h as u32).clamp(1, img_height);
+    let cx = (cx as u32).min(img_width - crop_w);
+    let cy = (cy as u32).min(img_height - crop_h);
+    (cx, cy, crop_w, crop_h)
+}
+
+/// Apply rounded corners to an image by painting corner pixels white.
+fn round_image_corners(img: &mut image_crate::RgbImage, radius_fraction: f32) {
+    let (w, h) = (img.width(), img.height());
+    let radius = (w.min(h) as f32 * radius_fraction) as u32;
+    if radius == 0 {
+        return;
+    }
+    let white = image_crate::Rgb([255u8, 255, 255]);
+    let r = radius as f32;
+
+    for corner_y in 0..radius {
+        for corner_x in 0..radius {
+            let dx = r - corner_x as f32 - 0.5;
+            let dy = r - corner_y as f32 - 0.5;
+            if dx * dx + dy * dy > r * r {
+                // Top-left
+                img.put_pixel(corner_x, corner_y, white);
+                // Top-right
+                img.put_pixel(w - 1 - corner_x, corner_y, white);
+                // Bottom-left
+                img.put_pixel(corner_x, h - 1 - corner_y, white);
+                // Bottom-right
+                img.put_pixel(w - 1 - corner_x, h - 1 - corner_y, white);
+            }
+        }
+    }
+}
+
+/// Trim transparent edges from an image and composite its alpha onto a white
+/// background, since PDF image placement here has no soft mask.
+fn flatten_transparency(img: &DynamicImage) -> DynamicImage {
+    let rgba = img.to_rgba8();
+    let (orig_w, orig_h) = (rgba.width(), rgba.height());
+
+    // Find bounding box of non-transparent pixels (alpha > 10)
+    let mut min_x = orig_w;
+    let mut min_y = orig_h;
+    let mut max_x = 0u32;
+    let mut max_y = 0u32;
+    for py in 0..orig_h {
+        for px in 0..orig_w {
+            if rgba.get_pixel(px, py).0[3] > 10 {
+                min_x = min_x.min(px);
+                min_y = min_y.min(py);
+                max_x = max_x.max(px);
+                max_y = max_y.max(py);
+            }
+        }
+    }
+
+    // If no opaque pixels found, use full image
+    let (crop_x, crop_y, w, h) = if max_x >= min_x && max_y >= min_y {
+        (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)
+    } else {
+        (0, 0, orig_w, orig_h)
+    };
+
+    let cropped = img.crop_imm(crop_x, crop_y, w, h);
+    let cropped_rgba = cropped.to_rgba8();
+    let mut rgb = image_crate::RgbImage::new(w, h);
+
+    for (pixel, out) in cropped_rgba.pixels().zip(rgb.pixels_mut()) {
+        let [r, g, b, a] = pixel.0;
+        let alpha = a as f32 / 255.0;
+        let inv = 1.0 - alpha;
+        out.0 = [
+            (r as f32 * alpha + 255.0 * inv) as u8,
+            (g as f32 * alpha + 255.0 * inv) as u8,
+            (b as f32 * alpha + 255.0 * inv) as u8,
+        ];
+    }
+
+    DynamicImage::ImageRgb8(rgb)
+}
+
+/// Crop an image to a circle: resize to a square, then composite onto a white background.
+fn crop_to_circle(img: &DynamicImage, size: u32) -> DynamicImage {
+    let (w, h) = img.dimensions();
+    // Center-crop to square first
+    let side = w.min(h);
+    let crop_x = (w - side) / 2;
+    let crop_y = (h - side) / 2;
+    let cropped = img.crop_imm(crop_x, crop_y, side, side);
+    let resized = cropped.resize_exact(size, size, image_crate::imageops::FilterType::Lanczos3);
+    let rgba = resized.to_rgba8();
+    let center = size as f32 / 2.0;
+    let radius_sq = center * center;
+    // Composite onto white background as RGB (PDF doesn't support alpha well)
+    let mut rgb = image_crate::RgbImage::new(size, size);
+    for py in 0..size {
+        for px in 0..size {
+            let dx = px as f32 - center;
+            let dy = py as f32 - center;
+            if dx * dx + dy * dy <= radius_sq {
+                let p = rgba.get_pixel(px, py);
+                rgb.put_pixel(px, py, image_crate::Rgb([p.0[0], p.0[1], p.0[2]]));
+            } else {
+                rgb.put_pixel(px, py, image_crate::Rgb([255, 255, 255]));
+            }
+        }
+    }
+    DynamicImage::ImageRgb8(rgb)
+}
@@ -1,11 +1,15 @@
+use crate::dates::{parse_import_date, ImportedDate};
 use crate::db::Database;
-use crate::models::{DuplicateMatch, ImportFamilyPreview, ImportMemberPreview, ImportPreview, ImportResult};
+use crate::jobs::{spawn_job, JobContext};
+use crate::models::{
+    DuplicateMatch, ImportDateIssue, ImportDateOptions, ImportFamilyPreview, ImportMemberPreview, ImportPreview,
+    ImportResult, PartialDate,
+};
 use calamine::{open_workbook, Reader, Xlsx};
-use chrono::NaiveDate;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use std::collections::HashMap;
 use std::path::Path;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 #[derive(Debug)]
 struct ImportRow {
@@ -27,106 +31,13 @@ struct ImportRow {
     member_role: Option<String>,
     member_phone: Option<String>,
     member_email: Option<String>,
-    member_birth_date: Option<String>,
-    member_wedding_date: Option<String>,
-}
-
-/// Normalize a date string to a consistent format.
-/// Handles dates with or without years (e.g., "1/15" becomes "01-15", "1/15/1990" becomes "1990-01-15")
-/// Also handles formats like "07/20/    " where year is blank/spaces
-fn normalize_date(date_str: &str) -> Option<String> {
-    let date_str = date_str.trim();
-    if date_str.is_empty() {
-        return None;
-    }
-
-    // Try to parse various date formats
-    // Handle Excel serial dates (numbers)
-    if let Ok(serial) = date_str.parse::<f64>() {
-        // Excel serial date: days since 1899-12-30
-        let days = serial as i64;
-        if days > 0 && days < 100000 {
-            // Convert Excel serial to date
-            let base = NaiveDate::from_ymd_opt(1899, 12, 30)?;
-            let date = base.checked_add_signed(chrono::TimeDelta::days(days))?;
-            return Some(date.format("%Y-%m-%d").to_string());
-        }
-    }
-
-    // Split by common delimiters and filter out empty/whitespace parts
-    let parts: Vec<&str> = date_str
-        .split(|c| c == '/' || c == '-' || c == '.')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    match parts.len() {
-        2 => {
-            // Month/Day only (no year)
-            let month: u32 = parts[0].parse().ok()?;
-            let day: u32 = parts[1].parse().ok()?;
-            if month >= 1 && month <= 12 && day >= 1 && day <= 31 {
-                Some(format!("{:02}-{:02}", month, day))
-            } else {
-                None
-            }
-        }
-        3 => {
-            // Full date with year
-            let p0: u32 = parts[0].parse().ok()?;
-            let p1: u32 = parts[1].parse().ok()?;
-            let p2: u32 = parts[2].parse().ok()?;
-
-            // Determine format: MM/DD/YYYY, DD/MM/YYYY, or YYYY-MM-DD
-            if p0 > 31 {
-                // YYYY-MM-DD format
-                let year = p0;
-                let month = p1;
-                let day = p2;
-                if month >= 1 && month <= 12 && day >= 1 && day <= 31 {
-                    Some(format!("{:04}-{:02}-{:02}", year, month, day))
-                } else {
-                    None
-                }
-            } else if p2 > 31 {
-                // MM/DD/YYYY or DD/MM/YYYY format - assume MM/DD/YYYY (US format)
-                let month = p0;
-                let day = p1;
-                let year = if p2 < 100 {
-                    if p2 > 50 { 1900 + p2 } else { 2000 + p2 }
-                } else {
-                    p2
-                };
-                if month >= 1 && month <= 12 && day >= 1 && day <= 31 {
-                    Some(format!("{:04}-{:02}-{:02}", year, month, day))
-                } else {
-                    None
-                }
-            } else {
-                // All parts <= 31, assume MM/DD/YY with 2-digit year
-                let month = p0;
-                let day = p1;
-                let year = if p2 < 100 {
-                    if p2 > 50 { 1900 + p2 } else { 2000 + p2 }
-                } else {
-                    p2
-                };
-                if month >= 1 && month <= 12 && day >= 1 && day <= 31 {
-                    Some(format!("{:04}-{:02}-{:02}", year, month, day))
-                } else {
-                    None
-                }
-            }
-        }
-        _ => {
-            // Try to preserve as-is if it looks like a valid date string
-            Some(date_str.to_string())
-        }
-    }
+    member_birth_date: Option<PartialDate>,
+    member_wedding_date: Option<PartialDate>,
+    date_issues: Vec<ImportDateIssue>,
 }
 
 /// Route to the appropriate parser based on file extension.
-fn parse_file(file_path: &str) -> Result<Vec<ImportRow>, String> {
+fn parse_file(file_path: &str, date_options: &ImportDateOptions) -> Result<Vec<ImportRow>, String> {
     let path = Path::new(file_path);
     let ext = path
         .extension()
@@ -135,15 +46,15 @@ fn parse_file(file_path: &str) -> Result<Vec<ImportRow>, String> {
         .unwrap_or_default();
 
     match ext.as_str() {
-        "xlsx" | "xls" => parse_xlsx(file_path),
-        "csv" => parse_csv(file_path),
+        "xlsx" | "xls" => parse_xlsx(file_path, date_options),
+        "csv" => parse_csv(file_path, date_options),
         _ => Err(format!("Unsupported file format: .{}. Use .xlsx, .xls, or .csv", ext)),
     }
 }
 
 /// Extract an ImportRow from a header map and a cell getter closure.
 /// Shared logic between XLSX and CSV parsers.
-fn extract_row(get_cell: &dyn Fn(&str) -> Option<String>) -> Option<ImportRow> {
+fn extract_row(get_cell: &dyn Fn(&str) -> Option<String>, date_options: &ImportDateOptions) -> Option<ImportRow> {
     let family_id = get_cell("family id")
         .or_else(|| get_cell("familyid"))
         .or_else(|| get_cell("family_id"));
@@ -194,6 +105,29 @@ fn extract_row(get_cell: &dyn Fn(&str) -> Option<String>) -> Option<ImportRow> {
         .or_else(|| get_cell("envelope name"))
         .or_else(|| get_cell("mail name"));
 
+    // Dates are normalized as they're read; ambiguous and unreadable ones are
+    // noted for the preview, and unreadable ones are left blank
+    let member_name = format!("{} {}", mfirst, mlast);
+    let mut date_issues = Vec::new();
+    let mut read_date = |field: &str, raw: Option<String>| -> Option<PartialDate> {
+        let raw = raw?;
+        let (value, kind, alternative) = match parse_import_date(&raw, date_options) {
+            ImportedDate::Exact(value) => return Some(value),
+            ImportedDate::Ambiguous { value, alternative } => (Some(value), "ambiguous", Some(alternative)),
+            ImportedDate::Invalid => (None, "invalid", None),
+        };
+        date_issues.push(ImportDateIssue {
+            family_id: fid.clone(),
+            member_name: member_name.clone(),
+            field: field.to_string(),
+            value: raw,
+            kind: kind.to_string(),
+            imported_as: value.map(|date| date.to_string()),
+            alternative: alternative.map(|date| date.to_string()),
+        });
+        value
+    };
+
     // Birth date
     let birth_date_raw = get_cell("birth date")
         .or_else(|| get_cell("birthdate"))
@@ -201,7 +135,7 @@ fn extract_row(get_cell: &dyn Fn(&str) -> Option<String>) -> Option<ImportRow> {
         .or_else(|| get_cell("birthday"))
         .or_else(|| get_cell("dob"))
         .or_else(|| get_cell("date of birth"));
-    let birth_date = birth_date_raw.and_then(|d| normalize_date(&d));
+    let birth_date = read_date("birth_date", birth_date_raw);
 
     // Wedding date
     let wedding_date_raw = get_cell("wedding date")
@@ -210,7 +144,7 @@ fn extract_row(get_cell: &dyn Fn(&str) -> Option<String>) -> Option<ImportRow> {
         .or_else(|| get_cell("anniversary"))
         .or_else(|| get_cell("marriage date"))
         .or_else(|| get_cell("married"));
-    let wedding_date = wedding_date_raw.and_then(|d| normalize_date(&d));
+    let wedding_date = read_date("wedding_date", wedding_date_raw);
 
     // Children (family-level field listing the children in the family)
     let children = get_cell("children");
@@ -266,10 +200,11 @@ fn extract_row(get_cell: &dyn Fn(&str) -> Option<String>) -> Option<ImportRow> {
         member_email: email,
         member_birth_date: birth_date,
         member_wedding_date: wedding_date,
+        date_issues,
     })
 }
 
-fn parse_xlsx(file_path: &str) -> Result<Vec<ImportRow>, String> {
+fn parse_xlsx(file_path: &str, date_options: &ImportDateOptions) -> Result<Vec<ImportRow>, String> {
     let path = Path::new(file_path);
     let mut workbook: Xlsx<_> = open_workbook(path).map_err(|e| format!("Failed to open file: {}", e))?;
 
@@ -305,7 +240,7 @@ fn parse_xlsx(file_path: &str) -> Result<Vec<ImportRow>, String> {
             })
         };
 
-        if let Some(import_row) = extract_row(&get_cell) {
+        if let Some(import_row) = extract_row(&get_cell, date_options) {
             rows.push(import_row);
         }
     }
@@ -313,7 +248,7 @@ fn parse_xlsx(file_path: &str) -> Result<Vec<ImportRow>, String> {
     Ok(rows)
 }
 
-fn parse_csv(file_path: &str) -> Result<Vec<ImportRow>, String> {
+fn parse_csv(file_path: &str, date_options: &ImportDateOptions) -> Result<Vec<ImportRow>, String> {
     let mut reader = csv::Reader::from_path(file_path)
         .map_err(|e| format!("Failed to open CSV file: {}", e))?;
 
@@ -341,7 +276,7 @@ fn parse_csv(file_path: &str) -> Result<Vec<ImportRow>, String> {
             })
         };
 
-        if let Some(import_row) = extract_row(&get_cell) {
+        if let Some(import_row) = extract_row(&get_cell, date_options) {
             rows.push(import_row);
         }
     }
@@ -350,8 +285,12 @@ fn parse_csv(file_path: &str) -> Result<Vec<ImportRow>, String> {
 }
 
 #[tauri::command]
-pub fn preview_import(db: State<'_, Database>, file_path: String) -> Result<ImportPreview, String> {
-    let rows = parse_file(&file_path)?;
+pub fn preview_import(
+    db: State<'_, Database>,
+    file_path: String,
+    date_options: ImportDateOptions,
+) -> Result<ImportPreview, String> {
+    let rows = parse_file(&file_path, &date_options)?;
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
     let mut families_map: HashMap<String, ImportFamilyPreview> = HashMap::new();
@@ -402,23 +341,74 @@ pub fn preview_import(db: State<'_, Database>, file_path: String) -> Result<Impo
 
     let families: Vec<ImportFamilyPreview> = families_map.into_values().collect();
     let total_members: usize = families.iter().map(|f| f.members.len()).sum();
+    let date_issues = rows.into_iter().flat_map(|row| row.date_issues).collect();
 
     Ok(ImportPreview {
         total_families: families.len(),
         total_members,
         families,
         duplicates,
+        date_issues,
     })
 }
 
+/// Overwrite a matched member with its spreadsheet row. A date cell that
+/// couldn't be read keeps the stored date instead of clearing it; a blank
+/// cell still clears it.
+fn update_member(conn: &Connection, member_id: i64, row: &ImportRow, sort_order: usize) -> rusqlite::Result<usize> {
+    let (birth, wedding) = (row.member_birth_date, row.member_wedding_date);
+    let unreadable = |field: &str| row.date_issues.iter().any(|issue| issue.field == field && issue.kind == "invalid");
+    conn.execute(
+        "UPDATE members SET role = ?1, phone = ?2, email = ?3,
+             birth_month = COALESCE(?4, CASE WHEN ?10 THEN birth_month END),
+             birth_day = COALESCE(?5, CASE WHEN ?10 THEN birth_day END),
+             birth_year = COALESCE(?6, CASE WHEN ?10 THEN birth_year END),
+             wedding_month = COALESCE(?7, CASE WHEN ?11 THEN wedding_month END),
+             wedding_day = COALESCE(?8, CASE WHEN ?11 THEN wedding_day END),
+             wedding_year = COALESCE(?9, CASE WHEN ?11 THEN wedding_year END),
+             sort_order = ?12, updated_at = datetime('now')
+         WHERE id = ?13",
+        params![
+            row.member_role,
+            row.member_phone,
+            row.member_email,
+            birth.map(|d| d.month),
+            birth.map(|d| d.day),
+            birth.and_then(|d| d.year),
+            wedding.map(|d| d.month),
+            wedding.map(|d| d.day),
+            wedding.and_then(|d| d.year),
+            unreadable("birth_date"),
+            unreadable("wedding_date"),
+            sort_order as i32,
+            member_id,
+        ],
+    )
+}
+
+/// Start importing a spreadsheet as a background job and return its job id.
+/// Reports "families" progress; the job's result is the ImportResult.
 #[tauri::command]
 pub fn execute_import(
-    db: State<'_, Database>,
+    app_handle: AppHandle,
     file_path: String,
     update_duplicates: bool,
+    date_options: ImportDateOptions,
+) -> Result<String, String> {
+    spawn_job(&app_handle, move |job| run_import(job, &file_path, update_duplicates, &date_options))
+}
+
+fn run_import(
+    job: &JobContext,
+    file_path: &str,
+    update_duplicates: bool,
+    date_options: &ImportDateOptions,
 ) -> Result<ImportResult, String> {
-    let rows = parse_file(&file_path)?;
+    let rows = parse_file(file_path, date_options)?;
+    let db = job.app_handle().state::<Database>();
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    // A cancelled import rolls back everything written so far
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
 
     let mut result = ImportResult {
         families_created: 0,
@@ -433,7 +423,10 @@ pub fn execute_import(
         families_map.entry(row.family_id.clone()).or_default().push(row);
     }
 
-    for (family_id, family_rows) in families_map {
+    let total_families = families_map.len() as u64;
+    for (index, (family_id, family_rows)) in families_map.into_iter().enumerate() {
+        job.check_cancelled()?;
+        job.progress("families", index as u64, Some(total_families));
         let first_row = family_rows.first().unwrap();
 
         let existing_family_id: Option<i64> = conn
@@ -503,6 +496,7 @@ pub fn execute_import(
         };
 
         for (sort_order, row) in family_rows.iter().enumerate() {
+            let (birth, wedding) = (row.member_birth_date, row.member_wedding_date);
             let existing_member_id: Option<i64> = conn
                 .query_row(
                     "SELECT id FROM members WHERE family_id = ? AND LOWER(first_name) = LOWER(?) AND LOWER(last_name) = LOWER(?)",
@@ -513,10 +507,7 @@ pub fn execute_import(
 
             if let Some(member_id) = existing_member_id {
                 if update_duplicates {
-                    if let Err(e) = conn.execute(
-                        "UPDATE members SET role = ?, phone = ?, email = ?, birth_date = ?, wedding_date = ?, sort_order = ?, updated_at = datetime('now') WHERE id = ?",
-                        params![row.member_role, row.member_phone, row.member_email, row.member_birth_date, row.member_wedding_date, sort_order as i32, member_id],
-                    ) {
+                    if let Err(e) = update_member(&conn, member_id, row, sort_order) {
                         result.errors.push(format!("Failed to update member {} {}: {}", row.member_first_name, row.member_last_name, e));
                     } else {
                         result.members_updated += 1;
@@ -524,8 +515,22 @@ pub fn execute_import(
                 }
             } else {
                 if let Err(e) = conn.execute(
-                    "INSERT INTO members (family_id, first_name, last_name, role, phone, email, birth_date, wedding_date, sort_order) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                    params![db_family_id, row.member_first_name, row.member_last_name, row.member_role, row.member_phone, row.member_email, row.member_birth_date, row.member_wedding_date, sort_order as i32],
+                    "INSERT INTO members (family_id, first_name, last_name, role, phone, email, birth_month, birth_day, birth_year, wedding_month, wedding_day, wedding_year, sort_order) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        db_family_id,
+                        row.member_first_name,
+                        row.member_last_name,
+                        row.member_role,
+                        row.member_phone,
+                        row.member_email,
+                        birth.map(|d| d.month),
+                        birth.map(|d| d.day),
+                        birth.and_then(|d| d.year),
+                        wedding.map(|d| d.month),
+                        wedding.map(|d| d.day),
+                        wedding.and_then(|d| d.year),
+                        sort_order as i32,
+                    ],
                 ) {
                     result.errors.push(format!("Failed to create member {} {}: {}", row.member_first_name, row.member_last_name, e));
                 } else {
@@ -535,10 +540,13 @@ pub fn execute_import(
         }
     }
 
-    let filename = Path::new(&file_path)
+    job.check_cancelled()?;
+    job.progress("families", total_families, Some(total_families));
+
+    let filename = Path::new(file_path)
         .file_name()
         .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_else(|| file_path.clone());
+        .unwrap_or_else(|| file_path.to_string());
 
     let _ = conn.execute(
         "INSERT INTO import_history (filename, families_created, families_updated, members_created, members_updated) VALUES (?, ?, ?, ?, ?)",
@@ -551,5 +559,51 @@ pub fn execute_import(
         ],
     );
 
+    tx.commit().map_err(|e| e.to_string())?;
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(birth_date: &str) -> ImportRow {
+        let cells = HashMap::from([
+            ("family id", "F001"),
+            ("family name", "Smith"),
+            ("first name", "John"),
+            ("last name", "Smith"),
+            ("birth date", birth_date),
+        ]);
+        let get_cell = |header: &str| cells.get(header).filter(|value| !value.is_empty()).map(|value| value.to_string());
+        extract_row(&get_cell, &ImportDateOptions::default()).unwrap()
+    }
+
+    fn birth_date(conn: &Connection) -> (Option<u32>, Option<u32>, Option<i32>) {
+        conn.query_row("SELECT birth_month, birth_day, birth_year FROM members WHERE id = 1", [], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn reimport_keeps_dates_it_cannot_read() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::migrations::migrate(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO families (id, family_id, name) VALUES (1, 'F001', 'Smith');
+             INSERT INTO members (id, family_id, first_name, last_name, birth_month, birth_day, birth_year)
+                 VALUES (1, 1, 'John', 'Smith', 3, 14, 1950);",
+        )
+        .unwrap();
+
+        update_member(&conn, 1, &row("sometime in spring"), 0).unwrap();
+        assert_eq!(birth_date(&conn), (Some(3), Some(14), Some(1950)));
+
+        update_member(&conn, 1, &row("1950-03-15"), 0).unwrap();
+        assert_eq!(birth_date(&conn), (Some(3), Some(15), Some(1950)));
+
+        update_member(&conn, 1, &row(""), 0).unwrap();
+        assert_eq!(birth_date(&conn), (None, None, None));
+    }
+}
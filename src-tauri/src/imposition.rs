@@ -0,0 +1,237 @@
+const DEFAULT_SIGNATURE_SHEETS: usize = 4;
+const MAX_SIGNATURE_SHEETS: usize = 16;
+
+/// How the booklet's pages, in reading order, are arranged onto printed sheet
+/// sides. Each scheme places pages in a grid of slots; slots past the last
+/// content page print blank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Imposition {
+    /// Nested sheets folded once and stapled through the fold
+    SaddleStitch,
+    /// Folded signatures of `sheets` nested sheets each, stacked and glued
+    /// at the spine
+    PerfectBound { sheets: usize },
+    /// `up` pages per side. Sheets are cut apart and the piles stacked in
+    /// order, for pocket directories.
+    CutStack { up: usize },
+}
+
+/// A page position on one sheet side. Columns count from the left and rows
+/// from the top.
+pub struct Slot {
+    /// 0-based content page index in reading order, or None to leave blank
+    pub page: Option<usize>,
+    pub col: usize,
+    pub row: usize,
+}
+
+/// One printed side of a sheet
+pub struct SheetSide {
+    pub slots: Vec<Slot>,
+}
+
+impl Imposition {
+    /// Look up a scheme by name: "saddle_stitch" (default), "perfect_bound",
+    /// "two_up" or "four_up"
+    pub fn from_name(name: Option<&str>, signature_sheets: Option<usize>) -> Result<Self, String> {
+        match name.unwrap_or("saddle_stitch") {
+            "saddle_stitch" => Ok(Imposition::SaddleStitch),
+            "perfect_bound" => {
+                let sheets = signature_sheets.unwrap_or(DEFAULT_SIGNATURE_SHEETS);
+                if !(1..=MAX_SIGNATURE_SHEETS).contains(&sheets) {
+                    return Err(format!(
+                        "Signatures must have between 1 and {} sheets",
+                        MAX_SIGNATURE_SHEETS
+                    ));
+                }
+                Ok(Imposition::PerfectBound { sheets })
+            }
+            "two_up" => Ok(Imposition::CutStack { up: 2 }),
+            "four_up" => Ok(Imposition::CutStack { up: 4 }),
+            other => Err(format!("Unknown imposition: {}", other)),
+        }
+    }
+
+    /// Slot grid on each sheet side as (columns, rows)
+    pub fn grid(&self) -> (usize, usize) {
+        match self {
+            Imposition::CutStack { up: 4 } => (2, 2),
+            _ => (2, 1),
+        }
+    }
+
+    /// Number of page slots needed for `page_count` pages. Folded schemes need
+    /// a multiple of 4; cut-stacks only fill out the last sheet.
+    pub fn padded_page_count(&self, page_count: usize, duplex: bool) -> usize {
+        let multiple = match self {
+            Imposition::SaddleStitch | Imposition::PerfectBound { .. } => 4,
+            Imposition::CutStack { up } if duplex => 2 * up,
+            Imposition::CutStack { up } => *up,
+        };
+        page_count.div_ceil(multiple) * multiple
+    }
+
+    /// Sheet sides in output order.
+    ///
+    /// Duplex output alternates each sheet's front and back. Backs have
+    /// their columns mirrored, which lines up with the front when the
+    /// landscape sheet is flipped on its short edge. Simplex cut-stacks print
+    /// one page per slot on fronts only; simplex folded schemes print every
+    /// front and then every back, so the stack can be turned over on its
+    /// short edge and fed through again.
+    pub fn sides(&self, page_count: usize, duplex: bool) -> Vec<SheetSide> {
+        let padded = self.padded_page_count(page_count, duplex);
+        let page = |idx: usize| (idx < page_count).then_some(idx);
+
+        let sheets: Vec<(SheetSide, Option<SheetSide>)> = match self {
+            Imposition::SaddleStitch => fold_signature(0, padded, page),
+            Imposition::PerfectBound { sheets } => (0..padded)
+                .step_by(4 * sheets)
+                .flat_map(|first| fold_signature(first, (padded - first).min(4 * sheets), page))
+                .collect(),
+            Imposition::CutStack { up } => {
+                let (cols, _) = self.grid();
+                let sides_per_sheet = if duplex { 2 } else { 1 };
+                let num_sheets = padded / (up * sides_per_sheet);
+                // Pile k holds a consecutive run of pages once the sheets are cut
+                let pile_len = num_sheets * sides_per_sheet;
+                (0..num_sheets)
+                    .map(|s| {
+                        let front = (0..*up)
+                            .map(|k| Slot {
+                                page: page(k * pile_len + s * sides_per_sheet),
+                                col: k % cols,
+                                row: k / cols,
+                            })
+                            .collect();
+                        let back = duplex.then(|| SheetSide {
+                            slots: (0..*up)
+                                .map(|k| Slot {
+                                    page: page(k * pile_len + 2 * s + 1),
+                                    col: cols - 1 - k % cols,
+                                    row: k / cols,
+                                })
+                                .collect(),
+                        });
+                        (SheetSide { slots: front }, back)
+                    })
+                    .collect()
+            }
+        };
+
+        if duplex {
+            sheets
+                .into_iter()
+                .flat_map(|(front, back)| std::iter::once(front).chain(back))
+                .collect()
+        } else {
+            let (fronts, backs): (Vec<_>, Vec<_>) = sheets.into_iter().unzip();
+            fronts.into_iter().chain(backs.into_iter().flatten()).collect()
+        }
+    }
+}
+
+/// Front and back of each sheet in one folded signature covering pages
+/// `first..first + pages`. The outermost sheet carries the first and last
+/// pages; the right-hand page of each front is backed by the next page.
+fn fold_signature(
+    first: usize,
+    pages: usize,
+    page: impl Fn(usize) -> Option<usize>,
+) -> Vec<(SheetSide, Option<SheetSide>)> {
+    let slot = |idx: usize, col: usize| Slot { page: page(first + idx), col, row: 0 };
+    (0..pages / 4)
+        .map(|s| {
+            let front = SheetSide {
+                slots: vec![slot(pages - 1 - 2 * s, 0), slot(2 * s, 1)],
+            };
+            let back = SheetSide {
+                slots: vec![slot(2 * s + 1, 0), slot(pages - 2 - 2 * s, 1)],
+            };
+            (front, Some(back))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Each side's pages as (col, row, 1-based page or 0 for blank), in the
+    /// order the slots are listed
+    fn layout(sides: &[SheetSide]) -> Vec<Vec<(usize, usize, usize)>> {
+        sides
+            .iter()
+            .map(|side| {
+                side.slots
+                    .iter()
+                    .map(|slot| (slot.col, slot.row, slot.page.map_or(0, |p| p + 1)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn saddle_stitch_eight_pages() {
+        let sides = Imposition::SaddleStitch.sides(8, true);
+        assert_eq!(
+            layout(&sides),
+            vec![
+                vec![(0, 0, 8), (1, 0, 1)],
+                vec![(0, 0, 2), (1, 0, 7)],
+                vec![(0, 0, 6), (1, 0, 3)],
+                vec![(0, 0, 4), (1, 0, 5)],
+            ]
+        );
+    }
+
+    #[test]
+    fn saddle_stitch_pads_with_blank_pages() {
+        let sides = Imposition::SaddleStitch.sides(6, true);
+        assert_eq!(Imposition::SaddleStitch.padded_page_count(6, true), 8);
+        assert_eq!(
+            layout(&sides),
+            vec![
+                vec![(0, 0, 0), (1, 0, 1)],
+                vec![(0, 0, 2), (1, 0, 0)],
+                vec![(0, 0, 6), (1, 0, 3)],
+                vec![(0, 0, 4), (1, 0, 5)],
+            ]
+        );
+    }
+
+    #[test]
+    fn saddle_stitch_simplex_prints_fronts_then_backs() {
+        let sides = Imposition::SaddleStitch.sides(8, false);
+        assert_eq!(
+            layout(&sides),
+            vec![
+                vec![(0, 0, 8), (1, 0, 1)],
+                vec![(0, 0, 6), (1, 0, 3)],
+                vec![(0, 0, 2), (1, 0, 7)],
+                vec![(0, 0, 4), (1, 0, 5)],
+            ]
+        );
+    }
+
+    #[test]
+    fn four_up_backs_mirror_columns_for_short_edge_flip() {
+        let sides = Imposition::CutStack { up: 4 }.sides(8, true);
+        assert_eq!(
+            layout(&sides),
+            vec![
+                vec![(0, 0, 1), (1, 0, 3), (0, 1, 5), (1, 1, 7)],
+                vec![(1, 0, 2), (0, 0, 4), (1, 1, 6), (0, 1, 8)],
+            ]
+        );
+    }
+
+    #[test]
+    fn cut_stack_simplex_prints_fronts_only() {
+        let sides = Imposition::CutStack { up: 2 }.sides(3, false);
+        assert_eq!(
+            layout(&sides),
+            vec![vec![(0, 0, 1), (1, 0, 3)], vec![(0, 0, 2), (1, 0, 0)]]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use crate::models::{JobFinished, JobProgress};
+use serde::Serialize;
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+
+const PROGRESS_EVENT: &str = "job-progress";
+const FINISHED_EVENT: &str = "job-finished";
+
+// Byte-count progress is reported at most this often
+const PROGRESS_BYTES_STEP: u64 = 256 * 1024;
+
+/// Error returned by job work that stopped because it was cancelled
+pub const CANCELLED: &str = "Cancelled";
+
+/// Cancellation flags for running background jobs, keyed by job id
+#[derive(Default)]
+pub struct JobRegistry {
+    jobs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+/// Handle passed to a job's work for reporting progress and checking for
+/// cancellation. Safe to share across worker threads.
+pub struct JobContext {
+    id: String,
+    app_handle: AppHandle,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobContext {
+    pub fn app_handle(&self) -> &AppHandle {
+        &self.app_handle
+    }
+
+    pub fn progress(&self, stage: &str, current: u64, total: Option<u64>) {
+        let payload = JobProgress {
+            job_id: self.id.clone(),
+            stage: stage.to_string(),
+            current,
+            total,
+        };
+        if let Err(e) = self.app_handle.emit(PROGRESS_EVENT, payload) {
+            log::warn!("Failed to emit job progress: {}", e);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Return early from job work with `?` once cancellation is requested
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Wraps a job's output file, reporting bytes written as "bytes" progress
+/// and failing writes once the job is cancelled
+pub struct ProgressWriter<'a, W: Write> {
+    inner: W,
+    job: &'a JobContext,
+    written: u64,
+    reported: u64,
+}
+
+impl<'a, W: Write> ProgressWriter<'a, W> {
+    pub fn new(inner: W, job: &'a JobContext) -> Self {
+        ProgressWriter { inner, job, written: 0, reported: 0 }
+    }
+}
+
+impl<W: Write> Write for ProgressWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.job.is_cancelled() {
+            return Err(io::Error::other(CANCELLED));
+        }
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        if self.written - self.reported >= PROGRESS_BYTES_STEP {
+            self.reported = self.written;
+            self.job.progress("bytes", self.written, None);
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        if self.written != self.reported {
+            self.reported = self.written;
+            self.job.progress("bytes", self.written, None);
+        }
+        Ok(())
+    }
+}
+
+/// Run `work` on a background thread and return its job id immediately.
+/// Progress is emitted as "job-progress" events while it runs, and its result
+/// or error as a single "job-finished" event.
+pub fn spawn_job<T, F>(app_handle: &AppHandle, work: F) -> Result<String, String>
+where
+    T: Serialize,
+    F: FnOnce(&JobContext) -> Result<T, String> + Send + 'static,
+{
+    let job = JobContext {
+        id: Uuid::new_v4().to_string(),
+        app_handle: app_handle.clone(),
+        cancelled: Arc::new(AtomicBool::new(false)),
+    };
+    let job_id = job.id.clone();
+
+    app_handle
+        .state::<JobRegistry>()
+        .jobs
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(job.id.clone(), job.cancelled.clone());
+
+    std::thread::spawn(move || {
+        // A panic in the work still finishes the job, as a failure
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| work(&job)))
+            .unwrap_or_else(|payload| Err(format!("Job stopped unexpectedly: {}", panic_message(&*payload))));
+
+        if let Ok(mut jobs) = job.app_handle.state::<JobRegistry>().jobs.lock() {
+            jobs.remove(&job.id);
+        }
+
+        let finished = match outcome {
+            Ok(value) => match serde_json::to_value(value) {
+                Ok(result) => JobFinished {
+                    job_id: job.id.clone(),
+                    status: "completed".to_string(),
+                    result: Some(result),
+                    error: None,
+                },
+                Err(e) => JobFinished {
+                    job_id: job.id.clone(),
+                    status: "failed".to_string(),
+                    result: None,
+                    error: Some(e.to_string()),
+                },
+            },
+            Err(_) if job.is_cancelled() => JobFinished {
+                job_id: job.id.clone(),
+                status: "cancelled".to_string(),
+                result: None,
+                error: None,
+            },
+            Err(e) => JobFinished {
+                job_id: job.id.clone(),
+                status: "failed".to_string(),
+                result: None,
+                error: Some(e),
+            },
+        };
+        if let Err(e) = job.app_handle.emit(FINISHED_EVENT, finished) {
+            log::error!("Failed to emit job result: {}", e);
+        }
+    });
+
+    Ok(job_id)
+}
+
+/// The message a panic was raised with
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown error")
+}
+
+/// Ask a running job to stop. The job finishes with a "cancelled" status
+/// once it reaches its next cancellation check.
+#[tauri::command]
+pub fn cancel_job(registry: State<'_, JobRegistry>, job_id: String) -> Result<(), String> {
+    let jobs = registry.jobs.lock().map_err(|e| e.to_string())?;
+    let cancelled = jobs
+        .get(&job_id)
+        .ok_or_else(|| format!("No running job with id {}", job_id))?;
+    cancelled.store(true, Ordering::Relaxed);
+    Ok(())
+}
@@ -1,10 +1,21 @@
 mod backup;
+mod calendar;
+mod canvas;
 mod commands;
+mod dates;
 mod db;
+mod fonts;
+mod images;
+mod imposition;
 mod import;
+mod jobs;
+mod locale;
+mod markdown;
+mod migrations;
 mod models;
 mod pdf;
 mod photos;
+mod theme;
 
 use db::Database;
 use tauri::Manager;
@@ -28,6 +39,8 @@ pub fn run() {
             let database = Database::new(app.handle())
                 .expect("Failed to initialize database");
             app.manage(database);
+            app.manage(images::ImageCache::default());
+            app.manage(jobs::JobRegistry::default());
 
             Ok(())
         })
@@ -41,6 +54,7 @@ pub fn run() {
             commands::reorder_members,
             // Member commands
             commands::get_members,
+            commands::get_all_members,
             commands::get_member,
             commands::create_member,
             commands::update_member,
@@ -51,14 +65,22 @@ pub fn run() {
             commands::update_staff,
             commands::delete_staff,
             commands::save_staff_photo,
+            commands::get_staff_categories,
+            commands::create_staff_category,
+            commands::update_staff_category,
+            commands::delete_staff_category,
+            commands::reorder_staff_categories,
             // Leadership commands
             commands::get_leadership,
             commands::create_leadership,
             commands::update_leadership,
             commands::delete_leadership,
+            commands::reorder_leadership,
             // Settings commands
             commands::get_settings,
             commands::save_settings,
+            // Database commands
+            commands::check_database_integrity,
             // Import commands
             import::preview_import,
             import::execute_import,
@@ -71,13 +93,30 @@ pub fn run() {
             photos::get_photo_path,
             photos::get_photo_base64,
             photos::crop_family_photo_to_member,
+            photos::get_photo_edits,
+            photos::preview_photo_edits,
+            photos::save_photo_edits,
+            photos::scan_photo_storage,
+            photos::clean_orphaned_photos,
             // PDF commands
             pdf::generate_pdf,
+            pdf::preview_pdf_pages,
+            pdf::preflight_directory,
             pdf::get_family_count,
+            calendar::export_celebrations_ics,
+            // Font commands
+            fonts::save_pdf_font,
+            fonts::clear_pdf_font,
+            // Theme commands
+            theme::get_pdf_theme,
+            theme::save_pdf_theme,
+            theme::get_pdf_theme_presets,
             // Backup commands
             backup::create_backup,
             backup::preview_restore,
             backup::restore_backup,
+            // Job commands
+            jobs::cancel_job,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,237 @@
+//! Languages the directory can be printed in: the fixed text the PDF
+//! generator emits, how celebration dates are written and how names sort.
+
+use rusqlite::Connection;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+    Korean,
+}
+
+/// Fixed text printed in the directory. Entries containing `{}` are filled
+/// in with `Locale::format`.
+#[derive(Debug, Clone, Copy)]
+pub enum Text {
+    LetterFromThePastor,
+    MissionStatement,
+    Contents,
+    Staff,
+    MeetOurStaff,
+    Leadership,
+    MinistryTeamLeadership,
+    ContactInfo,
+    PhotoDirectory,
+    FamilyListings,
+    Celebrations,
+    BirthdaysAndAnniversaries,
+    Birthdays,
+    Anniversaries,
+    AltAddress,
+    /// A page title repeated on the next page
+    Continued,
+    OfficeHours,
+    HomePhone,
+    CellPhone,
+    /// Filled in with `Locale::ordinal`
+    AnniversaryYears,
+    /// Filled in with `Locale::ordinal`
+    BirthdayAge,
+    /// Calendar event titles, filled in with a name
+    BirthdayOf,
+    AnniversaryOf,
+}
+
+impl Text {
+    const ALL: [Text; 23] = [
+        Text::LetterFromThePastor,
+        Text::MissionStatement,
+        Text::Contents,
+        Text::Staff,
+        Text::MeetOurStaff,
+        Text::Leadership,
+        Text::MinistryTeamLeadership,
+        Text::ContactInfo,
+        Text::PhotoDirectory,
+        Text::FamilyListings,
+        Text::Celebrations,
+        Text::BirthdaysAndAnniversaries,
+        Text::Birthdays,
+        Text::Anniversaries,
+        Text::AltAddress,
+        Text::Continued,
+        Text::OfficeHours,
+        Text::HomePhone,
+        Text::CellPhone,
+        Text::AnniversaryYears,
+        Text::BirthdayAge,
+        Text::BirthdayOf,
+        Text::AnniversaryOf,
+    ];
+
+    /// English, Spanish and Korean, in `Locale` order
+    fn catalog(self) -> [&'static str; 3] {
+        match self {
+            Text::LetterFromThePastor => ["Letter From the Pastor", "Carta del Pastor", "목사님 편지"],
+            Text::MissionStatement => ["Mission Statement", "Declaración de Misión", "사명 선언문"],
+            Text::Contents => ["Contents", "Contenido", "목차"],
+            Text::Staff => ["Staff", "Personal", "교역자"],
+            Text::MeetOurStaff => ["Meet Our Staff", "Conozca a Nuestro Personal", "교역자 소개"],
+            Text::Leadership => ["Leadership", "Liderazgo", "사역 리더"],
+            Text::MinistryTeamLeadership => ["Ministry Team Leadership", "Líderes de Ministerios", "사역팀 리더"],
+            Text::ContactInfo => ["Contact Info", "Información de Contacto", "연락처"],
+            Text::PhotoDirectory => ["Photo Directory", "Directorio Fotográfico", "사진 주소록"],
+            Text::FamilyListings => ["Family Listings", "Listado de Familias", "가정 목록"],
+            Text::Celebrations => ["Celebrations", "Celebraciones", "기념일"],
+            Text::BirthdaysAndAnniversaries => ["Birthdays & Anniversaries", "Cumpleaños y Aniversarios", "생일 및 결혼기념일"],
+            Text::Birthdays => ["Birthdays", "Cumpleaños", "생일"],
+            Text::Anniversaries => ["Anniversaries", "Aniversarios", "결혼기념일"],
+            Text::AltAddress => ["Alt Address:", "Otra Dirección:", "다른 주소:"],
+            Text::Continued => ["{} (continued)", "{} (continuación)", "{} (계속)"],
+            Text::OfficeHours => ["Office hours: {}", "Horario de oficina: {}", "근무 시간: {}"],
+            Text::HomePhone => ["H: {}", "Casa: {}", "집: {}"],
+            Text::CellPhone => ["C: {}", "Cel: {}", "휴대폰: {}"],
+            Text::AnniversaryYears => ["{} anniversary", "{} aniversario", "{}주년"],
+            Text::BirthdayAge => ["{} birthday", "{} cumpleaños", "{}세 생일"],
+            Text::BirthdayOf => ["{}'s Birthday", "Cumpleaños de {}", "{} 생일"],
+            Text::AnniversaryOf => ["{}'s Anniversary", "Aniversario de {}", "{} 결혼기념일"],
+        }
+    }
+}
+
+const MONTHS: [[&str; 12]; 3] = [
+    [
+        "January", "February", "March", "April", "May", "June",
+        "July", "August", "September", "October", "November", "December",
+    ],
+    [
+        "Enero", "Febrero", "Marzo", "Abril", "Mayo", "Junio",
+        "Julio", "Agosto", "Septiembre", "Octubre", "Noviembre", "Diciembre",
+    ],
+    ["1월", "2월", "3월", "4월", "5월", "6월", "7월", "8월", "9월", "10월", "11월", "12월"],
+];
+
+impl Locale {
+    /// Locale for a settings code: "en", "es" or "ko"
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "en" => Some(Locale::English),
+            "es" => Some(Locale::Spanish),
+            "ko" => Some(Locale::Korean),
+            _ => None,
+        }
+    }
+
+    /// Load the saved directory language, falling back to English
+    pub fn load(conn: &Connection) -> Self {
+        conn.query_row("SELECT value FROM settings WHERE key = 'locale'", [], |row| row.get::<_, String>(0))
+            .ok()
+            .and_then(|code| Locale::from_code(&code))
+            .unwrap_or_default()
+    }
+
+    pub fn text(self, text: Text) -> &'static str {
+        text.catalog()[self as usize]
+    }
+
+    /// A catalog entry with its `{}` replaced by `value`
+    pub fn format(self, text: Text, value: &str) -> String {
+        self.text(text).replacen("{}", value, 1)
+    }
+
+    /// Every catalog entry and month name in this language, with the
+    /// characters `day` and `month_and_year` add, for checking that the PDF
+    /// fonts can draw them
+    pub fn printed_text(self) -> String {
+        let mut text: String = Text::ALL.iter().map(|entry| self.text(*entry)).collect();
+        text.extend(MONTHS[self as usize]);
+        text.push_str(&self.day(1));
+        text.push_str(&self.month_and_year(0, 2000));
+        text
+    }
+
+    /// Month name for a 0-based month index
+    pub fn month_name(self, month_idx: usize) -> &'static str {
+        MONTHS[self as usize][month_idx]
+    }
+
+    /// A month and year as printed on the cover: "October 2026",
+    /// "Octubre de 2026" or "2026년 10월"
+    pub fn month_and_year(self, month_idx: usize, year: i32) -> String {
+        let month = self.month_name(month_idx);
+        match self {
+            Locale::English => format!("{} {}", month, year),
+            Locale::Spanish => format!("{} de {}", month, year),
+            Locale::Korean => format!("{}년 {}", year, month),
+        }
+    }
+
+    /// A day of the month as printed under its month: "15th", "15" or "15일"
+    pub fn day(self, day: u32) -> String {
+        match self {
+            Locale::English => self.ordinal(day),
+            Locale::Spanish => day.to_string(),
+            Locale::Korean => format!("{}일", day),
+        }
+    }
+
+    /// A count of years as an ordinal: "25th", "25.º" or "25"
+    pub fn ordinal(self, n: u32) -> String {
+        match self {
+            Locale::English => format!("{}{}", n, ordinal_suffix(n)),
+            Locale::Spanish => format!("{}.º", n),
+            Locale::Korean => n.to_string(),
+        }
+    }
+
+    /// Compare names the way the language alphabetizes them. Case and accents
+    /// only break ties, except in Spanish, where ñ is its own letter after n.
+    /// Hangul syllables already sit in dictionary order.
+    pub fn compare(self, a: &str, b: &str) -> Ordering {
+        self.sort_key(a).cmp(&self.sort_key(b)).then_with(|| a.cmp(b))
+    }
+
+    fn sort_key(self, s: &str) -> Vec<u32> {
+        s.chars()
+            .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .map(|c| match c {
+                'ñ' if self == Locale::Spanish => 'n' as u32 * 2 + 1,
+                c if c.is_whitespace() => ' ' as u32 * 2,
+                c => fold_accent(c) as u32 * 2,
+            })
+            .collect()
+    }
+}
+
+fn ordinal_suffix(day: u32) -> &'static str {
+    match (day % 10, day % 100) {
+        (1, 11) => "th",
+        (2, 12) => "th",
+        (3, 13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// The unaccented letter for accented Latin letters found in names
+fn fold_accent(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => 'a',
+        'ç' | 'č' | 'ć' => 'c',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ě' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => 'i',
+        'ñ' | 'ń' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'š' | 'ś' => 's',
+        'ž' | 'ź' | 'ż' => 'z',
+        c => c,
+    }
+}
@@ -0,0 +1,916 @@
+//! CommonMark layout for the pastor's letter, the first-page Markdown and
+//! staff bios. Text is parsed with pulldown-cmark and laid out into lines of
+//! positioned items, which `MarkdownFlow::paginate` splits across as many
+//! pages as the text needs and `draw_items` places on a page.
+
+use crate::canvas::PageCanvas;
+use crate::fonts::{LoadedFont, PdfFonts};
+use crate::images::{ImageTreatment, PreparedImages};
+use crate::pdf::get_full_resolution_path;
+use crate::theme::{MarkdownTheme, PdfTheme};
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
+use std::path::{Path, PathBuf};
+
+/// Points to mm
+const PT_TO_MM: f32 = 25.4 / 72.0;
+/// Height of a capital above the baseline, as a fraction of the font size
+const CAP_HEIGHT: f32 = 0.7;
+/// Depth of a descender below the baseline, as a fraction of the font size
+const DESCENT: f32 = 0.25;
+/// Gap between a list marker and the item's text
+const LIST_MARKER_GAP: f32 = 4.0;
+/// How far a block quote's text sits in from the quote's left edge
+const QUOTE_INDENT: f32 = 6.0;
+/// Inset of a `---` rule from each side, as a fraction of the column width
+const RULE_INSET: f32 = 0.15;
+/// Tallest an inline image is printed
+const MAX_IMAGE_HEIGHT: f32 = 80.0;
+/// Resolution an inline image is assumed to have, so small images aren't
+/// blown up to the full column width
+const IMAGE_NATURAL_DPI: f32 = 150.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextStyle {
+    Regular,
+    Bold,
+    Italic,
+}
+
+/// Something to draw, in mm from the column's left edge (x) and down from the
+/// first line's baseline (y, top, bottom)
+pub enum MarkdownItem {
+    /// Text with its baseline at y. Headings use the heading color.
+    Text { x: f32, y: f32, text: String, size: f32, style: TextStyle, heading: bool },
+    /// Decorative rule from `---`, or before an H1 that isn't the first content
+    Rule { x1: f32, x2: f32, y: f32 },
+    /// Thin line beneath link text
+    Underline { x1: f32, x2: f32, y: f32 },
+    /// Bar down the left of a block quote
+    QuoteBar { x: f32, top: f32, bottom: f32 },
+    /// Inline image from `photos/directory`, fitted inside the box
+    Image { path: PathBuf, x: f32, top: f32, width: f32, height: f32 },
+    /// Clickable area opening `url`
+    Link { x: f32, top: f32, width: f32, height: f32, url: String },
+}
+
+impl MarkdownItem {
+    fn shifted(mut self, dy: f32) -> Self {
+        match &mut self {
+            MarkdownItem::Text { y, .. } | MarkdownItem::Rule { y, .. } | MarkdownItem::Underline { y, .. } => *y += dy,
+            MarkdownItem::QuoteBar { top, bottom, .. } => {
+                *top += dy;
+                *bottom += dy;
+            }
+            MarkdownItem::Image { top, .. } | MarkdownItem::Link { top, .. } => *top += dy,
+        }
+        self
+    }
+}
+
+/// One line of laid-out content. Items are positioned relative to the line's
+/// baseline, and the next line's baseline sits `height` further down plus its
+/// own `space_before`.
+struct Line {
+    /// Gap above the line, dropped at the top of a page
+    space_before: f32,
+    height: f32,
+    /// How far the line's content reaches above its baseline
+    ascent: f32,
+    /// How far the line's content reaches below its baseline
+    depth: f32,
+    items: Vec<MarkdownItem>,
+    /// x of the bar of each block quote the line sits in
+    quote_bars: Vec<f32>,
+    /// Headings and rules move to the next page with the line after them
+    keep_with_next: bool,
+}
+
+enum Inline {
+    Word { text: String, style: TextStyle, link: Option<String>, space_before: bool },
+    Break,
+}
+
+/// Markdown and headings laid out down a column, ready to be drawn in one
+/// piece or split across pages
+pub struct MarkdownFlow<'a> {
+    width: f32,
+    theme: &'a MarkdownTheme,
+    fonts: &'a PdfFonts,
+    lines: Vec<Line>,
+    /// Space the next line needs above it
+    space: f32,
+}
+
+impl<'a> MarkdownFlow<'a> {
+    pub fn new(width: f32, theme: &'a MarkdownTheme, fonts: &'a PdfFonts) -> Self {
+        MarkdownFlow { width, theme, fonts, lines: Vec::new(), space: 0.0 }
+    }
+
+    /// Lay out CommonMark text. Images are looked up by file name in
+    /// `images_dir`, and print their alt text without one. Following the
+    /// directory's long-standing convention, content after a `---` rule or a
+    /// later H1 is centered.
+    pub fn push_markdown(&mut self, markdown: &str, images_dir: Option<&Path>) {
+        let mut state = ParseState::new(self.lines.len(), images_dir);
+        for event in Parser::new(markdown) {
+            state.event(self, event);
+        }
+        state.flush(self);
+    }
+
+    /// A bold H1-sized heading
+    pub fn push_heading(&mut self, text: &str, centered: bool) {
+        let words = plain_words(text, TextStyle::Bold);
+        let (size, line_height) = (self.theme.h1_size, self.theme.h1_line_height);
+        self.push_inlines(&words, size, line_height, true, centered, 0.0, &[], None);
+    }
+
+    /// A full-width rule `space_before` below the previous line, with the next
+    /// line's baseline `height` below the rule
+    pub fn push_rule(&mut self, space_before: f32, height: f32) {
+        self.space = space_before;
+        self.push_rule_line(0.0, self.width, height);
+    }
+
+    /// Each line of `text` centered on its own, wrapping where too wide
+    pub fn push_centered_lines(&mut self, text: &str) {
+        self.space = self.space.max(self.theme.paragraph_spacing);
+        let (size, line_height) = (self.theme.body_size, self.theme.body_line_height);
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let words = plain_words(line, TextStyle::Regular);
+            self.push_inlines(&words, size, line_height, false, true, 0.0, &[], None);
+        }
+    }
+
+    /// Distance from the first baseline to the baseline after the last line
+    pub fn height(&self) -> f32 {
+        let spaces: f32 = self.lines.iter().skip(1).map(|line| line.space_before).sum();
+        spaces + self.lines.iter().map(|line| line.height).sum::<f32>()
+    }
+
+    /// Everything in one column, for text that is kept whole
+    pub fn into_items(self) -> Vec<MarkdownItem> {
+        self.paginate(f32::INFINITY, f32::INFINITY).pop().unwrap_or_default()
+    }
+
+    /// Split the lines into pages, `first_page` and then `other_pages` mm from
+    /// the first baseline to the lowest point content may reach. Headings stay
+    /// with the line after them.
+    pub fn paginate(self, first_page: f32, other_pages: f32) -> Vec<Vec<MarkdownItem>> {
+        let mut lines = self.lines;
+        let mut pages = Vec::new();
+        let mut items: Vec<MarkdownItem> = Vec::new();
+        let mut available = first_page;
+        let mut y = 0.0;
+        let mut page_empty = true;
+        // Quote bars of the line above, as (x, bottom), so bars run unbroken
+        let mut bars: Vec<(f32, f32)> = Vec::new();
+
+        for i in 0..lines.len() {
+            let mut chain_end = i;
+            while lines[chain_end].keep_with_next && chain_end + 1 < lines.len() {
+                chain_end += 1;
+            }
+            let chain = &lines[i..=chain_end];
+
+            if !page_empty {
+                let line_fits = lines_bottom(&lines[i..=i], y, false) <= available;
+                let chain_fits = lines_bottom(chain, y, false) <= available;
+                let chain_fits_alone = lines_bottom(chain, 0.0, true) <= other_pages;
+                if !line_fits || (!chain_fits && chain_fits_alone) {
+                    pages.push(std::mem::take(&mut items));
+                    available = other_pages;
+                    y = 0.0;
+                    page_empty = true;
+                    bars.clear();
+                }
+            }
+
+            let line = &mut lines[i];
+            if !page_empty {
+                y += line.space_before;
+            }
+            let mut line_bars = Vec::new();
+            for &x in &line.quote_bars {
+                let top = bars.iter().find(|(bar_x, _)| *bar_x == x).map_or(y - line.ascent, |(_, bottom)| *bottom);
+                let bottom = y + line.height - line.ascent;
+                items.push(MarkdownItem::QuoteBar { x, top, bottom });
+                line_bars.push((x, bottom));
+            }
+            bars = line_bars;
+            items.extend(std::mem::take(&mut line.items).into_iter().map(|item| item.shifted(y)));
+            page_empty = false;
+            y += line.height;
+        }
+
+        pages.push(items);
+        pages.retain(|page| !page.is_empty());
+        pages
+    }
+
+    fn take_space(&mut self) -> f32 {
+        std::mem::take(&mut self.space)
+    }
+
+    fn push_rule_line(&mut self, x1: f32, x2: f32, height: f32) {
+        let space_before = self.take_space();
+        self.lines.push(Line {
+            space_before,
+            height,
+            ascent: 0.0,
+            depth: 0.0,
+            items: vec![MarkdownItem::Rule { x1, x2, y: 0.0 }],
+            quote_bars: Vec::new(),
+            keep_with_next: true,
+        });
+    }
+
+    /// Wrap words into lines `left` mm in from the column's edge. Words without
+    /// a space before them stay on the same line as the word they follow.
+    #[allow(clippy::too_many_arguments)]
+    fn push_inlines(
+        &mut self,
+        inlines: &[Inline],
+        size: f32,
+        line_height: f32,
+        heading: bool,
+        centered: bool,
+        left: f32,
+        quote_bars: &[f32],
+        mut marker: Option<(String, f32)>,
+    ) {
+        let width = self.width - left;
+        let space_width = self.fonts.regular.width(" ", size);
+
+        // Group words that are printed without a space between them. A group
+        // after a hard break starts a new line.
+        let mut groups: Vec<(Vec<&Inline>, f32, bool)> = Vec::new();
+        let mut break_pending = false;
+        for inline in inlines {
+            match inline {
+                Inline::Word { text, style, space_before, .. } => {
+                    let word_width = style_font(self.fonts, *style).width(text, size);
+                    match groups.last_mut() {
+                        Some((group, group_width, _)) if !space_before && !break_pending => {
+                            group.push(inline);
+                            *group_width += word_width;
+                        }
+                        _ => groups.push((vec![inline], word_width, std::mem::take(&mut break_pending))),
+                    }
+                }
+                Inline::Break => break_pending = true,
+            }
+        }
+
+        let mut lines: Vec<Vec<Vec<&Inline>>> = vec![Vec::new()];
+        let mut line_width = 0.0;
+        for (group, group_width, forced) in groups {
+            let current = lines.last_mut().expect("at least one line");
+            let gap = if current.is_empty() { 0.0 } else { space_width };
+            if forced || (!current.is_empty() && line_width + gap + group_width > width) {
+                lines.push(vec![group]);
+                line_width = group_width;
+            } else {
+                current.push(group);
+                line_width += gap + group_width;
+            }
+        }
+
+        let ascent = size * CAP_HEIGHT * PT_TO_MM;
+        for groups in lines {
+            let mut items = Vec::new();
+            let mut pieces: Vec<(f32, &str, TextStyle, Option<&str>)> = Vec::new();
+            let mut runs: Vec<(f32, String, TextStyle)> = Vec::new();
+            let mut x = 0.0;
+            for (g, group) in groups.iter().enumerate() {
+                if g > 0 {
+                    x += space_width;
+                }
+                for (w, inline) in group.iter().enumerate() {
+                    let Inline::Word { text, style, link, .. } = inline else { continue };
+                    pieces.push((x, text, *style, link.as_deref()));
+                    // Consecutive words in one style are drawn as one run
+                    match runs.last_mut() {
+                        Some((_, run, run_style)) if run_style == style => {
+                            if g > 0 && w == 0 {
+                                run.push(' ');
+                            }
+                            run.push_str(text);
+                        }
+                        _ => runs.push((x, text.clone(), *style)),
+                    }
+                    x += style_font(self.fonts, *style).width(text, size);
+                }
+            }
+            let offset = left + if centered { ((width - x) / 2.0).max(0.0) } else { 0.0 };
+            for (run_x, text, style) in runs {
+                items.push(MarkdownItem::Text { x: offset + run_x, y: 0.0, text, size, style, heading });
+            }
+
+            // Links are underlined and clickable over their whole extent
+            let mut start = 0;
+            while start < pieces.len() {
+                let Some(url) = pieces[start].3 else {
+                    start += 1;
+                    continue;
+                };
+                let mut end = start + 1;
+                while end < pieces.len() && pieces[end].3 == Some(url) {
+                    end += 1;
+                }
+                let (last_x, last_text, last_style, _) = pieces[end - 1];
+                let x1 = offset + pieces[start].0;
+                let x2 = offset + last_x + style_font(self.fonts, last_style).width(last_text, size);
+                let underline_y = size * 0.12 * PT_TO_MM;
+                items.push(MarkdownItem::Underline { x1, x2, y: underline_y });
+                items.push(MarkdownItem::Link {
+                    x: x1,
+                    top: -ascent - 0.5,
+                    width: x2 - x1,
+                    height: ascent + 1.0 + underline_y,
+                    url: url.to_string(),
+                });
+                start = end;
+            }
+
+            if let Some((text, marker_x)) = marker.take() {
+                let body_size = self.theme.body_size;
+                items.push(MarkdownItem::Text { x: marker_x, y: 0.0, text, size: body_size, style: TextStyle::Regular, heading: false });
+            }
+
+            let space_before = self.take_space();
+            self.lines.push(Line {
+                space_before,
+                height: line_height,
+                ascent,
+                depth: size * DESCENT * PT_TO_MM,
+                items,
+                quote_bars: quote_bars.to_vec(),
+                keep_with_next: heading,
+            });
+        }
+    }
+
+    fn push_image(&mut self, path: PathBuf, size: (u32, u32), left: f32, quote_bars: &[f32], link: Option<&str>, marker: Option<(String, f32)>) {
+        let (width_px, height_px) = (size.0.max(1) as f32, size.1.max(1) as f32);
+        let available = self.width - left;
+        let mut width = available.min(width_px * 25.4 / IMAGE_NATURAL_DPI);
+        let mut height = width * height_px / width_px;
+        if height > MAX_IMAGE_HEIGHT {
+            height = MAX_IMAGE_HEIGHT;
+            width = height * width_px / height_px;
+        }
+        // Top of the image lines up with the top of capitals in body text
+        let ascent = self.theme.body_size * CAP_HEIGHT * PT_TO_MM;
+        let x = left + (available - width) / 2.0;
+
+        let mut items = vec![MarkdownItem::Image { path, x, top: -ascent, width, height }];
+        if let Some(url) = link {
+            items.push(MarkdownItem::Link { x, top: -ascent, width, height, url: url.to_string() });
+        }
+        if let Some((text, marker_x)) = marker {
+            let size = self.theme.body_size;
+            items.push(MarkdownItem::Text { x: marker_x, y: 0.0, text, size, style: TextStyle::Regular, heading: false });
+        }
+
+        self.space = self.space.max(self.theme.paragraph_spacing);
+        let space_before = self.take_space();
+        self.lines.push(Line {
+            space_before,
+            height,
+            ascent,
+            depth: height - ascent,
+            items,
+            quote_bars: quote_bars.to_vec(),
+            keep_with_next: false,
+        });
+        self.space = self.theme.paragraph_spacing;
+    }
+}
+
+/// Where the parser is within the Markdown's blocks and inline styles
+struct ParseState<'d> {
+    images_dir: Option<&'d Path>,
+    /// Lines in the flow before this Markdown, to tell whether an H1 is first
+    first_line: usize,
+    inlines: Vec<Inline>,
+    space_pending: bool,
+    strong: u32,
+    emphasis: u32,
+    link: Option<String>,
+    heading: Option<HeadingLevel>,
+    /// Next number of each open list, None for bulleted lists
+    lists: Vec<Option<u64>>,
+    /// Marker for the next line of a list item, and its x
+    marker: Option<(String, f32)>,
+    left: f32,
+    quote_bars: Vec<f32>,
+    /// Destination and alt text of an image being read
+    image: Option<(String, String)>,
+    in_code_block: bool,
+    centered: bool,
+}
+
+impl<'d> ParseState<'d> {
+    fn new(first_line: usize, images_dir: Option<&'d Path>) -> Self {
+        ParseState {
+            images_dir,
+            first_line,
+            inlines: Vec::new(),
+            space_pending: false,
+            strong: 0,
+            emphasis: 0,
+            link: None,
+            heading: None,
+            lists: Vec::new(),
+            marker: None,
+            left: 0.0,
+            quote_bars: Vec::new(),
+            image: None,
+            in_code_block: false,
+            centered: false,
+        }
+    }
+
+    fn event(&mut self, flow: &mut MarkdownFlow, event: Event) {
+        let theme = flow.theme;
+        match event {
+            Event::Start(Tag::Paragraph) => {
+                self.flush(flow);
+                flow.space = flow.space.max(theme.paragraph_spacing);
+            }
+            Event::Start(Tag::Heading { level, .. }) => {
+                self.flush(flow);
+                flow.space = flow.space.max(theme.paragraph_spacing);
+                if level == HeadingLevel::H1 && flow.lines.len() > self.first_line {
+                    self.push_rule(flow, theme.paragraph_spacing);
+                }
+                flow.space += theme.heading_spacing_before;
+                self.heading = Some(level);
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                self.flush(flow);
+                flow.space = flow.space.max(theme.paragraph_spacing);
+                self.quote_bars.push(self.left + 1.0);
+                self.left += QUOTE_INDENT;
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                self.flush(flow);
+                flow.space = flow.space.max(theme.paragraph_spacing);
+                self.in_code_block = true;
+                self.left += theme.list_indent;
+            }
+            Event::Start(Tag::List(start)) => {
+                self.flush(flow);
+                if self.lists.is_empty() {
+                    flow.space = flow.space.max(theme.paragraph_spacing);
+                }
+                self.lists.push(start);
+            }
+            Event::Start(Tag::Item) => {
+                self.flush(flow);
+                let marker_x = self.left + theme.list_indent;
+                self.left = marker_x + LIST_MARKER_GAP;
+                self.marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        let text = format!("{}.", number);
+                        *number += 1;
+                        // Numbers are right-aligned so the item text lines up
+                        let text_width = flow.fonts.regular.width(&text, theme.body_size);
+                        Some((text, self.left - 1.5 - text_width))
+                    }
+                    _ => {
+                        let bullet = if self.lists.len() > 1 { "\u{2013}" } else { "\u{2022}" };
+                        Some((bullet.to_string(), marker_x))
+                    }
+                };
+            }
+            Event::Start(Tag::Emphasis) => self.emphasis += 1,
+            Event::Start(Tag::Strong) => self.strong += 1,
+            Event::Start(Tag::Link { dest_url, .. }) => self.link = Some(dest_url.to_string()),
+            Event::Start(Tag::Image { dest_url, .. }) => self.image = Some((dest_url.to_string(), String::new())),
+
+            Event::End(TagEnd::Paragraph) => self.flush(flow),
+            Event::End(TagEnd::Heading(_)) => {
+                self.flush(flow);
+                self.heading = None;
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                self.flush(flow);
+                self.quote_bars.pop();
+                self.left -= QUOTE_INDENT;
+                flow.space = flow.space.max(theme.paragraph_spacing);
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                self.flush(flow);
+                self.in_code_block = false;
+                self.left -= theme.list_indent;
+            }
+            Event::End(TagEnd::List(_)) => {
+                self.flush(flow);
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    flow.space = flow.space.max(theme.paragraph_spacing);
+                }
+            }
+            Event::End(TagEnd::Item) => {
+                self.flush(flow);
+                // An empty item still shows its marker
+                if self.marker.is_some() {
+                    self.inlines.push(Inline::Break);
+                    self.flush(flow);
+                }
+                self.left -= theme.list_indent + LIST_MARKER_GAP;
+                self.marker = None;
+            }
+            Event::End(TagEnd::Emphasis) => self.emphasis = self.emphasis.saturating_sub(1),
+            Event::End(TagEnd::Strong) => self.strong = self.strong.saturating_sub(1),
+            Event::End(TagEnd::Link) => self.link = None,
+            Event::End(TagEnd::Image) => {
+                if let Some((url, alt)) = self.image.take() {
+                    self.end_image(flow, &url, &alt);
+                }
+            }
+
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, ref mut alt)) = self.image {
+                    alt.push_str(&text);
+                } else if self.in_code_block {
+                    for (i, line) in text.trim_end_matches('\n').split('\n').enumerate() {
+                        if i > 0 {
+                            self.inlines.push(Inline::Break);
+                        }
+                        self.push_text(line);
+                    }
+                    self.inlines.push(Inline::Break);
+                } else {
+                    self.push_text(&text);
+                }
+            }
+            Event::SoftBreak => self.space_pending = true,
+            Event::HardBreak => self.inlines.push(Inline::Break),
+            Event::Rule => {
+                self.flush(flow);
+                flow.space = flow.space.max(theme.paragraph_spacing);
+                self.push_rule(flow, theme.paragraph_spacing + 2.0);
+            }
+            // Raw HTML, footnotes and the like have no place in print
+            _ => {}
+        }
+    }
+
+    fn style(&self) -> TextStyle {
+        if self.heading.is_some() || self.strong > 0 {
+            TextStyle::Bold
+        } else if self.emphasis > 0 {
+            TextStyle::Italic
+        } else {
+            TextStyle::Regular
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let style = self.style();
+        let leading_space = text.starts_with(char::is_whitespace);
+        for (i, word) in text.split_whitespace().enumerate() {
+            let space_before = i > 0 || leading_space || self.space_pending;
+            self.inlines.push(Inline::Word { text: word.to_string(), style, link: self.link.clone(), space_before });
+            self.space_pending = false;
+        }
+        if text.ends_with(char::is_whitespace) {
+            self.space_pending = true;
+        }
+    }
+
+    /// A `---` rule, after which content is centered
+    fn push_rule(&mut self, flow: &mut MarkdownFlow, height: f32) {
+        let inset = (flow.width - self.left) * RULE_INSET;
+        flow.push_rule_line(self.left + inset, flow.width - inset, height);
+        self.centered = true;
+    }
+
+    fn end_image(&mut self, flow: &mut MarkdownFlow, url: &str, alt: &str) {
+        let found = self.images_dir.zip(local_image_name(url)).and_then(|(dir, name)| {
+            let base = dir.join(name);
+            let full_res = get_full_resolution_path(&base);
+            let path = if full_res.exists() { full_res } else { base };
+            match image::image_dimensions(&path) {
+                Ok(size) => Some((path, size)),
+                Err(e) => {
+                    log::warn!("Skipping Markdown image {}: {}", path.display(), e);
+                    None
+                }
+            }
+        });
+
+        match found {
+            Some((path, size)) => {
+                self.flush(flow);
+                let marker = self.marker.take();
+                flow.push_image(path, size, self.left, &self.quote_bars, self.link.as_deref(), marker);
+                self.space_pending = false;
+            }
+            None => {
+                self.emphasis += 1;
+                self.push_text(alt);
+                self.emphasis -= 1;
+            }
+        }
+    }
+
+    /// Lay out the words read so far as lines of the current block
+    fn flush(&mut self, flow: &mut MarkdownFlow) {
+        self.space_pending = false;
+        if self.inlines.is_empty() {
+            return;
+        }
+        let inlines = std::mem::take(&mut self.inlines);
+        let theme = flow.theme;
+        let (size, line_height) = match self.heading {
+            Some(HeadingLevel::H1) => (theme.h1_size, theme.h1_line_height),
+            Some(HeadingLevel::H2) => (theme.h2_size, theme.h2_line_height),
+            Some(_) => (theme.h3_size, theme.h3_line_height),
+            None => (theme.body_size, theme.body_line_height),
+        };
+        let marker = self.marker.take();
+        flow.push_inlines(&inlines, size, line_height, self.heading.is_some(), self.centered, self.left, &self.quote_bars, marker);
+    }
+}
+
+/// File names of the images a Markdown text shows from its images folder,
+/// so photo storage can tell they're in use
+pub fn image_file_names(markdown: &str) -> Vec<String> {
+    Parser::new(markdown)
+        .filter_map(|event| match event {
+            Event::Start(Tag::Image { dest_url, .. }) => local_image_name(&dest_url).map(str::to_string),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The file name an image is looked up by. None for web addresses.
+fn local_image_name(url: &str) -> Option<&str> {
+    if url.contains("://") {
+        return None;
+    }
+    Path::new(url).file_name()?.to_str()
+}
+
+fn plain_words(text: &str, style: TextStyle) -> Vec<Inline> {
+    text.split_whitespace()
+        .map(|word| Inline::Word { text: word.to_string(), style, link: None, space_before: true })
+        .collect()
+}
+
+/// Lowest point lines reach when laid out from the baseline `y`. The first
+/// line's space is dropped when it starts a page.
+fn lines_bottom(lines: &[Line], mut y: f32, at_page_top: bool) -> f32 {
+    let mut bottom = y;
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 || !at_page_top {
+            y += line.space_before;
+        }
+        bottom = y + line.depth;
+        y += line.height;
+    }
+    bottom
+}
+
+fn style_font(fonts: &PdfFonts, style: TextStyle) -> &LoadedFont {
+    match style {
+        TextStyle::Regular => &fonts.regular,
+        TextStyle::Bold => &fonts.bold,
+        TextStyle::Italic => &fonts.italic,
+    }
+}
+
+/// Draw laid-out items with the first baseline at `top`
+pub fn draw_items(
+    canvas: &dyn PageCanvas,
+    items: &[MarkdownItem],
+    left: f32,
+    top: f32,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    images: &PreparedImages,
+) {
+    for item in items {
+        match item {
+            MarkdownItem::Text { x, y, text, size, style, heading } => {
+                canvas.set_fill_color(if *heading { &theme.colors.heading } else { &theme.colors.text });
+                canvas.text(text, *size, left + x, top - y, style_font(fonts, *style));
+            }
+            MarkdownItem::Rule { x1, x2, y } => {
+                canvas.set_outline_color(&theme.colors.rule);
+                canvas.set_outline_thickness(0.75);
+                canvas.line(left + x1, top - y, left + x2, top - y);
+            }
+            MarkdownItem::Underline { x1, x2, y } => {
+                canvas.set_outline_color(&theme.colors.text);
+                canvas.set_outline_thickness(0.3);
+                canvas.line(left + x1, top - y, left + x2, top - y);
+            }
+            MarkdownItem::QuoteBar { x, top: bar_top, bottom } => {
+                canvas.set_outline_color(&theme.colors.rule);
+                canvas.set_outline_thickness(1.5);
+                canvas.line(left + x, top - bar_top, left + x, top - bottom);
+            }
+            MarkdownItem::Image { path, x, top: image_top, width, height } => {
+                let Some(image) = images.get(path, ImageTreatment::Flattened) else {
+                    continue;
+                };
+                // Trimming transparent edges can change the aspect, so fit
+                // the prepared image inside its box
+                let draw_width = width.min(height * image.aspect());
+                let draw_height = draw_width / image.aspect();
+                let image_x = left + x + (width - draw_width) / 2.0;
+                canvas.image(image, image_x, top - image_top - draw_height, image.dpi_for_width(draw_width));
+            }
+            MarkdownItem::Link { x, top: link_top, width, height, url } => {
+                canvas.link(left + x, top - link_top - height, *width, *height, url);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fonts::FontData;
+    use rusqlite::Connection;
+
+    const WIDTH: f32 = 120.0;
+
+    fn fonts() -> PdfFonts {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::migrations::migrate(&mut conn).unwrap();
+        PdfFonts::load(&FontData::load(&conn, Path::new(""))).unwrap()
+    }
+
+    fn layout(markdown: &str, images_dir: Option<&Path>, first_page: f32, other_pages: f32) -> Vec<Vec<MarkdownItem>> {
+        layout_with(&fonts(), markdown, images_dir, first_page, other_pages)
+    }
+
+    fn layout_with(
+        fonts: &PdfFonts,
+        markdown: &str,
+        images_dir: Option<&Path>,
+        first_page: f32,
+        other_pages: f32,
+    ) -> Vec<Vec<MarkdownItem>> {
+        let theme = MarkdownTheme::default();
+        let mut flow = MarkdownFlow::new(WIDTH, &theme, fonts);
+        flow.push_markdown(markdown, images_dir);
+        flow.paginate(first_page, other_pages)
+    }
+
+    /// (x, y, text, style, heading) of every text item
+    fn texts(items: &[MarkdownItem]) -> Vec<(f32, f32, &str, TextStyle, bool)> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                MarkdownItem::Text { x, y, text, style, heading, .. } => Some((*x, *y, text.as_str(), *style, *heading)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn quote_bars(items: &[MarkdownItem]) -> Vec<(f32, f32, f32)> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                MarkdownItem::QuoteBar { x, top, bottom } => Some((*x, *top, *bottom)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn words(pages: &[Vec<MarkdownItem>]) -> Vec<String> {
+        pages
+            .iter()
+            .flat_map(|page| texts(page).into_iter().flat_map(|(_, _, text, _, _)| text.split_whitespace()))
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn paragraph(n: usize) -> String {
+        (0..n).map(|i| format!("word{}", i)).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn headings_are_never_left_at_the_bottom_of_a_page() {
+        let markdown = (1..=8)
+            .map(|i| format!("## Section {}\n\n{}\n", i, paragraph(30)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let fonts = fonts();
+        for page_height in (30..=120).map(|h| h as f32) {
+            let pages = layout_with(&fonts, &markdown, None, page_height, page_height);
+            for page in &pages[..pages.len() - 1] {
+                let last_line = texts(page).into_iter().max_by(|a, b| a.1.total_cmp(&b.1)).unwrap();
+                assert!(!last_line.4, "\"{}\" ends a {} mm page", last_line.2, page_height);
+            }
+        }
+    }
+
+    #[test]
+    fn numbered_lists_number_their_items_and_align_the_text() {
+        let pages = layout("3. First\n4. Second\n\n   1. Nested\n", None, f32::INFINITY, f32::INFINITY);
+        let lines = texts(&pages[0]);
+        let find = |text: &str| *lines.iter().find(|line| line.2 == text).unwrap();
+
+        let theme = MarkdownTheme::default();
+        let item_x = theme.list_indent + LIST_MARKER_GAP;
+        assert_eq!(find("First").0, item_x);
+        assert_eq!(find("Second").0, item_x);
+        assert_eq!(find("Nested").0, 2.0 * item_x);
+
+        // Markers end just before the item text, on the item's baseline
+        for (marker, item) in [("3.", "First"), ("4.", "Second"), ("1.", "Nested")] {
+            let (marker_x, marker_y, ..) = find(marker);
+            let (item_x, item_y, ..) = find(item);
+            assert!(marker_x < item_x && item_x - marker_x < LIST_MARKER_GAP + 6.0);
+            assert_eq!(marker_y, item_y);
+        }
+    }
+
+    #[test]
+    fn nested_quotes_indent_and_draw_a_bar_per_level() {
+        let pages = layout("> Outer\n>\n> > Inner\n", None, f32::INFINITY, f32::INFINITY);
+        let lines = texts(&pages[0]);
+        assert_eq!(lines[0].2, "Outer");
+        assert_eq!(lines[0].0, QUOTE_INDENT);
+        assert_eq!(lines[1].2, "Inner");
+        assert_eq!(lines[1].0, 2.0 * QUOTE_INDENT);
+
+        let bars = quote_bars(&pages[0]);
+        let inner_bars: Vec<f32> = bars.iter().filter(|bar| bar.2 > lines[1].1 - 1.0).map(|bar| bar.0).collect();
+        assert_eq!(inner_bars, [1.0, QUOTE_INDENT + 1.0]);
+        // The outer bar runs unbroken from the first line to the second
+        let outer: Vec<_> = bars.iter().filter(|bar| bar.0 == 1.0).collect();
+        for pair in outer.windows(2) {
+            assert_eq!(pair[0].2, pair[1].1);
+        }
+    }
+
+    #[test]
+    fn long_text_continues_onto_later_pages() {
+        let markdown = (0..12).map(|_| paragraph(40)).collect::<Vec<_>>().join("\n\n");
+        let (first_page, other_pages) = (40.0, 150.0);
+        let pages = layout(&markdown, None, first_page, other_pages);
+        assert!(pages.len() >= 3);
+
+        for (i, page) in pages.iter().enumerate() {
+            let available = if i == 0 { first_page } else { other_pages };
+            let lines = texts(page);
+            assert!(lines.iter().all(|line| line.1 <= available));
+            // Every page starts at its own first baseline
+            assert_eq!(lines[0].1, 0.0);
+        }
+        let expected: Vec<String> = (0..12).flat_map(|_| paragraph(40).split(' ').map(str::to_string).collect::<Vec<_>>()).collect();
+        assert_eq!(words(&pages), expected);
+    }
+
+    #[test]
+    fn quote_bars_restart_at_the_top_of_each_page() {
+        let markdown = format!("> {}\n", paragraph(200));
+        let pages = layout(&markdown, None, 40.0, 40.0);
+        assert!(pages.len() > 1);
+        for page in &pages {
+            let bars = quote_bars(page);
+            assert!(bars[0].1 < 0.0 && bars[0].1 > -5.0);
+            for pair in bars.windows(2) {
+                assert_eq!(pair[0].2, pair[1].1);
+            }
+        }
+    }
+
+    #[test]
+    fn links_are_underlined_and_clickable() {
+        let pages = layout("Visit [our website](https://example.com) today.", None, f32::INFINITY, f32::INFINITY);
+        let underline = pages[0].iter().find_map(|item| match item {
+            MarkdownItem::Underline { x1, x2, .. } => Some((*x1, *x2)),
+            _ => None,
+        });
+        let link = pages[0].iter().find_map(|item| match item {
+            MarkdownItem::Link { x, width, url, .. } => Some((*x, *x + *width, url.as_str())),
+            _ => None,
+        });
+        let (x1, x2) = underline.unwrap();
+        assert_eq!(link, Some((x1, x2, "https://example.com")));
+
+        let visit_end = fonts().regular.width("Visit ", MarkdownTheme::default().body_size);
+        assert!((x1 - visit_end).abs() < 0.01);
+    }
+
+    #[test]
+    fn missing_images_print_their_alt_text() {
+        let dir = std::env::temp_dir().join(format!("markdown-{}", uuid::Uuid::new_v4()));
+        let pages = layout("![A church picnic](picnic.jpg)", Some(&dir), f32::INFINITY, f32::INFINITY);
+        assert!(!pages[0].iter().any(|item| matches!(item, MarkdownItem::Image { .. })));
+        let lines = texts(&pages[0]);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].2, "A church picnic");
+        assert!(lines[0].3 == TextStyle::Italic);
+    }
+}
@@ -0,0 +1,761 @@
+use crate::dates::{parse_import_date, ImportedDate};
+use crate::models::{ImportDateOptions, PartialDate};
+use rusqlite::{params, Connection, Result};
+
+/// One step of the schema history. Steps run in order, each in its own
+/// transaction, and `PRAGMA user_version` records how many have been applied.
+struct Migration {
+    description: &'static str,
+    /// Settings key that marked this step done before migrations were numbered
+    legacy_flag: Option<&'static str>,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+/// The full schema history. Only ever append to this list; a database's
+/// version is the number of entries it has applied.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "Create families, members, import history and settings tables",
+        legacy_flag: None,
+        apply: create_base_tables,
+    },
+    Migration {
+        description: "Add families.mailing_name",
+        legacy_flag: None,
+        apply: |conn| add_column(conn, "families", "mailing_name", "TEXT"),
+    },
+    Migration {
+        description: "Add members.wedding_date",
+        legacy_flag: None,
+        apply: |conn| add_column(conn, "members", "wedding_date", "TEXT"),
+    },
+    Migration {
+        description: "Add families.children",
+        legacy_flag: None,
+        apply: |conn| add_column(conn, "families", "children", "TEXT"),
+    },
+    Migration {
+        description: "Add family alternate address columns",
+        legacy_flag: None,
+        apply: |conn| {
+            for column in ["alt_address", "alt_city", "alt_state", "alt_zip"] {
+                add_column(conn, "families", column, "TEXT")?;
+            }
+            Ok(())
+        },
+    },
+    Migration {
+        description: "Add families.directory_adults and directory_children",
+        legacy_flag: None,
+        apply: |conn| {
+            add_column(conn, "families", "directory_adults", "TEXT")?;
+            add_column(conn, "families", "directory_children", "TEXT")
+        },
+    },
+    Migration {
+        description: "Fill directory_children from children",
+        legacy_flag: None,
+        apply: |conn| {
+            conn.execute(
+                "UPDATE families SET directory_children = children WHERE (directory_children IS NULL OR directory_children = '') AND children IS NOT NULL AND children != ''",
+                [],
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        description: "Fill directory_adults from member first names",
+        legacy_flag: Some("migration_adults_from_members_v2"),
+        apply: fill_directory_adults,
+    },
+    Migration {
+        description: "Create leadership and staff tables",
+        legacy_flag: None,
+        apply: create_leadership_and_staff_tables,
+    },
+    Migration {
+        description: "Add families.sort_order",
+        legacy_flag: None,
+        apply: |conn| add_column(conn, "families", "sort_order", "INTEGER NOT NULL DEFAULT 0"),
+    },
+    Migration {
+        description: "Add families.include_photo_in_directory",
+        legacy_flag: None,
+        apply: |conn| {
+            add_column(conn, "families", "include_photo_in_directory", "INTEGER NOT NULL DEFAULT 1")
+        },
+    },
+    Migration {
+        description: "Add staff.title",
+        legacy_flag: None,
+        apply: |conn| add_column(conn, "staff", "title", "TEXT NOT NULL DEFAULT ''"),
+    },
+    Migration {
+        description: "Add families.photo_crop",
+        legacy_flag: None,
+        apply: |conn| add_column(conn, "families", "photo_crop", "TEXT"),
+    },
+    Migration {
+        description: "Remove members of deleted families",
+        legacy_flag: Some("migration_orphaned_members_v1"),
+        apply: remove_orphaned_members,
+    },
+    Migration {
+        description: "Move leadership names into leadership_people, linked to members",
+        legacy_flag: None,
+        apply: create_leadership_people,
+    },
+    Migration {
+        description: "Create staff_categories from the roles staff already have",
+        legacy_flag: None,
+        apply: create_staff_categories,
+    },
+    Migration {
+        description: "Add staff contact details and bio",
+        legacy_flag: None,
+        apply: |conn| {
+            add_column(conn, "staff", "email", "TEXT")?;
+            add_column(conn, "staff", "phone", "TEXT")?;
+            add_column(conn, "staff", "office_hours", "TEXT")?;
+            add_column(conn, "staff", "bio", "TEXT")
+        },
+    },
+    Migration {
+        description: "Split member birth and wedding dates into month, day and year columns",
+        legacy_flag: None,
+        apply: split_member_dates,
+    },
+];
+
+/// Bring a database up to the current schema, applying each pending migration
+/// in its own transaction
+pub fn migrate(conn: &mut Connection) -> Result<()> {
+    // Schema changes run with foreign keys off, as SQLite recommends, so that
+    // older databases with dangling rows can still be altered
+    let foreign_keys: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+    conn.pragma_update(None, "foreign_keys", false)?;
+    let result = apply_pending(conn);
+    conn.pragma_update(None, "foreign_keys", foreign_keys)?;
+    result
+}
+
+fn apply_pending(conn: &mut Connection) -> Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let applied = usize::try_from(version).unwrap_or(0);
+
+    if applied > MIGRATIONS.len() {
+        log::warn!(
+            "Database schema version {} is newer than this app ({})",
+            applied,
+            MIGRATIONS.len()
+        );
+        return Ok(());
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+        let version = index + 1;
+        let tx = conn.transaction()?;
+
+        let done_before_versioning = match migration.legacy_flag {
+            Some(flag) => legacy_flag_set(&tx, flag)?,
+            None => false,
+        };
+        if !done_before_versioning {
+            log::info!("Applying migration {}: {}", version, migration.description);
+            (migration.apply)(&tx)?;
+        }
+
+        tx.pragma_update(None, "user_version", version as i64)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+/// Whether a pre-versioning one-off migration recorded itself as done
+fn legacy_flag_set(conn: &Connection, flag: &str) -> Result<bool> {
+    let mut stmt = conn.prepare("SELECT 1 FROM settings WHERE key = ? AND value = 'done'")?;
+    stmt.exists(params![flag])
+}
+
+/// Add a column unless it's already there. Databases from before migrations
+/// were numbered start at version 0 with any of these columns in place.
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let exists = conn
+        .prepare("SELECT 1 FROM pragma_table_info(?) WHERE name = ?")?
+        .exists(params![table, column])?;
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn create_base_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS families (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            family_id TEXT NOT NULL UNIQUE,
+            name TEXT NOT NULL,
+            address TEXT,
+            city TEXT,
+            state TEXT,
+            zip TEXT,
+            phone TEXT,
+            email TEXT,
+            photo_path TEXT,
+            notes TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS members (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            family_id INTEGER NOT NULL,
+            first_name TEXT NOT NULL,
+            last_name TEXT NOT NULL,
+            role TEXT,
+            birth_date TEXT,
+            phone TEXT,
+            email TEXT,
+            photo_path TEXT,
+            notes TEXT,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (family_id) REFERENCES families(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS import_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            filename TEXT NOT NULL,
+            imported_at TEXT NOT NULL DEFAULT (datetime('now')),
+            families_created INTEGER NOT NULL DEFAULT 0,
+            families_updated INTEGER NOT NULL DEFAULT 0,
+            members_created INTEGER NOT NULL DEFAULT 0,
+            members_updated INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_families_family_id ON families(family_id);
+        CREATE INDEX IF NOT EXISTS idx_families_name ON families(name);
+        CREATE INDEX IF NOT EXISTS idx_members_family_id ON members(family_id);
+        CREATE INDEX IF NOT EXISTS idx_members_last_name ON members(last_name);
+        ",
+    )
+}
+
+fn create_leadership_and_staff_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS leadership (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ministry TEXT NOT NULL,
+            names TEXT NOT NULL DEFAULT '',
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS staff (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            role TEXT NOT NULL DEFAULT 'staff',
+            photo_path TEXT,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        ",
+    )
+}
+
+/// Populate directory_adults from member first names, leaving out anyone
+/// listed as a child
+fn fill_directory_adults(conn: &Connection) -> Result<()> {
+    let mut family_stmt = conn.prepare("SELECT id, children FROM families")?;
+    let families: Vec<(i64, Option<String>)> = family_stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?)))?
+        .collect::<Result<_>>()?;
+
+    let mut member_stmt = conn.prepare(
+        "SELECT first_name FROM members WHERE family_id = ? ORDER BY sort_order, last_name, first_name",
+    )?;
+
+    for (family_id, children_str) in &families {
+        // Parse children names into a set (lowercase for comparison)
+        let child_names: Vec<String> = children_str
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let member_names: Vec<String> = member_stmt
+            .query_map(params![family_id], |row| row.get::<_, String>(0))?
+            .collect::<Result<_>>()?;
+
+        let adult_names: Vec<&str> = member_names
+            .iter()
+            .filter(|name| !child_names.contains(&name.to_lowercase()))
+            .map(|s| s.as_str())
+            .collect();
+
+        if !adult_names.is_empty() {
+            conn.execute(
+                "UPDATE families SET directory_adults = ? WHERE id = ?",
+                params![adult_names.join(", "), family_id],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Delete members left behind by families deleted while foreign keys were
+/// not enforced
+fn remove_orphaned_members(conn: &Connection) -> Result<()> {
+    let mut orphan_stmt = conn.prepare(
+        "SELECT id, first_name, last_name, family_id FROM members
+         WHERE family_id NOT IN (SELECT id FROM families)",
+    )?;
+    let orphans: Vec<(i64, String, String, i64)> = orphan_stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+        .collect::<Result<_>>()?;
+
+    for (id, first_name, last_name, family_id) in &orphans {
+        log::warn!(
+            "Removing member {} {} (id {}) of missing family {}",
+            first_name,
+            last_name,
+            id,
+            family_id
+        );
+    }
+
+    conn.execute(
+        "DELETE FROM members WHERE family_id NOT IN (SELECT id FROM families)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Split each ministry's one-name-per-line text into leadership_people rows,
+/// linking a name to a member when exactly one member has that full name
+fn create_leadership_people(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE leadership_people (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            leadership_id INTEGER NOT NULL REFERENCES leadership(id) ON DELETE CASCADE,
+            member_id INTEGER REFERENCES members(id) ON DELETE SET NULL,
+            name TEXT NOT NULL,
+            sort_order INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE INDEX idx_leadership_people_leadership_id ON leadership_people(leadership_id);
+        ",
+    )?;
+    add_column(conn, "leadership", "contact", "TEXT")?;
+
+    let mut leadership_stmt = conn.prepare("SELECT id, names FROM leadership")?;
+    let ministries: Vec<(i64, String)> = leadership_stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_>>()?;
+
+    let mut member_stmt = conn.prepare(
+        "SELECT id FROM members WHERE first_name || ' ' || last_name = ? COLLATE NOCASE",
+    )?;
+    let mut insert_stmt = conn.prepare(
+        "INSERT INTO leadership_people (leadership_id, member_id, name, sort_order) VALUES (?, ?, ?, ?)",
+    )?;
+
+    for (leadership_id, names) in &ministries {
+        let names = names.lines().map(str::trim).filter(|name| !name.is_empty());
+        for (index, name) in names.enumerate() {
+            let matches: Vec<i64> = member_stmt
+                .query_map(params![name], |row| row.get(0))?
+                .collect::<Result<_>>()?;
+            let member_id = match matches.as_slice() {
+                [id] => Some(*id),
+                _ => None,
+            };
+            insert_stmt.execute(params![leadership_id, member_id, name, index as i64])?;
+        }
+    }
+
+    conn.execute("ALTER TABLE leadership DROP COLUMN names", [])?;
+    Ok(())
+}
+
+/// Staff roles used to be fixed to pastor, staff and elder. Those keep their
+/// old look on the staff page; any other role in use gets a photo grid
+/// category of its own after them.
+fn create_staff_categories(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE staff_categories (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            key TEXT NOT NULL UNIQUE,
+            title TEXT NOT NULL,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            photo_scale REAL NOT NULL DEFAULT 1.0,
+            layout TEXT NOT NULL DEFAULT 'photo_grid',
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+
+        INSERT INTO staff_categories (key, title, sort_order, layout) VALUES
+            ('pastor', 'Pastor', 0, 'featured'),
+            ('staff', 'Staff', 1, 'list'),
+            ('elder', 'Elders', 2, 'photo_grid');
+        ",
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT role FROM staff
+         WHERE role NOT IN (SELECT key FROM staff_categories)
+         ORDER BY role",
+    )?;
+    let roles: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_>>()?;
+
+    for (index, role) in roles.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO staff_categories (key, title, sort_order) VALUES (?, ?, ?)",
+            params![role, title_case(role), 3 + index as i64],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Dates used to be free text: "MM-DD" or "YYYY-MM-DD" from the app, or
+/// whatever an older import couldn't make sense of. Text that can't be read
+/// as a date is logged, kept at the end of the member's notes and recorded in
+/// unreadable_member_dates so preflight can point it out.
+fn split_member_dates(conn: &Connection) -> Result<()> {
+    for prefix in ["birth", "wedding"] {
+        add_column(conn, "members", &format!("{}_month", prefix), "INTEGER")?;
+        add_column(conn, "members", &format!("{}_day", prefix), "INTEGER")?;
+        add_column(conn, "members", &format!("{}_year", prefix), "INTEGER")?;
+    }
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS unreadable_member_dates (
+            member_id INTEGER NOT NULL,
+            field TEXT NOT NULL,
+            value TEXT NOT NULL,
+            FOREIGN KEY (member_id) REFERENCES members(id) ON DELETE CASCADE
+        );",
+    )?;
+
+    let mut stmt = conn.prepare(
+        // SQLite keeps whatever type a value was written with, so read
+        // numeric dates (Excel serials) as text too
+        "SELECT id, first_name, last_name, CAST(birth_date AS TEXT), CAST(wedding_date AS TEXT), notes FROM members
+         WHERE birth_date != '' OR wedding_date != ''",
+    )?;
+    type DateRow = (i64, String, String, Option<String>, Option<String>, Option<String>);
+    let members: Vec<DateRow> = stmt
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+        })?
+        .collect::<Result<_>>()?;
+
+    for (id, first_name, last_name, birth_date, wedding_date, notes) in members {
+        let mut unreadable = Vec::new();
+        for (prefix, label, value) in [("birth", "Birth date", birth_date), ("wedding", "Wedding date", wedding_date)] {
+            let Some(value) = value.filter(|v| !v.trim().is_empty()) else {
+                continue;
+            };
+            let date = PartialDate::parse(&value).or_else(|| {
+                match parse_import_date(&value, &ImportDateOptions::default()) {
+                    ImportedDate::Exact(date) | ImportedDate::Ambiguous { value: date, .. } => Some(date),
+                    ImportedDate::Invalid => None,
+                }
+            });
+            match date {
+                Some(date) => {
+                    conn.execute(
+                        &format!("UPDATE members SET {0}_month = ?, {0}_day = ?, {0}_year = ? WHERE id = ?", prefix),
+                        params![date.month, date.day, date.year, id],
+                    )?;
+                }
+                None => {
+                    log::warn!(
+                        "Unrecognized {} \"{}\" for member {} {} (id {}) moved to notes",
+                        label.to_lowercase(),
+                        value,
+                        first_name,
+                        last_name,
+                        id
+                    );
+                    conn.execute(
+                        "INSERT INTO unreadable_member_dates (member_id, field, value) VALUES (?, ?, ?)",
+                        params![id, format!("{}_date", prefix), value],
+                    )?;
+                    unreadable.push(format!("{}: {}", label, value));
+                }
+            }
+        }
+
+        if !unreadable.is_empty() {
+            let notes = notes
+                .filter(|n| !n.trim().is_empty())
+                .into_iter()
+                .chain(unreadable)
+                .collect::<Vec<_>>()
+                .join("\n");
+            conn.execute("UPDATE members SET notes = ? WHERE id = ?", params![notes, id])?;
+        }
+    }
+
+    conn.execute("ALTER TABLE members DROP COLUMN birth_date", [])?;
+    conn.execute("ALTER TABLE members DROP COLUMN wedding_date", [])?;
+    Ok(())
+}
+
+/// "youth_pastor" -> "Youth Pastor"
+fn title_case(key: &str) -> String {
+    key.split(|c: char| c == '_' || c == '-' || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Column names and definitions of every table, sorted so that columns
+    /// added by ALTER TABLE compare equal to ones from CREATE TABLE
+    fn schema(conn: &Connection) -> Vec<(String, Vec<String>)> {
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+            .unwrap();
+        let tables: Vec<String> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+
+        tables
+            .into_iter()
+            .map(|table| {
+                let mut stmt = conn
+                    .prepare("SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?)")
+                    .unwrap();
+                let mut columns: Vec<String> = stmt
+                    .query_map(params![table], |row| {
+                        Ok(format!(
+                            "{} {} notnull={} default={:?} pk={}",
+                            row.get::<_, String>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, i64>(2)?,
+                            row.get::<_, Option<String>>(3)?,
+                            row.get::<_, i64>(4)?,
+                        ))
+                    })
+                    .unwrap()
+                    .map(|r| r.unwrap())
+                    .collect();
+                columns.sort();
+                (table, columns)
+            })
+            .collect()
+    }
+
+    fn user_version(conn: &Connection) -> usize {
+        conn.query_row("PRAGMA user_version", [], |row| row.get::<_, i64>(0))
+            .unwrap() as usize
+    }
+
+    /// Load a fixture the way an old app version would have left it, with
+    /// foreign keys unenforced, then migrate it with them on as the app does
+    fn upgrade(fixture: &str) -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "foreign_keys", false).unwrap();
+        conn.execute_batch(fixture).unwrap();
+        conn.pragma_update(None, "foreign_keys", true).unwrap();
+        migrate(&mut conn).unwrap();
+        conn
+    }
+
+    fn current_schema() -> Vec<(String, Vec<String>)> {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        schema(&conn)
+    }
+
+    fn family_names(conn: &Connection, family_id: &str) -> (Option<String>, Option<String>) {
+        conn.query_row(
+            "SELECT directory_adults, directory_children FROM families WHERE family_id = ?",
+            params![family_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap()
+    }
+
+    fn member_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM members", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn fresh_database_reaches_current_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+
+        // Running again is a no-op
+        migrate(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn upgrades_initial_schema() {
+        let conn = upgrade(include_str!("../tests/fixtures/schema_initial.sql"));
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+        assert_eq!(schema(&conn), current_schema());
+
+        assert_eq!(family_names(&conn, "F001"), (Some("Jane, John".into()), None));
+        // The member of the deleted family is gone
+        assert_eq!(member_count(&conn), 3);
+
+        // Dates move into their own columns; text that isn't a date goes to
+        // the notes
+        let mut stmt = conn
+            .prepare("SELECT first_name, birth_month, birth_day, birth_year, notes FROM members ORDER BY id")
+            .unwrap();
+        type MemberDates = (String, Option<u32>, Option<u32>, Option<i32>, Option<String>);
+        let dates: Vec<MemberDates> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(
+            dates,
+            vec![
+                ("John".into(), Some(3), Some(14), Some(1970), None),
+                ("Jane".into(), Some(7), Some(1), None, None),
+                ("Mary".into(), None, None, None, Some("Birth date: sometime in May".into())),
+            ]
+        );
+        let unreadable: (String, String) = conn
+            .query_row(
+                "SELECT m.first_name, u.field FROM unreadable_member_dates u JOIN members m ON m.id = u.member_id",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(unreadable, ("Mary".into(), "birth_date".into()));
+    }
+
+    #[test]
+    fn upgrades_dates_stored_as_numbers() {
+        let fixture = format!(
+            "{}\nINSERT INTO members (family_id, first_name, last_name, birth_date) VALUES (1, 'Sam', 'Smith', 29000);",
+            include_str!("../tests/fixtures/schema_initial.sql")
+        );
+        let conn = upgrade(&fixture);
+        let date: (Option<u32>, Option<u32>, Option<i32>) = conn
+            .query_row(
+                "SELECT birth_month, birth_day, birth_year FROM members WHERE first_name = 'Sam'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(date, (Some(5), Some(25), Some(1979)));
+    }
+
+    #[test]
+    fn upgrades_alt_address_schema() {
+        let conn = upgrade(include_str!("../tests/fixtures/schema_alt_address.sql"));
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+        assert_eq!(schema(&conn), current_schema());
+
+        assert_eq!(
+            family_names(&conn, "F001"),
+            (Some("Bob, Ann".into()), Some("Tim".into()))
+        );
+        let title: String = conn
+            .query_row("SELECT title FROM staff WHERE name = 'Pat Lee'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(title, "");
+
+        // Names matching a member are linked; the rest stay free text
+        let mut stmt = conn
+            .prepare("SELECT member_id, name FROM leadership_people ORDER BY sort_order")
+            .unwrap();
+        let people: Vec<(Option<i64>, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(people, vec![(Some(1), "Bob Brown".into()), (None, "Sue Smith".into())]);
+
+        // Roles beyond the original three get a category after them
+        let mut stmt = conn
+            .prepare("SELECT key, title, layout FROM staff_categories ORDER BY sort_order")
+            .unwrap();
+        let categories: Vec<(String, String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(
+            categories,
+            vec![
+                ("pastor".into(), "Pastor".into(), "featured".into()),
+                ("staff".into(), "Staff".into(), "list".into()),
+                ("elder".into(), "Elders".into(), "photo_grid".into()),
+                ("deacon".into(), "Deacon".into(), "photo_grid".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn upgrades_directory_names_schema() {
+        let conn = upgrade(include_str!("../tests/fixtures/schema_directory_names.sql"));
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+        assert_eq!(schema(&conn), current_schema());
+
+        // Already filled before versioning, so hand edits are kept
+        assert_eq!(
+            family_names(&conn, "F001"),
+            (Some("Robert & Ann".into()), Some("Tim".into()))
+        );
+    }
+
+    #[test]
+    fn upgrades_photo_crop_schema() {
+        let conn = upgrade(include_str!("../tests/fixtures/schema_photo_crop.sql"));
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+        assert_eq!(schema(&conn), current_schema());
+
+        let photo_crop: Option<String> = conn
+            .query_row("SELECT photo_crop FROM families WHERE family_id = 'F001'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(photo_crop.as_deref(), Some(r#"{"mode":"focus","x":0.5,"y":0.25}"#));
+        assert_eq!(member_count(&conn), 2);
+    }
+}
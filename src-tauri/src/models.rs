@@ -1,4 +1,9 @@
+use chrono::NaiveDate;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::Row;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::hash::{Hash, Hasher};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Family {
@@ -21,10 +26,52 @@ pub struct Family {
     pub directory_adults: Option<String>,
     pub directory_children: Option<String>,
     pub include_photo_in_directory: bool,
+    pub photo_crop: Option<PhotoCrop>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// How a family photo is framed wherever it gets cropped (photo grid,
+/// avatars). Coordinates are fractions (0-1) of the original image's width
+/// and height, measured from the top-left.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum PhotoCrop {
+    /// Keep this point as near the center of each crop as the photo allows
+    Focus { x: f32, y: f32 },
+    /// Use only this region of the photo, centered in any further crop
+    Rect { x: f32, y: f32, width: f32, height: f32 },
+}
+
+// Crops compare by exact value so prepared images can be keyed by them
+impl Eq for PhotoCrop {}
+
+impl Hash for PhotoCrop {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            PhotoCrop::Focus { x, y } => [x, y].map(f32::to_bits).hash(state),
+            PhotoCrop::Rect { x, y, width, height } => {
+                [x, y, width, height].map(f32::to_bits).hash(state)
+            }
+        }
+    }
+}
+
+// Stored as JSON text in families.photo_crop
+impl ToSql for PhotoCrop {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        Ok(ToSqlOutput::from(json))
+    }
+}
+
+impl FromSql for PhotoCrop {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        serde_json::from_str(value.as_str()?).map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FamilyInput {
     pub family_id: String,
@@ -73,16 +120,61 @@ pub struct FamilyUpdate {
     pub directory_adults: Option<String>,
     pub directory_children: Option<String>,
     pub include_photo_in_directory: Option<bool>,
+    /// Replacing the photo without setting this clears the old framing
+    #[serde(default, deserialize_with = "deserialize_nullable_field")]
+    pub photo_crop: Option<Option<PhotoCrop>>,
 }
 
-fn deserialize_nullable_field<'de, D>(deserializer: D) -> Result<Option<Option<String>>, D::Error>
+fn deserialize_nullable_field<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
 where
     D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
 {
     // If the field is present, deserialize its value (which may be null)
     Ok(Some(Option::deserialize(deserializer)?))
 }
 
+/// Non-destructive edits applied, in field order, to a photo's original to
+/// produce its display, `_full` and `_thumb` variants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhotoEdits {
+    /// Turn the photo upright using the camera's EXIF orientation
+    pub auto_orient: bool,
+    /// Clockwise rotation in degrees: 0, 90, 180 or 270
+    pub rotation: u16,
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+    /// Region to keep, in fractions (0-1) of the rotated and flipped photo
+    pub crop: Option<CropRegion>,
+    /// Exposure change in stops, from -2 to 2
+    pub exposure: f32,
+    /// Contrast change in percent, from -100 to 100
+    pub contrast: f32,
+}
+
+impl Default for PhotoEdits {
+    fn default() -> Self {
+        PhotoEdits {
+            auto_orient: true,
+            rotation: 0,
+            flip_horizontal: false,
+            flip_vertical: false,
+            crop: None,
+            exposure: 0.0,
+            contrast: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CropRegion {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Member {
     pub id: i64,
@@ -90,8 +182,8 @@ pub struct Member {
     pub first_name: String,
     pub last_name: String,
     pub role: Option<String>,
-    pub birth_date: Option<String>,
-    pub wedding_date: Option<String>,
+    pub birth_date: Option<PartialDate>,
+    pub wedding_date: Option<PartialDate>,
     pub phone: Option<String>,
     pub email: Option<String>,
     pub photo_path: Option<String>,
@@ -101,6 +193,91 @@ pub struct Member {
     pub updated_at: String,
 }
 
+/// A birthday or anniversary: month and day, plus the year when it's known.
+/// Sent to the frontend as "MM-DD" or "YYYY-MM-DD" and stored in separate
+/// month, day and year columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PartialDate {
+    pub month: u32,
+    pub day: u32,
+    pub year: Option<i32>,
+}
+
+impl PartialDate {
+    /// The date, if it's on the calendar. February 29 is allowed without a
+    /// year.
+    pub fn new(month: u32, day: u32, year: Option<i32>) -> Option<Self> {
+        // 2000 was a leap year
+        NaiveDate::from_ymd_opt(year.unwrap_or(2000), month, day)?;
+        Some(Self { month, day, year })
+    }
+
+    /// Parse "MM-DD" or "YYYY-MM-DD"
+    pub fn parse(s: &str) -> Option<Self> {
+        let number = |part: &str| -> Option<u32> {
+            if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            part.parse().ok()
+        };
+        match s.trim().split('-').collect::<Vec<_>>().as_slice() {
+            [month, day] if month.len() <= 2 && day.len() <= 2 => Self::new(number(month)?, number(day)?, None),
+            [year, month, day] if year.len() == 4 && month.len() <= 2 && day.len() <= 2 => {
+                Self::new(number(month)?, number(day)?, Some(number(year)? as i32))
+            }
+            _ => None,
+        }
+    }
+
+    /// Read a date stored as month, day and year columns, starting at `idx`
+    pub fn from_row(row: &Row, idx: usize) -> rusqlite::Result<Option<Self>> {
+        let month: Option<u32> = row.get(idx)?;
+        let day: Option<u32> = row.get(idx + 1)?;
+        let year: Option<i32> = row.get(idx + 2)?;
+        Ok(match (month, day) {
+            (Some(month), Some(day)) => Some(Self { month, day, year }),
+            _ => None,
+        })
+    }
+
+    /// 0-based month, for indexing month tables
+    pub fn month_idx(&self) -> usize {
+        self.month as usize - 1
+    }
+
+    /// Years since the date as of its anniversary in `year`: the age turned
+    /// or years married. None without a known year or before the date.
+    pub fn years_in(&self, year: i32) -> Option<u32> {
+        self.year
+            .and_then(|since| u32::try_from(year - since).ok())
+            .filter(|&years| years > 0)
+    }
+}
+
+impl fmt::Display for PartialDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.year {
+            Some(year) => write!(f, "{:04}-{:02}-{:02}", year, self.month, self.day),
+            None => write!(f, "{:02}-{:02}", self.month, self.day),
+        }
+    }
+}
+
+impl TryFrom<String> for PartialDate {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s).ok_or_else(|| format!("\"{}\" isn't a valid date. Use MM-DD or YYYY-MM-DD.", s))
+    }
+}
+
+impl From<PartialDate> for String {
+    fn from(date: PartialDate) -> Self {
+        date.to_string()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemberInput {
     pub family_id: i64,
@@ -161,12 +338,22 @@ pub struct Settings {
     pub church_phone: Option<String>,
     pub church_email: Option<String>,
     pub church_website: Option<String>,
+    pub pdf_font_regular: Option<String>,
+    pub pdf_font_bold: Option<String>,
+    pub pdf_font_italic: Option<String>,
+    /// Language the directory is printed in: "en", "es" or "ko"
+    #[serde(default = "default_locale")]
+    pub locale: String,
 }
 
 fn default_theme() -> String {
     "system".to_string()
 }
 
+fn default_locale() -> String {
+    "en".to_string()
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -191,6 +378,10 @@ impl Default for Settings {
             church_phone: None,
             church_email: None,
             church_website: None,
+            pdf_font_regular: None,
+            pdf_font_bold: None,
+            pdf_font_italic: None,
+            locale: default_locale(),
         }
     }
 }
@@ -201,6 +392,54 @@ pub struct ImportPreview {
     pub total_families: usize,
     pub total_members: usize,
     pub duplicates: Vec<DuplicateMatch>,
+    pub date_issues: Vec<ImportDateIssue>,
+}
+
+/// How the import reads dates in the spreadsheet
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ImportDateOptions {
+    #[serde(default)]
+    pub order: DateOrder,
+    /// Two-digit years at or below this are read as 20xx, the rest as 19xx
+    #[serde(default = "default_century_pivot")]
+    pub century_pivot: u32,
+}
+
+impl Default for ImportDateOptions {
+    fn default() -> Self {
+        Self {
+            order: DateOrder::default(),
+            century_pivot: default_century_pivot(),
+        }
+    }
+}
+
+fn default_century_pivot() -> u32 {
+    50
+}
+
+/// Which number comes first in an all-numeric date like "03/04/1980"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateOrder {
+    #[default]
+    MonthFirst,
+    DayFirst,
+}
+
+/// A birth or wedding date the import couldn't read with certainty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportDateIssue {
+    pub family_id: String,
+    pub member_name: String,
+    /// "birth_date" or "wedding_date"
+    pub field: String,
+    pub value: String,
+    /// "ambiguous" (imported as `imported_as`, though `alternative` also
+    /// fits) or "invalid" (left blank)
+    pub kind: String,
+    pub imported_as: Option<String>,
+    pub alternative: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -250,23 +489,106 @@ pub struct PdfOptions {
     pub first_page_markdown: Option<String>,
     pub back_cover_image_path: Option<String>,
     pub celebration_image_path: Option<String>,
+    /// Front cover laid out from the church logo and contact details when no
+    /// cover image is set: "centered" (default), "left_aligned" or "none"
+    pub cover_template: Option<String>,
+    /// Back cover layout when no back cover image is set, with the same choices
+    pub back_cover_template: Option<String>,
     pub photo_grid_rows: Option<usize>,
+    #[serde(default)]
+    pub include_table_of_contents: bool,
+    /// Add "Meet Our Staff" pages with the photo and bio of each staff member
+    /// who has a bio
+    #[serde(default)]
+    pub include_staff_bios: bool,
+    /// Follow every anniversary with a known year by "25th anniversary", not
+    /// just the milestone ones
+    #[serde(default)]
+    pub show_anniversary_years: bool,
+    /// Call out 80th, 90th and 100th-and-later birthdays
+    #[serde(default)]
+    pub highlight_birthday_milestones: bool,
+    /// Year anniversaries and ages are counted for; defaults to this year
+    pub celebrations_year: Option<i32>,
+    /// Photo resolution/compression preset: "print" (default), "screen" or "email"
+    pub image_quality: Option<String>,
+    /// Bleed in mm added around the trim of each sheet for print-shop output
+    pub bleed_mm: Option<f32>,
+    /// Draw crop, fold and registration marks outside the trim
+    pub crop_marks: Option<bool>,
+    /// Write a PDF/X-3 file: CMYK colors and photos, an output intent and no links
+    pub pdf_x: Option<bool>,
+    /// Sheet layout: "saddle_stitch" (default), "perfect_bound", "two_up" or "four_up"
+    pub imposition: Option<String>,
+    /// Sheets per folded signature for perfect binding
+    pub signature_sheets: Option<usize>,
+    /// Print both sides of each sheet (default) rather than one side at a time
+    pub duplex: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfPagePreview {
+    /// 1-based page number in reading order
+    pub page_number: usize,
+    /// PNG image as a base64 data URI
+    pub image: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfPreview {
+    /// Total booklet pages, including blank padding pages
+    pub page_count: usize,
+    pub pages: Vec<PdfPagePreview>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightIssue {
+    /// "error" when content will be missing from the booklet, "warning" when it
+    /// will print but may look wrong
+    pub severity: String,
+    pub category: String,
+    pub family_id: Option<i64>,
+    pub member_id: Option<i64>,
+    pub staff_id: Option<i64>,
+    /// Family, member, staff or image name the issue is about
+    pub subject: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightReport {
+    pub family_count: usize,
+    pub issues: Vec<PreflightIssue>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Leadership {
     pub id: i64,
     pub ministry: String,
-    pub names: String,
+    pub people: Vec<LeadershipPerson>,
+    /// Who to contact about the ministry, shown in its own column
+    pub contact: Option<String>,
     pub sort_order: i32,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// Someone serving in a ministry: a directory member, or a name typed in for
+/// people who aren't in the directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeadershipPerson {
+    pub member_id: Option<i64>,
+    /// The member's current name when linked. Kept as a fallback in case the
+    /// member is deleted.
+    pub name: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LeadershipInput {
     pub ministry: String,
-    pub names: String,
+    #[serde(default)]
+    pub people: Vec<LeadershipPerson>,
+    pub contact: Option<String>,
     #[serde(default)]
     pub sort_order: i32,
 }
@@ -278,6 +600,11 @@ pub struct Staff {
     pub title: String,
     pub role: String,
     pub photo_path: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub office_hours: Option<String>,
+    /// Markdown, printed on the "Meet Our Staff" pages
+    pub bio: Option<String>,
     pub sort_order: i32,
     pub created_at: String,
     pub updated_at: String,
@@ -290,9 +617,47 @@ pub struct StaffInput {
     pub title: String,
     pub role: String,
     #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub office_hours: Option<String>,
+    #[serde(default)]
+    pub bio: Option<String>,
+    #[serde(default)]
     pub sort_order: i32,
 }
 
+/// A group on the staff page. Staff belong to one through `Staff.role`, which
+/// holds the category's key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaffCategory {
+    pub id: i64,
+    pub key: String,
+    pub title: String,
+    pub sort_order: i32,
+    /// Multiplier on the theme's staff photo size
+    pub photo_scale: f64,
+    /// "featured", "photo_grid" or "list"
+    pub layout: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaffCategoryInput {
+    pub title: String,
+    #[serde(default = "default_photo_scale")]
+    pub photo_scale: f64,
+    pub layout: String,
+    #[serde(default)]
+    pub sort_order: i32,
+}
+
+fn default_photo_scale() -> f64 {
+    1.0
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupManifest {
     pub version: String,
@@ -302,3 +667,79 @@ pub struct BackupManifest {
     pub member_count: usize,
     pub photo_count: usize,
 }
+
+/// A row whose foreign key points at a row that doesn't exist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignKeyViolation {
+    pub table: String,
+    pub row_id: Option<i64>,
+    pub parent_table: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseIntegrityReport {
+    pub ok: bool,
+    pub foreign_keys_enabled: bool,
+    /// Problems reported by `PRAGMA integrity_check`
+    pub integrity_errors: Vec<String>,
+    pub foreign_key_violations: Vec<ForeignKeyViolation>,
+}
+
+/// A file under the photos folder, relative to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredPhotoFile {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// A database row or setting that names a photo file which no longer exists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingPhotoReference {
+    /// "family", "member", "staff" or "setting"
+    pub source: String,
+    pub record_id: Option<i64>,
+    pub setting_key: Option<String>,
+    /// Family, member or staff name, or a description of the setting
+    pub subject: String,
+    pub photo_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoStorageReport {
+    pub file_count: usize,
+    pub total_bytes: u64,
+    /// Files no photo, variant or original in the database accounts for
+    pub orphaned_files: Vec<StoredPhotoFile>,
+    pub orphaned_bytes: u64,
+    pub missing_files: Vec<MissingPhotoReference>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoCleanupResult {
+    pub dry_run: bool,
+    /// Files removed, or that would be removed on a dry run
+    pub removed_files: Vec<StoredPhotoFile>,
+    pub bytes_reclaimed: u64,
+}
+
+/// Payload of the "job-progress" event emitted by background jobs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobProgress {
+    pub job_id: String,
+    /// What is being counted, e.g. "pages", "images", "bytes", "files", "families"
+    pub stage: String,
+    pub current: u64,
+    /// None when the total isn't known up front
+    pub total: Option<u64>,
+}
+
+/// Payload of the "job-finished" event emitted once per background job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobFinished {
+    pub job_id: String,
+    /// "completed", "failed" or "cancelled"
+    pub status: String,
+    /// The command's return value when completed
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
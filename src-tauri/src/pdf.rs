@@ -1,11 +1,28 @@
+use crate::canvas::{MeasureCanvas, PageCanvas, PdfCanvas, RasterCanvas};
+use crate::commands::{leadership_people, query_staff_categories};
 use crate::db::Database;
-use crate::models::{Family, FamilyWithMembers, Member, PdfOptions};
+use crate::fonts::{EmbeddedFonts, FontData, LoadedFont, PdfFonts, RasterFonts};
+use crate::images::{
+    grid_crop_size, prepare_images, ImageCache, ImageQuality, ImageRequest, ImageTreatment,
+    PreparedImage, PreparedImages, GRID_PHOTO_ASPECT, STAFF_PHOTO_ASPECT,
+};
+use crate::imposition::{Imposition, Slot};
+use crate::jobs::{spawn_job, JobContext, ProgressWriter};
+use crate::locale::{Locale, Text};
+use crate::markdown::{draw_items, MarkdownFlow, MarkdownItem};
+use crate::theme::{BorderStyle, PdfTheme, PhotoTheme};
+use crate::models::{
+    Family, FamilyWithMembers, Member, PartialDate, PdfOptions, PdfPagePreview, PdfPreview,
+    PhotoCrop, PreflightIssue, PreflightReport,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{Datelike, Local};
 use printpdf::*;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use std::fs::File;
-use std::io::BufWriter;
-use std::path::PathBuf;
-use tauri::{AppHandle, State};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
 
 // Content page dimensions (half of US Legal sheet folded)
 const PAGE_WIDTH_MM: f32 = 177.8; // 7 inches
@@ -15,55 +32,46 @@ const PAGE_HEIGHT_MM: f32 = 215.9; // 8.5 inches
 const SHEET_WIDTH_MM: f32 = 355.6; // 14 inches
 const SHEET_HEIGHT_MM: f32 = 215.9; // 8.5 inches
 
+// Print-shop output: bleed past the trim, and printer's marks in the slug
+// beyond it. Marks never start inside the bleed.
+const MAX_BLEED_MM: f32 = 12.7; // 1/2 inch
+const CROP_MARK_MIN_OFFSET_MM: f32 = 3.0;
+const CROP_MARK_LENGTH_MM: f32 = 6.0;
+const REGISTRATION_MARK_RADIUS_MM: f32 = 2.5;
+const MARK_THICKNESS_PT: f32 = 0.25;
+// Crop and registration marks are drawn in plain black
+const REGISTRATION_COLOR: &str = "#000000";
+
 // Photo grid layout: 2 columns x 4 rows
-const GRID_MARGIN_X: f32 = 8.0;
-const GRID_MARGIN_TOP: f32 = 8.0;
-const GRID_MARGIN_BOTTOM: f32 = 1.0;
 const GRID_COLS: usize = 2;
 const GRID_ROWS: usize = 5;
-const GRID_CAPTION_HEIGHT: f32 = 9.0; // Space below photo for name+adults line + children line
-const GRID_COL_SPACING: f32 = 4.0;
-const GRID_ROW_SPACING: f32 = 0.75;
-const GRID_CELL_PADDING: f32 = 0.5;
-// Uniform aspect ratio for photo grid — matches Peters reference photo (6637 x 4427)
-const GRID_PHOTO_ASPECT: f32 = 6637.0 / 4427.0;
+
+// Preview rendering resolution
+const PREVIEW_DEFAULT_DPI: f32 = 96.0;
+const PREVIEW_MAX_DPI: f32 = 300.0;
+
+// Photos printing below this effective resolution are flagged by preflight
+const PREFLIGHT_MIN_DPI: f32 = 150.0;
 
 // Text card layout
-const CARD_MARGIN: f32 = 15.0;
 const CARD_COLS: usize = 2;
-const CARD_COL_GAP: f32 = 10.0;
-const CARD_SPACING: f32 = 6.0;
-const CARD_NAME_SIZE: f32 = 11.0;
-const CARD_TEXT_SIZE: f32 = 9.0;
-const CARD_LINE_HEIGHT: f32 = 4.5;
-
-// Font sizes
-const GRID_NAME_SIZE: f32 = 9.0;
-
-// Celebration page layout
-const CELEB_MARGIN_TOP: f32 = 15.0;
-const CELEB_MARGIN_BOTTOM: f32 = 17.0;
-const CELEB_TITLE_SIZE: f32 = 16.0;
-const CELEB_MONTH_SIZE: f32 = 12.0;
-const CELEB_SECTION_SIZE: f32 = 8.0;
-const CELEB_TEXT_SIZE: f32 = 10.0;
-const CELEB_LINE_HEIGHT: f32 = 4.6;
-const CELEB_MONTH_SPACING: f32 = 6.0;
-const CELEB_SECTION_SPACING: f32 = 3.0;
-const CELEB_COL_GAP: f32 = 8.0;
+
+// Line height in mm per point of church name on generated covers
+const COVER_NAME_LEADING: f32 = 1.3 * 25.4 / 72.0;
 
 /// Get the full resolution path for a photo (used for print quality PDFs)
-fn get_full_resolution_path(image_path: &PathBuf) -> PathBuf {
+pub fn get_full_resolution_path(image_path: &Path) -> PathBuf {
     let stem = image_path.file_stem().unwrap_or_default().to_string_lossy();
-    let parent = image_path.parent().unwrap_or(std::path::Path::new(""));
+    let parent = image_path.parent().unwrap_or(Path::new(""));
     parent.join(format!("{}_full.jpg", stem))
 }
 
 // Content page types for the two-pass approach
 enum ContentPage {
-    FullImage(PathBuf),
+    FullImage { image_path: PathBuf, bleed: f32 },
     CoverImage {
         image_path: PathBuf,
+        bleed: f32,
         title_line1: String,
         title_line2: String,
         title_color: String,
@@ -71,49 +79,670 @@ enum ContentPage {
     PhotoGrid { entries: Vec<PhotoGridEntry>, grid_rows: usize },
     TextCards([Vec<FamilyCardData>; 2]),
     CelebrationPages(CelebPage),
-    MarkdownContent(String),
-    FirstInsidePage {
-        pastor_letter: Option<String>,
-        mission_statement: Option<String>,
-    },
-    StaffPage(Vec<StaffEntry>),
-    LeadershipPage {
-        entries: Vec<LeadershipEntry>,
-        contact_left: Vec<String>,
-        contact_right: Vec<String>,
+    /// One page of the pastor's letter or first-page Markdown
+    Markdown(Vec<MarkdownItem>),
+    StaffPage(Vec<StaffBlock>),
+    StaffBios(StaffBioPage),
+    TableOfContents(Vec<TocEntry>),
+    LeadershipPage(LeadershipPage),
+    /// Front cover composed from the church logo and details
+    TemplateCover {
+        layout: CoverLayout,
+        church: ChurchIdentity,
+        title_line1: String,
+        title_line2: String,
     },
+    /// Back cover composed from the church logo and details
+    TemplateBackCover { layout: CoverLayout, church: ChurchIdentity },
     Blank,
 }
 
+/// How a generated cover arranges the church logo and details
+#[derive(Clone, Copy, PartialEq)]
+enum CoverLayout {
+    Centered,
+    /// Flush left against an accent bar
+    LeftAligned,
+}
+
+impl CoverLayout {
+    /// Look up a layout by name: "centered" (default), "left_aligned", or
+    /// "none" to leave the cover blank
+    fn from_name(name: Option<&str>) -> Result<Option<Self>, String> {
+        match name.unwrap_or("centered") {
+            "centered" => Ok(Some(CoverLayout::Centered)),
+            "left_aligned" => Ok(Some(CoverLayout::LeftAligned)),
+            "none" => Ok(None),
+            other => Err(format!("Unknown cover template: {}", other)),
+        }
+    }
+}
+
+/// The church's name, logo and contact details from settings
+#[derive(Clone)]
+struct ChurchIdentity {
+    name: String,
+    logo_path: Option<PathBuf>,
+    address_lines: Vec<String>,
+    phone_lines: Vec<String>,
+    email: Option<String>,
+    website: Option<String>,
+}
+
+impl ChurchIdentity {
+    fn load(conn: &Connection, name: &str, photos_dir: &Path) -> Self {
+        let setting = |key: &str| {
+            conn.query_row("SELECT value FROM settings WHERE key = ?", params![key], |row| {
+                row.get::<_, String>(0)
+            })
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+        };
+        let lines = |key: &str| -> Vec<String> {
+            setting(key)
+                .map(|value| {
+                    value
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        // The logo is stored in the photos root, with a full-resolution variant
+        let logo_path = setting("church_logo_path").and_then(|filename| {
+            let base = photos_dir.join(filename);
+            let full_res = get_full_resolution_path(&base);
+            [full_res, base].into_iter().find(|path| path.exists())
+        });
+
+        ChurchIdentity {
+            name: name.trim().to_string(),
+            logo_path,
+            address_lines: lines("church_address"),
+            phone_lines: lines("church_phone"),
+            email: setting("church_email"),
+            website: setting("church_website"),
+        }
+    }
+
+    /// Every contact line: address, phone, email, then website
+    fn contact_lines(&self) -> Vec<String> {
+        let (mut left, right) = self.contact_columns();
+        left.extend(right);
+        left
+    }
+
+    /// Address and phone on the left, email and website on the right
+    fn contact_columns(&self) -> (Vec<String>, Vec<String>) {
+        let left = self.address_lines.iter().chain(&self.phone_lines).cloned().collect();
+        let right = self.email.iter().chain(&self.website).cloned().collect();
+        (left, right)
+    }
+}
+
+/// A section listed on the table-of-contents page, with its 1-based start page
+struct TocEntry {
+    title: String,
+    page: usize,
+}
+
 struct LeadershipEntry {
     ministry: String,
-    names: String,
+    names: Vec<String>,
+    contact: Option<String>,
 }
 
+impl LeadershipEntry {
+    /// The names as one comma-separated run wrapped to the names column, and
+    /// the contact wrapped to the contact column
+    fn lines(&self, columns: &LeadershipColumns, theme: &PdfTheme, fonts: &PdfFonts) -> (Vec<String>, Vec<String>) {
+        let size = theme.leadership.names_size;
+        let names = wrap_text(&self.names.join(", "), &fonts.regular, size, columns.names_width);
+        let contact = match (&self.contact, columns.contact_width) {
+            (Some(contact), Some(width)) => wrap_text(contact, &fonts.regular, size, width),
+            _ => Vec::new(),
+        };
+        (names, contact)
+    }
+
+    /// Vertical space from this row's baseline to the next row's
+    fn height(&self, columns: &LeadershipColumns, theme: &PdfTheme, fonts: &PdfFonts) -> f32 {
+        let (names, contact) = self.lines(columns, theme, fonts);
+        let extra_lines = names.len().max(contact.len()).max(1) - 1;
+        theme.leadership.row_spacing + extra_lines as f32 * theme.leadership.names_line_height
+    }
+}
+
+/// Horizontal layout of the leadership page, in mm from the left margin
+#[derive(Clone, Copy)]
+struct LeadershipColumns {
+    names_x: f32,
+    names_width: f32,
+    contact_x: f32,
+    /// Set when some ministry has a contact to show
+    contact_width: Option<f32>,
+}
+
+impl LeadershipColumns {
+    const GAP: f32 = 4.0;
+
+    /// Names start at the theme's indent. A contact column takes its width
+    /// from the names, so the ministry column shrinks to its widest entry
+    /// to give some back.
+    fn new(entries: &[LeadershipEntry], theme: &PdfTheme, fonts: &PdfFonts) -> Self {
+        let lead = &theme.leadership;
+        let usable_width = PAGE_WIDTH_MM - 2.0 * lead.margin_x;
+        let contact_width = entries
+            .iter()
+            .any(|entry| entry.contact.is_some())
+            .then_some(lead.contact_column_width);
+
+        let names_x = match contact_width {
+            Some(_) => entries
+                .iter()
+                .map(|entry| fonts.bold.width(&entry.ministry.to_uppercase(), lead.ministry_size) + Self::GAP)
+                .fold(0.0, f32::max)
+                .min(lead.names_indent),
+            None => lead.names_indent,
+        };
+        let reserved = contact_width.map_or(0.0, |width| width + Self::GAP);
+        LeadershipColumns {
+            names_x,
+            names_width: usable_width - names_x - reserved,
+            contact_x: usable_width - lead.contact_column_width,
+            contact_width,
+        }
+    }
+}
+
+/// One page of the ministry team leadership section
+struct LeadershipPage {
+    entries: Vec<LeadershipEntry>,
+    /// Carries on from the previous page
+    continued: bool,
+    columns: LeadershipColumns,
+    /// Church contact details, on the last page only
+    contact_left: Vec<String>,
+    contact_right: Vec<String>,
+}
+
+/// Baseline of the first leadership row, below the page title
+fn leadership_first_row_y(theme: &PdfTheme) -> f32 {
+    PAGE_HEIGHT_MM - theme.leadership.margin_top - 14.0
+}
+
+/// Lowest point page content may reach without running into the footer
+fn content_bottom(theme: &PdfTheme) -> f32 {
+    theme.footer.baseline + theme.footer.font_size + 2.0
+}
+
+/// Height of the rule, heading and lines of the church contact block
+fn leadership_contact_block_height(line_count: usize, theme: &PdfTheme) -> f32 {
+    4.0 + 10.0 + 12.0 + line_count.saturating_sub(1) as f32 * theme.leadership.contact_line_height
+}
+
+/// Split the leadership entries into pages in order, leaving room after the
+/// last one for the church contact block. If the block doesn't fit below the
+/// last entry it gets a page of its own.
+fn paginate_leadership(
+    entries: Vec<LeadershipEntry>,
+    contact_left: Vec<String>,
+    contact_right: Vec<String>,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+) -> Vec<LeadershipPage> {
+    let columns = LeadershipColumns::new(&entries, theme, fonts);
+    let available = leadership_first_row_y(theme) - content_bottom(theme);
+    let new_page = |continued| LeadershipPage {
+        entries: Vec::new(),
+        continued,
+        columns,
+        contact_left: Vec::new(),
+        contact_right: Vec::new(),
+    };
+
+    let mut pages = Vec::new();
+    let mut page = new_page(false);
+    let mut used = 0.0;
+    for entry in entries {
+        let height = entry.height(&columns, theme, fonts);
+        // The row spacing below the last row on a page can run into the margin
+        let drawn = height - theme.leadership.row_spacing;
+        if !page.entries.is_empty() && used + drawn > available {
+            pages.push(std::mem::replace(&mut page, new_page(true)));
+            used = 0.0;
+        }
+        used += height;
+        page.entries.push(entry);
+    }
+
+    let contact_lines = contact_left.len().max(contact_right.len());
+    if contact_lines > 0 {
+        if used + leadership_contact_block_height(contact_lines, theme) > available {
+            pages.push(std::mem::replace(&mut page, new_page(true)));
+        }
+        page.contact_left = contact_left;
+        page.contact_right = contact_right;
+    }
+    pages.push(page);
+    pages
+}
+
+#[derive(Clone)]
 struct StaffEntry {
     name: String,
     title: String,
     role: String,
     photo_path: Option<PathBuf>,
+    /// Phone, email and office hours, for the "Meet Our Staff" pages
+    contact: Vec<String>,
+    /// Markdown; staff with a bio get a "Meet Our Staff" entry
+    bio: Option<String>,
+}
+
+/// Phone and email on one line, then office hours
+fn staff_contact_lines(
+    email: Option<String>,
+    phone: Option<String>,
+    office_hours: Option<String>,
+    locale: Locale,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    let reach: Vec<String> = [phone, email]
+        .into_iter()
+        .flatten()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if !reach.is_empty() {
+        lines.push(reach.join("  \u{00b7}  "));
+    }
+    if let Some(hours) = office_hours {
+        let mut hours_lines = hours.lines().map(str::trim).filter(|l| !l.is_empty());
+        if let Some(first) = hours_lines.next() {
+            lines.push(locale.format(Text::OfficeHours, first));
+            lines.extend(hours_lines.map(str::to_string));
+        }
+    }
+    lines
+}
+
+/// Space between entries on the "Meet Our Staff" pages
+const STAFF_BIO_GAP: f32 = 10.0;
+/// Space between a bio's photo and its text
+const STAFF_BIO_PHOTO_GAP: f32 = 6.0;
+
+/// One page of "Meet Our Staff"
+struct StaffBioPage {
+    entries: Vec<StaffEntry>,
+    continued: bool,
+}
+
+/// Width of the text column beside a bio photo
+fn staff_bio_text_width(theme: &PdfTheme) -> f32 {
+    PAGE_WIDTH_MM - 2.0 * theme.markdown.margin_x - theme.staff.photo_width - STAFF_BIO_PHOTO_GAP
+}
+
+/// Distance from the page heading's baseline to the top of the first entry
+fn staff_bio_heading_height(theme: &PdfTheme) -> f32 {
+    theme.markdown.h1_line_height + theme.markdown.paragraph_spacing
+}
+
+/// Height of an entry: its photo or its text, whichever is taller
+fn staff_bio_height(entry: &StaffEntry, theme: &PdfTheme, fonts: &PdfFonts) -> f32 {
+    let text_bottom = draw_staff_bio_text(&MeasureCanvas, entry, 0.0, 0.0, staff_bio_text_width(theme), theme, fonts);
+    (-text_bottom).max(theme.staff.photo_height)
+}
+
+/// Split the staff with bios into pages, keeping each entry whole. An entry
+/// taller than a page gets a page to itself.
+fn paginate_staff_bios(entries: Vec<StaffEntry>, theme: &PdfTheme, fonts: &PdfFonts) -> Vec<StaffBioPage> {
+    let available = PAGE_HEIGHT_MM - theme.markdown.margin_top - staff_bio_heading_height(theme) - content_bottom(theme);
+
+    let mut pages = Vec::new();
+    let mut page = StaffBioPage { entries: Vec::new(), continued: false };
+    let mut used = 0.0;
+    for entry in entries {
+        let height = staff_bio_height(&entry, theme, fonts);
+        if !page.entries.is_empty() && used + height > available {
+            pages.push(std::mem::replace(&mut page, StaffBioPage { entries: Vec::new(), continued: true }));
+            used = 0.0;
+        }
+        used += height + STAFF_BIO_GAP;
+        page.entries.push(entry);
+    }
+
+    if !page.entries.is_empty() {
+        pages.push(page);
+    }
+    pages
+}
+
+/// How a staff category is laid out on the staff page
+#[derive(Clone, Copy, PartialEq)]
+enum StaffLayout {
+    /// Portraits one above another, with a list category beside them
+    Featured,
+    /// Portraits spread across the page in rows
+    PhotoGrid,
+    /// "TITLE Name" lines without photos
+    List,
+}
+
+impl StaffLayout {
+    fn from_name(name: &str) -> Self {
+        match name {
+            "featured" => StaffLayout::Featured,
+            "list" => StaffLayout::List,
+            _ => StaffLayout::PhotoGrid,
+        }
+    }
+}
+
+/// A staff category and its people, in page order
+struct StaffSection {
+    title: String,
+    layout: StaffLayout,
+    photo_width: f32,
+    photo_height: f32,
+    entries: Vec<StaffEntry>,
+}
+
+/// One piece of a staff page, placed top to bottom
+enum StaffBlock {
+    Heading { title: String, space_below: f32 },
+    /// Portraits side by side with names beneath
+    Photos { entries: Vec<StaffEntry>, width: f32, height: f32, space_below: f32 },
+    /// A single "TITLE Name" line
+    Listing(StaffEntry),
+    /// A featured category in the left column with a list category beside it
+    Beside { left: Vec<StaffBlock>, right: Vec<StaffBlock> },
+}
+
+const STAFF_LIST_LINE_HEIGHT: f32 = 7.0;
+/// Least space between portraits in a row, leaving room for the names beneath
+const STAFF_PHOTO_MIN_GAP: f32 = 20.0;
+/// Share of the width the featured column takes when a list sits beside it
+const STAFF_FEATURED_COLUMN: f32 = 0.4;
+
+impl StaffBlock {
+    /// Distance from this block's top to the next one's
+    fn height(&self) -> f32 {
+        match self {
+            StaffBlock::Heading { space_below, .. } => *space_below,
+            StaffBlock::Photos { height, space_below, .. } => height + space_below,
+            StaffBlock::Listing(_) => STAFF_LIST_LINE_HEIGHT,
+            StaffBlock::Beside { left, right } => {
+                let stack = |blocks: &[StaffBlock]| blocks.iter().map(StaffBlock::height).sum::<f32>();
+                stack(left).max(stack(right))
+            }
+        }
+    }
+
+    /// How far below its top the block actually draws. The spacing under the
+    /// last block on a page can run into the bottom margin.
+    fn extent(&self) -> f32 {
+        match self {
+            StaffBlock::Heading { space_below, .. } => *space_below,
+            StaffBlock::Photos { height, .. } => height + 7.0,
+            StaffBlock::Listing(_) => 2.0,
+            StaffBlock::Beside { left, right } => staff_stack_extent(left).max(staff_stack_extent(right)),
+        }
+    }
+
+    /// Portraits placed by this block, with the size each is printed at
+    fn collect_photos<'a>(&'a self, photos: &mut Vec<(&'a StaffEntry, f32, f32)>) {
+        match self {
+            StaffBlock::Photos { entries, width, height, .. } => {
+                photos.extend(entries.iter().map(|entry| (entry, *width, *height)));
+            }
+            StaffBlock::Beside { left, right } => {
+                for block in left.iter().chain(right) {
+                    block.collect_photos(photos);
+                }
+            }
+            StaffBlock::Heading { .. } | StaffBlock::Listing(_) => {}
+        }
+    }
+}
+
+fn staff_stack_extent(blocks: &[StaffBlock]) -> f32 {
+    match blocks.split_last() {
+        Some((last, rest)) => rest.iter().map(StaffBlock::height).sum::<f32>() + last.extent(),
+        None => 0.0,
+    }
+}
+
+impl StaffSection {
+    fn heading(&self) -> StaffBlock {
+        self.heading_titled(self.title.clone())
+    }
+
+    /// Heading repeated where the category carries on over a page break
+    fn continued_heading(&self, locale: Locale) -> StaffBlock {
+        self.heading_titled(locale.format(Text::Continued, &self.title))
+    }
+
+    fn heading_titled(&self, title: String) -> StaffBlock {
+        let space_below = if self.layout == StaffLayout::List { 10.0 } else { 8.0 };
+        StaffBlock::Heading { title, space_below }
+    }
+
+    /// The section's rows below its heading, for a column `width` wide
+    fn rows(&self, width: f32) -> Vec<StaffBlock> {
+        let photo_rows = |per_row: usize, space_below| {
+            self.entries
+                .chunks(per_row)
+                .map(|row| StaffBlock::Photos {
+                    entries: row.to_vec(),
+                    width: self.photo_width,
+                    height: self.photo_height,
+                    space_below,
+                })
+                .collect()
+        };
+        match self.layout {
+            StaffLayout::Featured => photo_rows(1, 18.0),
+            StaffLayout::PhotoGrid => {
+                let fit = (width + STAFF_PHOTO_MIN_GAP) / (self.photo_width + STAFF_PHOTO_MIN_GAP);
+                photo_rows((fit as usize).max(1), 14.0)
+            }
+            StaffLayout::List => self.entries.iter().cloned().map(StaffBlock::Listing).collect(),
+        }
+    }
+
+    fn blocks(&self, width: f32) -> Vec<StaffBlock> {
+        let mut blocks = vec![self.heading()];
+        blocks.extend(self.rows(width));
+        blocks
+    }
+}
+
+/// Lay the staff categories out in order over as many pages as they need. A
+/// featured category followed by a list category share the top of a page
+/// side by side when they fit; otherwise every category runs full width.
+/// Headings stay with their first row, and a category that breaks across
+/// pages repeats its heading.
+fn paginate_staff(sections: Vec<StaffSection>, theme: &PdfTheme, locale: Locale) -> Vec<Vec<StaffBlock>> {
+    let usable_width = PAGE_WIDTH_MM - 2.0 * theme.staff.margin_x;
+    let available = PAGE_HEIGHT_MM - theme.staff.margin_top - content_bottom(theme);
+
+    let mut pages = Vec::new();
+    let mut page: Vec<StaffBlock> = Vec::new();
+    let mut used = 0.0;
+
+    let mut sections = sections.into_iter().filter(|s| !s.entries.is_empty()).peekable();
+    while let Some(section) = sections.next() {
+        if section.layout == StaffLayout::Featured {
+            if let Some(list) = sections.next_if(|next| next.layout == StaffLayout::List) {
+                let column = usable_width * STAFF_FEATURED_COLUMN;
+                let beside = StaffBlock::Beside {
+                    left: section.blocks(column),
+                    right: list.blocks(usable_width - column),
+                };
+                if beside.extent() <= available {
+                    if !page.is_empty() && used + beside.extent() > available {
+                        pages.push(std::mem::take(&mut page));
+                        used = 0.0;
+                    }
+                    used += beside.height();
+                    page.push(beside);
+                    continue;
+                }
+                for section in [section, list] {
+                    place_staff_section(section, usable_width, available, locale, &mut pages, &mut page, &mut used);
+                }
+                continue;
+            }
+        }
+        place_staff_section(section, usable_width, available, locale, &mut pages, &mut page, &mut used);
+    }
+
+    if !page.is_empty() {
+        pages.push(page);
+    }
+    pages
+}
+
+/// Add a full-width category to the staff pages, starting new pages as it fills them
+fn place_staff_section(
+    section: StaffSection,
+    width: f32,
+    available: f32,
+    locale: Locale,
+    pages: &mut Vec<Vec<StaffBlock>>,
+    page: &mut Vec<StaffBlock>,
+    used: &mut f32,
+) {
+    let heading_height = section.heading().height();
+    for (index, row) in section.rows(width).into_iter().enumerate() {
+        let needed = if index == 0 { heading_height + row.extent() } else { row.extent() };
+        if !page.is_empty() && *used + needed > available {
+            pages.push(std::mem::take(page));
+            *used = 0.0;
+            if index > 0 {
+                page.push(section.continued_heading(locale));
+                *used += heading_height;
+            }
+        }
+        if index == 0 {
+            page.push(section.heading());
+            *used += heading_height;
+        }
+        *used += row.height();
+        page.push(row);
+    }
 }
 
 impl ContentPage {
-    /// All content pages get footers except cover/back images and blank pages.
+    /// All content pages get footers except covers and blank pages.
     fn needs_footer(&self) -> bool {
-        !matches!(self, ContentPage::FullImage(_) | ContentPage::CoverImage { .. } | ContentPage::Blank)
+        !matches!(
+            self,
+            ContentPage::FullImage { .. }
+                | ContentPage::CoverImage { .. }
+                | ContentPage::TemplateCover { .. }
+                | ContentPage::TemplateBackCover { .. }
+                | ContentPage::Blank
+        )
+    }
+
+    /// Photos this page places, with the size each is printed at
+    fn image_requests(&self, theme: &PdfTheme, requests: &mut Vec<ImageRequest>) {
+        let mut request = |path: &PathBuf, treatment, max_width_mm, max_height_mm| {
+            requests.push(ImageRequest { path: path.clone(), treatment, max_width_mm, max_height_mm });
+        };
+        match self {
+            // Title-only covers carry an empty image path
+            ContentPage::CoverImage { image_path, .. } if !image_path.exists() => {}
+            ContentPage::FullImage { image_path, bleed } | ContentPage::CoverImage { image_path, bleed, .. } => {
+                let (width, height) = full_image_area(theme, *bleed);
+                request(image_path, full_image_treatment(theme, *bleed), width, height);
+            }
+            ContentPage::PhotoGrid { entries, grid_rows } => {
+                let layout = GridLayout::new(theme, *grid_rows);
+                for entry in entries {
+                    let treatment = ImageTreatment::Grid { crop: entry.photo_crop };
+                    request(&entry.photo_path, treatment, layout.photo_width, layout.photo_height);
+                }
+            }
+            ContentPage::TextCards(columns) => {
+                let size = theme.cards.member_photo_size;
+                for member in columns.iter().flatten().flat_map(|card| &card.members) {
+                    if let Some(ref path) = member.photo_path {
+                        request(path, ImageTreatment::Avatar, size, size);
+                    }
+                }
+            }
+            ContentPage::CelebrationPages(celeb_page) if celeb_page.is_first => {
+                if let Some(ref path) = celeb_page.image_path {
+                    request(path, ImageTreatment::Flattened, PAGE_WIDTH_MM, celebration_image_height(theme));
+                }
+            }
+            ContentPage::StaffPage(blocks) => {
+                let treatment = ImageTreatment::staff(theme.photos.corner_radius);
+                let mut photos = Vec::new();
+                for block in blocks {
+                    block.collect_photos(&mut photos);
+                }
+                for (entry, width, height) in photos {
+                    if let Some(ref path) = entry.photo_path {
+                        request(path, treatment, width, height);
+                    }
+                }
+            }
+            ContentPage::Markdown(items) => {
+                for item in items {
+                    if let MarkdownItem::Image { path, width, height, .. } = item {
+                        request(path, ImageTreatment::Flattened, *width, *height);
+                    }
+                }
+            }
+            ContentPage::StaffBios(bio_page) => {
+                let treatment = ImageTreatment::staff(theme.photos.corner_radius);
+                for entry in &bio_page.entries {
+                    if let Some(ref path) = entry.photo_path {
+                        request(path, treatment, theme.staff.photo_width, theme.staff.photo_height);
+                    }
+                }
+            }
+            ContentPage::TemplateCover { church, .. } | ContentPage::TemplateBackCover { church, .. } => {
+                if let Some(ref path) = church.logo_path {
+                    let width = PAGE_WIDTH_MM - 2.0 * theme.cover.margin;
+                    request(path, ImageTreatment::Flattened, width, theme.cover.logo_height);
+                }
+            }
+            _ => {}
+        }
     }
 }
 
-struct CelebrationEntry {
-    name: String,
-    day: u32,
+pub struct CelebrationEntry {
+    pub name: String,
+    pub date: PartialDate,
+    /// The member whose birthday it is, or the couple married on this date
+    pub member_ids: Vec<i64>,
     display_date: String, // e.g., "March 15"
+    /// Printed after the name, e.g. "50th anniversary"
+    note: Option<String>,
+    /// A milestone birthday or anniversary, printed in bold
+    milestone: bool,
 }
 
-struct CelebrationMonth {
+/// Anniversaries called out on the celebrations pages
+const ANNIVERSARY_MILESTONES: [u32; 7] = [25, 40, 50, 60, 65, 70, 75];
+
+/// Birthdays called out when enabled, along with every one from 100 on
+const BIRTHDAY_MILESTONES: [u32; 2] = [80, 90];
+
+pub struct CelebrationMonth {
     month_name: String,
-    birthdays: Vec<CelebrationEntry>,
-    anniversaries: Vec<CelebrationEntry>,
+    pub birthdays: Vec<CelebrationEntry>,
+    pub anniversaries: Vec<CelebrationEntry>,
 }
 
 /// A single renderable item in the celebration flow layout
@@ -124,18 +753,18 @@ enum CelebItem {
     /// "Birthdays" or "Anniversaries" section label
     SectionHeader(String),
     /// A single date + name entry
-    Entry { display_date: String, name: String },
+    Entry { display_date: String, name: String, note: Option<String>, milestone: bool },
     /// Vertical spacing between months
     MonthGap,
 }
 
 impl CelebItem {
-    fn height(&self) -> f32 {
+    fn height(&self, theme: &PdfTheme) -> f32 {
         match self {
             CelebItem::MonthHeader { .. } => 2.0 + 5.0, // text + accent line + gap
-            CelebItem::SectionHeader(_) => CELEB_SECTION_SIZE * 0.4 + CELEB_SECTION_SPACING * 0.5,
-            CelebItem::Entry { .. } => CELEB_LINE_HEIGHT,
-            CelebItem::MonthGap => CELEB_MONTH_SPACING,
+            CelebItem::SectionHeader(_) => theme.celebrations.section_size * 0.4 + theme.celebrations.section_spacing * 0.5,
+            CelebItem::Entry { .. } => theme.celebrations.line_height,
+            CelebItem::MonthGap => theme.celebrations.month_spacing,
         }
     }
 }
@@ -150,6 +779,7 @@ struct CelebPage {
 
 struct PhotoGridEntry {
     photo_path: PathBuf,
+    photo_crop: Option<PhotoCrop>,
     family_name: String,
     display_name: String,
     directory_adults: Option<String>,
@@ -177,9 +807,9 @@ struct FamilyCardData {
 }
 
 impl FamilyCardData {
-    fn height(&self) -> f32 {
+    fn height(&self, theme: &PdfTheme) -> f32 {
         // Base height from family info lines
-        let mut h = CARD_NAME_SIZE * 0.6;
+        let mut h = theme.cards.name_size * 0.6;
         let mut family_lines = 0;
         if self.mailing_name.is_some() { family_lines += 1; }
         if self.address.is_some() { family_lines += 1; }
@@ -190,139 +820,945 @@ impl FamilyCardData {
             if self.alt_city_state_zip.is_some() { family_lines += 1; }
         }
         if self.phone.is_some() { family_lines += 1; }
-        h += family_lines as f32 * CARD_LINE_HEIGHT;
+        h += family_lines as f32 * theme.cards.line_height;
 
         // If any member has a photo, all members get photo-height spacing for alignment
         let any_member_has_photo = self.members.iter().any(|m| m.photo_path.is_some());
         for member in &self.members {
-            let mut member_text_h = CARD_LINE_HEIGHT; // name line
-            if member.phone.is_some() { member_text_h += CARD_LINE_HEIGHT; }
-            if member.email.is_some() { member_text_h += CARD_LINE_HEIGHT; }
+            let mut member_text_h = theme.cards.line_height; // name line
+            if member.phone.is_some() { member_text_h += theme.cards.line_height; }
+            if member.email.is_some() { member_text_h += theme.cards.line_height; }
             if any_member_has_photo {
-                h += member_text_h.max(MEMBER_PHOTO_SIZE_MM + 1.0);
+                h += member_text_h.max(theme.cards.member_photo_size + 1.0);
             } else {
                 h += member_text_h;
             }
         }
 
-        h + CARD_SPACING
+        h + theme.cards.spacing
     }
 }
 
+/// Start generating the booklet PDF as a background job and return its job
+/// id. Reports "pages", "images" and "bytes" progress; the job's result is
+/// the output path.
 #[tauri::command]
 pub fn generate_pdf(
     app_handle: AppHandle,
-    db: State<'_, Database>,
     options: PdfOptions,
     output_path: String,
 ) -> Result<String, String> {
-    let photos_dir = Database::get_photos_dir(&app_handle);
+    spawn_job(&app_handle, move |job| {
+        let result = write_pdf(job, &options, &output_path);
+        if result.is_err() {
+            // Don't leave a truncated PDF behind
+            let _ = std::fs::remove_file(&output_path);
+        }
+        result
+    })
+}
 
-    // Query all families with members
-    let families_with_members: Vec<FamilyWithMembers> = {
-        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+fn write_pdf(job: &JobContext, options: &PdfOptions, output_path: &str) -> Result<String, String> {
+    let app_handle = job.app_handle();
+    let db = app_handle.state::<Database>();
+    let image_cache = app_handle.state::<ImageCache>();
+
+    let (font_data, theme, locale) = load_print_settings(app_handle, &db)?;
+    let fonts = PdfFonts::load(&font_data)?;
+    let image_quality = ImageQuality::preset(options.image_quality.as_deref())?;
+
+    let content_pages = build_content_pages(app_handle, &db, options, &theme, &fonts, locale)?;
+    job.check_cancelled()?;
+
+    // Decode, crop and encode every photo up front, in parallel
+    let mut image_requests = Vec::new();
+    for page in &content_pages {
+        page.image_requests(&theme, &mut image_requests);
+    }
+    let images = prepare_images(image_requests, image_quality, &image_cache, Some(job))?;
+
+    // ========================================
+    // Pass 2: Imposition and PDF rendering
+    // ========================================
+    let imposition = Imposition::from_name(options.imposition.as_deref(), options.signature_sheets)?;
+    let sides = imposition.sides(content_pages.len(), options.duplex.unwrap_or(true));
+    let total_slots: usize = sides.iter().map(|side| side.slots.len()).sum();
+    let current_year = chrono::Utc::now().format("%Y").to_string();
+
+    let sheet = SheetLayout::new(options, &imposition)?;
+    let (mut doc, page1, layer1) = PdfDocument::new(
+        &options.church_name,
+        Mm(sheet.media_width()),
+        Mm(sheet.media_height()),
+        "Layer 1",
+    );
+    if sheet.pdf_x {
+        // Embeds the CMYK output intent and the PDF/X identification keys.
+        // The canvases draw in DeviceCMYK to match.
+        doc = doc
+            .with_conformance(PdfConformance::X3_2002_PDF_1_3)
+            .with_trapping(false);
+    }
+
+    let embedded_fonts = EmbeddedFonts::embed(&doc, &font_data)?;
+
+    let mut placed = 0;
+    for (i, side) in sides.iter().enumerate() {
+        job.check_cancelled()?;
+
+        let (page, layer) = if i == 0 {
+            (page1, layer1)
+        } else {
+            doc.add_page(Mm(sheet.media_width()), Mm(sheet.media_height()), "Sheet")
+        };
+        let layer = sheet.begin_side(&doc, page, layer, &embedded_fonts);
+
+        for slot in &side.slots {
+            placed += 1;
+            let Some(idx) = slot.page else {
+                continue;
+            };
+            let canvas = sheet.page_canvas(layer.clone(), &embedded_fonts, slot);
+            let page = &content_pages[idx];
+            render_content_page(&canvas, page, 0.0, slot.col == 0, &theme, &fonts, &images, locale);
+            if page.needs_footer() {
+                render_footer(&canvas, 0.0, &options.church_name, &current_year, idx + 1, &theme, &fonts);
+            }
+        }
+
+        job.progress("pages", placed as u64, Some(total_slots as u64));
+    }
+
+    let mut bytes = doc.save_to_bytes().map_err(|e| format!("Save error: {:?}", e))?;
+    if sheet.pdf_x {
+        bytes = strip_optional_content(&bytes)?;
+    }
+    let file = File::create(output_path).map_err(|e| e.to_string())?;
+    let mut buf_writer = BufWriter::new(ProgressWriter::new(file, job));
+    buf_writer.write_all(&bytes).map_err(|e| e.to_string())?;
+    buf_writer.flush().map_err(|e| e.to_string())?;
+    job.check_cancelled()?;
+
+    Ok(output_path.to_string())
+}
+
+/// Remove the optional content group printpdf wraps around every layer.
+/// PDF/X-3 is based on PDF 1.3, which has no optional content, so preflight
+/// rejects files that carry it.
+fn strip_optional_content(pdf: &[u8]) -> Result<Vec<u8>, String> {
+    let mut doc = lopdf::Document::load_mem(pdf).map_err(|e| e.to_string())?;
+    for page_id in doc.get_pages().into_values() {
+        let content = doc.get_page_content(page_id).map_err(|e| e.to_string())?;
+        let mut content = lopdf::content::Content::decode(&content).map_err(|e| e.to_string())?;
+        // Pages never use marked content of their own
+        content.operations.retain(|op| op.operator != "BDC" && op.operator != "EMC");
+        let content = content.encode().map_err(|e| e.to_string())?;
+        doc.change_page_content(page_id, content).map_err(|e| e.to_string())?;
+
+        let (_, resource_ids) = doc.get_page_resources(page_id);
+        for id in resource_ids {
+            if let Ok(resources) = doc.get_dictionary_mut(id) {
+                resources.remove(b"Properties");
+            }
+        }
+    }
+    doc.catalog_mut().map_err(|e| e.to_string())?.remove(b"OCProperties");
+    doc.prune_objects();
+    doc.compress();
+
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+/// Bleed in mm requested for print-shop output (0 when not set)
+fn print_bleed(options: &PdfOptions) -> Result<f32, String> {
+    let bleed = options.bleed_mm.unwrap_or(0.0);
+    if !(0.0..=MAX_BLEED_MM).contains(&bleed) {
+        return Err(format!("Bleed must be between 0 and {} mm", MAX_BLEED_MM));
+    }
+    Ok(bleed)
+}
+
+/// Media size and trim position of an imposed sheet, and where each page
+/// slot sits on it. With bleed or printer's marks the media box grows around
+/// the trim, and pages are drawn relative to their slot so layout code is
+/// unaffected.
+struct SheetLayout {
+    bleed: f32,
+    crop_marks: bool,
+    /// Draw for PDF/X-3: CMYK colors and no links
+    pdf_x: bool,
+    /// Distance from the trim to the media edge on every side
+    margin: f32,
+    cols: usize,
+    rows: usize,
+    /// Pages shrink to fit when the imposition has more slots than the sheet
+    /// holds at full size
+    page_scale: f32,
+}
+
+impl SheetLayout {
+    fn new(options: &PdfOptions, imposition: &Imposition) -> Result<Self, String> {
+        let bleed = print_bleed(options)?;
+        let crop_marks = options.crop_marks.unwrap_or(false);
+        let pdf_x = options.pdf_x.unwrap_or(false);
+        let margin = if crop_marks {
+            crop_mark_offset(bleed) + CROP_MARK_LENGTH_MM + 1.0
+        } else {
+            bleed
+        };
+        let (cols, rows) = imposition.grid();
+        let page_scale = (SHEET_WIDTH_MM / cols as f32 / PAGE_WIDTH_MM)
+            .min(SHEET_HEIGHT_MM / rows as f32 / PAGE_HEIGHT_MM);
+        Ok(SheetLayout { bleed, crop_marks, pdf_x, margin, cols, rows, page_scale })
+    }
+
+    fn media_width(&self) -> f32 {
+        SHEET_WIDTH_MM + 2.0 * self.margin
+    }
+
+    fn media_height(&self) -> f32 {
+        SHEET_HEIGHT_MM + 2.0 * self.margin
+    }
+
+    /// Set a sheet side's trim and bleed boxes and draw its printer's marks
+    fn begin_side(
+        &self,
+        doc: &PdfDocumentReference,
+        page: PdfPageIndex,
+        layer: PdfLayerIndex,
+        fonts: &EmbeddedFonts,
+    ) -> PdfLayerReference {
+        let page = doc.get_page(page);
+        page.extend_with(self.page_boxes());
+        let layer = page.get_layer(layer);
+        if self.crop_marks {
+            let canvas = PdfCanvas::new(layer.clone(), fonts)
+                .with_origin(self.margin, self.margin)
+                .with_pdf_x(self.pdf_x);
+            draw_printer_marks(&canvas, self.bleed, self.cols, self.rows);
+        }
+        layer
+    }
+
+    /// A canvas with its origin at the bottom-left of a page slot, scaled so
+    /// pages can be drawn at their normal size
+    fn page_canvas<'a>(&self, layer: PdfLayerReference, fonts: &'a EmbeddedFonts, slot: &Slot) -> PdfCanvas<'a> {
+        let slot_width = SHEET_WIDTH_MM / self.cols as f32;
+        let slot_height = SHEET_HEIGHT_MM / self.rows as f32;
+        let x = slot.col as f32 * slot_width + (slot_width - PAGE_WIDTH_MM * self.page_scale) / 2.0;
+        let y = (self.rows - 1 - slot.row) as f32 * slot_height
+            + (slot_height - PAGE_HEIGHT_MM * self.page_scale) / 2.0;
+        PdfCanvas::new(layer, fonts)
+            .with_origin(self.margin + x, self.margin + y)
+            .with_scale(self.page_scale)
+            .with_pdf_x(self.pdf_x)
+    }
+
+    fn page_boxes(&self) -> lopdf::Dictionary {
+        let pdf_box = |inset: f32| -> lopdf::Object {
+            let points = |mm: f32| lopdf::Object::Real(Pt::from(Mm(mm)).0);
+            vec![
+                points(inset),
+                points(inset),
+                points(self.media_width() - inset),
+                points(self.media_height() - inset),
+            ]
+            .into()
+        };
+        let mut boxes = lopdf::Dictionary::new();
+        boxes.set("MediaBox", pdf_box(0.0));
+        boxes.set("CropBox", pdf_box(0.0));
+        boxes.set("BleedBox", pdf_box(self.margin - self.bleed));
+        boxes.set("TrimBox", pdf_box(self.margin));
+        boxes
+    }
+}
+
+/// Printer's marks start this far from the trim, outside the bleed
+fn crop_mark_offset(bleed: f32) -> f32 {
+    bleed.max(CROP_MARK_MIN_OFFSET_MM)
+}
 
-        let mut family_stmt = conn
+/// Draw crop marks at the sheet's trim corners, fold or cut marks between the
+/// `cols` x `rows` page slots, and registration targets above and below the
+/// sheet. Coordinates are relative to the trim, so every mark sits at
+/// negative positions or beyond the sheet size.
+fn draw_printer_marks(canvas: &dyn PageCanvas, bleed: f32, cols: usize, rows: usize) {
+    let near = crop_mark_offset(bleed);
+    let far = near + CROP_MARK_LENGTH_MM;
+    let (w, h) = (SHEET_WIDTH_MM, SHEET_HEIGHT_MM);
+
+    canvas.set_outline_color(REGISTRATION_COLOR);
+    canvas.set_outline_thickness(MARK_THICKNESS_PT);
+
+    for x in [0.0, w] {
+        canvas.line(x, -far, x, -near);
+        canvas.line(x, h + near, x, h + far);
+    }
+    for y in [0.0, h] {
+        canvas.line(-far, y, -near, y);
+        canvas.line(w + near, y, w + far, y);
+    }
+
+    for col in 1..cols {
+        let x = w * col as f32 / cols as f32;
+        canvas.line(x, -far, x, -near);
+        canvas.line(x, h + near, x, h + far);
+    }
+    for row in 1..rows {
+        let y = h * row as f32 / rows as f32;
+        canvas.line(-far, y, -near, y);
+        canvas.line(w + near, y, w + far, y);
+    }
+
+    // Centered in the slug over each quarter of the sheet width, clear of
+    // the fold and cut marks
+    let r = REGISTRATION_MARK_RADIUS_MM;
+    let center = (near + far) / 2.0;
+    for x in [w / 4.0, w * 3.0 / 4.0] {
+        for y in [-center, h + center] {
+            canvas.rounded_rect(x - r, y - r, 2.0 * r, 2.0 * r, r);
+            canvas.line(x - r * 1.4, y, x + r * 1.4, y);
+            canvas.line(x, y - r * 1.4, x, y + r * 1.4);
+        }
+    }
+}
+
+/// Render selected content pages to PNG images for on-screen previews.
+/// Runs the same layout pass as generate_pdf and draws each page with the same
+/// renderers, but rasterizes directly instead of building a PDF. Page numbers
+/// are 1-based in reading order; numbers past the end are skipped.
+#[tauri::command]
+pub fn preview_pdf_pages(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    image_cache: State<'_, ImageCache>,
+    options: PdfOptions,
+    pages: Vec<usize>,
+    dpi: Option<f32>,
+) -> Result<PdfPreview, String> {
+    let (font_data, theme, locale) = load_print_settings(&app_handle, &db)?;
+    let fonts = PdfFonts::load(&font_data)?;
+    let raster_fonts = RasterFonts::parse(&font_data)?;
+    let image_quality = ImageQuality::preset(options.image_quality.as_deref())?;
+
+    let content_pages = build_content_pages(&app_handle, &db, &options, &theme, &fonts, locale)?;
+    let current_year = chrono::Utc::now().format("%Y").to_string();
+    let dpi = dpi.unwrap_or(PREVIEW_DEFAULT_DPI).clamp(1.0, PREVIEW_MAX_DPI);
+
+    // Only the requested pages' photos are prepared. They use the same
+    // quality preset as generate_pdf, so both share cache entries.
+    let selected: Vec<(usize, &ContentPage)> = pages
+        .into_iter()
+        .filter_map(|page_number| {
+            let page = page_number.checked_sub(1).and_then(|idx| content_pages.get(idx))?;
+            Some((page_number, page))
+        })
+        .collect();
+    let mut image_requests = Vec::new();
+    for (_, page) in &selected {
+        page.image_requests(&theme, &mut image_requests);
+    }
+    let images = prepare_images(image_requests, image_quality, &image_cache, None)?;
+
+    let mut previews = Vec::new();
+    for (page_number, page) in selected {
+        let canvas = RasterCanvas::new(PAGE_WIDTH_MM, PAGE_HEIGHT_MM, dpi, &raster_fonts);
+        // Even reading-order pages sit on the left half of a spread
+        render_content_page(&canvas, page, 0.0, page_number % 2 == 0, &theme, &fonts, &images, locale);
+        if page.needs_footer() {
+            render_footer(&canvas, 0.0, &options.church_name, &current_year, page_number, &theme, &fonts);
+        }
+
+        let mut png = Vec::new();
+        image_crate::DynamicImage::ImageRgb8(canvas.into_image())
+            .write_to(&mut std::io::Cursor::new(&mut png), image_crate::ImageOutputFormat::Png)
+            .map_err(|e| format!("Failed to encode preview: {}", e))?;
+
+        previews.push(PdfPagePreview {
+            page_number,
+            image: format!("data:image/png;base64,{}", STANDARD.encode(&png)),
+        });
+    }
+
+    Ok(PdfPreview {
+        page_count: content_pages.len(),
+        pages: previews,
+    })
+}
+
+/// Query every family with its members, ordered the way the directory lists them
+pub fn load_families_with_members(conn: &Connection) -> Result<Vec<FamilyWithMembers>, String> {
+    let mut family_stmt = conn
+        .prepare(
+            "SELECT id, family_id, name, mailing_name, address, city, state, zip, phone,
+                    photo_path, notes, children, alt_address, alt_city, alt_state, alt_zip,
+                    directory_adults, directory_children, include_photo_in_directory, photo_crop, created_at, updated_at
+             FROM families ORDER BY name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let families = family_stmt
+        .query_map([], |row| {
+            Ok(Family {
+                id: row.get(0)?,
+                family_id: row.get(1)?,
+                name: row.get(2)?,
+                mailing_name: row.get(3)?,
+                address: row.get(4)?,
+                city: row.get(5)?,
+                state: row.get(6)?,
+                zip: row.get(7)?,
+                phone: row.get(8)?,
+                photo_path: row.get(9)?,
+                notes: row.get(10)?,
+                children: row.get(11)?,
+                alt_address: row.get(12)?,
+                alt_city: row.get(13)?,
+                alt_state: row.get(14)?,
+                alt_zip: row.get(15)?,
+                directory_adults: row.get(16)?,
+                directory_children: row.get(17)?,
+                include_photo_in_directory: row.get(18)?,
+                photo_crop: row.get(19)?,
+                created_at: row.get(20)?,
+                updated_at: row.get(21)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut result = Vec::new();
+    for family in families {
+        let mut member_stmt = conn
             .prepare(
-                "SELECT id, family_id, name, mailing_name, address, city, state, zip, phone,
-                        photo_path, notes, children, alt_address, alt_city, alt_state, alt_zip,
-                        directory_adults, directory_children, include_photo_in_directory, created_at, updated_at
-                 FROM families ORDER BY name",
+                "SELECT id, family_id, first_name, last_name, role, phone, email,
+                        photo_path, notes, sort_order, created_at, updated_at,
+                        birth_month, birth_day, birth_year, wedding_month, wedding_day, wedding_year
+                 FROM members WHERE family_id = ? ORDER BY sort_order, last_name, first_name",
             )
             .map_err(|e| e.to_string())?;
 
-        let families = family_stmt
-            .query_map([], |row| {
-                Ok(Family {
+        let members = member_stmt
+            .query_map(params![family.id], |row| {
+                Ok(Member {
                     id: row.get(0)?,
                     family_id: row.get(1)?,
-                    name: row.get(2)?,
-                    mailing_name: row.get(3)?,
-                    address: row.get(4)?,
-                    city: row.get(5)?,
-                    state: row.get(6)?,
-                    zip: row.get(7)?,
-                    phone: row.get(8)?,
-                    photo_path: row.get(9)?,
-                    notes: row.get(10)?,
-                    children: row.get(11)?,
-                    alt_address: row.get(12)?,
-                    alt_city: row.get(13)?,
-                    alt_state: row.get(14)?,
-                    alt_zip: row.get(15)?,
-                    directory_adults: row.get(16)?,
-                    directory_children: row.get(17)?,
-                    include_photo_in_directory: row.get(18)?,
-                    created_at: row.get(19)?,
-                    updated_at: row.get(20)?,
+                    first_name: row.get(2)?,
+                    last_name: row.get(3)?,
+                    role: row.get(4)?,
+                    birth_date: PartialDate::from_row(row, 12)?,
+                    wedding_date: PartialDate::from_row(row, 15)?,
+                    phone: row.get(5)?,
+                    email: row.get(6)?,
+                    photo_path: row.get(7)?,
+                    notes: row.get(8)?,
+                    sort_order: row.get(9)?,
+                    created_at: row.get(10)?,
+                    updated_at: row.get(11)?,
                 })
             })
             .map_err(|e| e.to_string())?
             .collect::<Result<Vec<_>, _>>()
             .map_err(|e| e.to_string())?;
 
-        let mut result = Vec::new();
-        for family in families {
-            let mut member_stmt = conn
-                .prepare(
-                    "SELECT id, family_id, first_name, last_name, role, birth_date, wedding_date, phone, email,
-                            photo_path, notes, sort_order, created_at, updated_at
-                     FROM members WHERE family_id = ? ORDER BY sort_order, last_name, first_name",
-                )
-                .map_err(|e| e.to_string())?;
-
-            let members = member_stmt
-                .query_map(params![family.id], |row| {
-                    Ok(Member {
-                        id: row.get(0)?,
-                        family_id: row.get(1)?,
-                        first_name: row.get(2)?,
-                        last_name: row.get(3)?,
-                        role: row.get(4)?,
-                        birth_date: row.get(5)?,
-                        wedding_date: row.get(6)?,
-                        phone: row.get(7)?,
-                        email: row.get(8)?,
-                        photo_path: row.get(9)?,
-                        notes: row.get(10)?,
-                        sort_order: row.get(11)?,
-                        created_at: row.get(12)?,
-                        updated_at: row.get(13)?,
-                    })
-                })
-                .map_err(|e| e.to_string())?
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| e.to_string())?;
+        result.push(FamilyWithMembers { family, members });
+    }
+
+    Ok(result)
+}
+
+/// Build the text card for one family: formatted address lines and the
+/// contact details and photo of each member.
+fn family_card_data(fwm: &FamilyWithMembers, photos_dir: &Path) -> FamilyCardData {
+    let mut city_state_zip_parts: Vec<String> = Vec::new();
+    if let Some(ref city) = fwm.family.city.as_ref().filter(|s| !s.trim().is_empty()) {
+        city_state_zip_parts.push(city.to_string());
+    }
+    if let Some(ref state) = fwm.family.state.as_ref().filter(|s| !s.trim().is_empty()) {
+        if let Some(last) = city_state_zip_parts.pop() {
+            city_state_zip_parts.push(format!("{}, {}", last, state));
+        } else {
+            city_state_zip_parts.push(state.to_string());
+        }
+    }
+    if let Some(ref zip) = fwm.family.zip.as_ref().filter(|s| !s.trim().is_empty()) {
+        city_state_zip_parts.push(zip.to_string());
+    }
+    let city_state_zip = if city_state_zip_parts.is_empty() {
+        None
+    } else {
+        let joined = city_state_zip_parts.join(" ");
+        let trimmed = joined.trim().trim_matches(',').trim();
+        if trimmed.is_empty() { None } else { Some(joined) }
+    };
+
+    // Build alt city/state/zip
+    let mut alt_csz_parts: Vec<String> = Vec::new();
+    if let Some(ref city) = fwm.family.alt_city.as_ref().filter(|s| !s.trim().is_empty()) {
+        alt_csz_parts.push(city.to_string());
+    }
+    if let Some(ref state) = fwm.family.alt_state.as_ref().filter(|s| !s.trim().is_empty()) {
+        if let Some(last) = alt_csz_parts.pop() {
+            alt_csz_parts.push(format!("{}, {}", last, state));
+        } else {
+            alt_csz_parts.push(state.to_string());
+        }
+    }
+    if let Some(ref zip) = fwm.family.alt_zip.as_ref().filter(|s| !s.trim().is_empty()) {
+        alt_csz_parts.push(zip.to_string());
+    }
+    let alt_city_state_zip = if alt_csz_parts.is_empty() {
+        None
+    } else {
+        let joined = alt_csz_parts.join(" ");
+        let trimmed = joined.trim().trim_matches(',').trim();
+        if trimmed.is_empty() { None } else { Some(joined) }
+    };
+
+    let members: Vec<MemberCardInfo> = fwm.members.iter().map(|m| {
+        let photo_path = m.photo_path.as_ref().and_then(|pp| {
+            let base = photos_dir.join("members").join(pp);
+            let full = get_full_resolution_path(&base);
+            let resolved = if full.exists() { full } else { base };
+            if resolved.exists() { Some(resolved) } else { None }
+        });
+        MemberCardInfo {
+            name: format!("{} {}", m.first_name, m.last_name),
+            phone: m.phone.clone().filter(|s| !s.trim().is_empty()),
+            email: m.email.clone().filter(|s| !s.trim().is_empty()),
+            photo_path,
+        }
+    }).collect();
+
+    FamilyCardData {
+        name: fwm.family.name.clone(),
+        mailing_name: fwm.family.mailing_name.clone().filter(|s| !s.trim().is_empty() && s.trim() != ","),
+        address: fwm.family.address.clone().filter(|s| !s.trim().is_empty()),
+        city_state_zip,
+        alt_address: fwm.family.alt_address.clone().filter(|s| !s.trim().is_empty()),
+        alt_city_state_zip,
+        phone: fwm.family.phone.clone().filter(|s| !s.trim().is_empty()),
+        members,
+    }
+}
+
+/// Load the configured fonts (or the bundled defaults), layout theme and
+/// directory language
+fn load_print_settings(app_handle: &AppHandle, db: &Database) -> Result<(FontData, PdfTheme, Locale), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    Ok((
+        FontData::load(&conn, &Database::get_fonts_dir(app_handle)),
+        PdfTheme::load(&conn),
+        Locale::load(&conn),
+    ))
+}
+
+/// Check the directory for problems that generate_pdf would otherwise skip or
+/// clip without saying so: missing or unreadable photos, photos too small for
+/// their print size, families without addresses, captions and listings wider
+/// than their space, fonts that can't draw the directory language, and
+/// birthdays or anniversaries the date upgrade moved to a member's notes.
+#[tauri::command]
+pub fn preflight_directory(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    options: PdfOptions,
+) -> Result<PreflightReport, String> {
+    let photos_dir = Database::get_photos_dir(&app_handle);
+    let (font_data, theme, locale) = load_print_settings(&app_handle, &db)?;
+    let fonts = PdfFonts::load(&font_data)?;
+
+    let (families_with_members, staff, unreadable_dates) = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let families = load_families_with_members(&conn)?;
+
+        // The largest size each photo prints at: its category's size on the
+        // staff page (lists have no photos), or full size beside a bio
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, photo_path, scale FROM (
+                     SELECT s.id, s.name, s.photo_path, s.sort_order,
+                            MAX(CASE WHEN c.layout = 'list' THEN 0.0 ELSE COALESCE(c.photo_scale, 1.0) END,
+                                CASE WHEN ? AND TRIM(COALESCE(s.bio, '')) != '' THEN 1.0 ELSE 0.0 END) AS scale
+                     FROM staff s
+                     LEFT JOIN staff_categories c ON c.key = s.role
+                 )
+                 WHERE scale > 0
+                 ORDER BY sort_order, name",
+            )
+            .map_err(|e| e.to_string())?;
+        let staff: Vec<(i64, String, Option<String>, f64)> = stmt
+            .query_map(params![options.include_staff_bios], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+
+        // Dates the schema upgrade couldn't read and moved to the member's
+        // notes, until someone enters the date
+        let mut stmt = conn
+            .prepare(
+                "SELECT m.family_id, m.id, m.first_name || ' ' || m.last_name, u.field, u.value
+                 FROM unreadable_member_dates u
+                 JOIN members m ON m.id = u.member_id
+                 WHERE (u.field = 'birth_date' AND m.birth_month IS NULL)
+                    OR (u.field = 'wedding_date' AND m.wedding_month IS NULL)
+                 ORDER BY m.family_id, m.sort_order, u.field",
+            )
+            .map_err(|e| e.to_string())?;
+        let unreadable_dates: Vec<(i64, i64, String, String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+
+        (families, staff, unreadable_dates)
+    };
+
+    let mut issues = Vec::new();
+
+    for (family_id, member_id, name, field, value) in &unreadable_dates {
+        let label = if field == "wedding_date" { "Wedding date" } else { "Birth date" };
+        issues.push(PreflightSubject::member(*family_id, *member_id, name).issue(
+            "warning",
+            "unreadable_date",
+            format!("{} \"{}\" couldn't be read and was moved to the member's notes", label, value),
+        ));
+    }
+
+    // Headings, month names and dates in the directory language
+    for (font, style) in [(&fonts.regular, "Regular"), (&fonts.bold, "Bold")] {
+        let missing = font.metrics.missing_chars(&locale.printed_text());
+        if !missing.is_empty() {
+            let sample: String = missing.iter().take(8).collect();
+            issues.push(PreflightSubject::font(&format!("{} font", style)).issue(
+                "error",
+                "font_coverage",
+                format!(
+                    "Font has no characters for {} of the directory language's headings and dates (e.g. {}). Choose a font that covers the language in Settings.",
+                    missing.len(),
+                    sample
+                ),
+            ));
+        }
+    }
+
+    // Booklet images chosen on the generate page
+    let bleed = print_bleed(&options)?;
+    for (name, path) in [("Front cover", &options.cover_image_path), ("Back cover", &options.back_cover_image_path)] {
+        if let Some(ref filename) = path {
+            let path = photos_dir.join("directory").join(filename);
+            preflight_full_image(&path, &PreflightSubject::image(name), &theme, bleed, &mut issues);
+        }
+    }
+    if let Some(ref filename) = options.celebration_image_path {
+        let subject = PreflightSubject::image("Celebrations header");
+        preflight_photo(&photos_dir.join("directory").join(filename), &subject, &mut issues);
+    }
+
+    let grid = GridLayout::new(&theme, options.photo_grid_rows.unwrap_or(4));
+    let card_width = card_column_width(&theme);
+    let card_height = card_column_height(&theme);
+
+    for fwm in &families_with_members {
+        let family = &fwm.family;
+        let subject = PreflightSubject::family(family.id, &family.name);
+
+        if family.address.as_deref().unwrap_or("").trim().is_empty() {
+            issues.push(subject.issue("warning", "missing_address", "Family has no mailing address".to_string()));
+        }
+
+        // Photo grid entry
+        if let Some(photo_path) = family.photo_path.as_ref().filter(|_| family.include_photo_in_directory) {
+            let path = photos_dir.join("families").join(photo_path);
+            if let Some((w, h)) = preflight_photo(&path, &subject, &mut issues) {
+                let (crop_w, _) = grid_crop_size(w, h, family.photo_crop);
+                check_resolution(crop_w, grid.photo_width, &subject, &mut issues);
+
+                // Matches the caption drawn by render_photo_grid
+                let name_line_width = match family.directory_adults.as_ref().filter(|a| !a.is_empty()) {
+                    Some(adults) => {
+                        fonts.bold.width(&format!("{}, ", family.name), theme.grid.caption_size)
+                            + fonts.regular.width(adults, theme.grid.caption_size)
+                    }
+                    None => fonts.bold.width(&family.name, theme.grid.caption_size),
+                };
+                if name_line_width > grid.photo_width {
+                    issues.push(subject.issue(
+                        "warning",
+                        "caption_overflow",
+                        "Photo caption name and adults line is wider than the photo".to_string(),
+                    ));
+                }
+                if let Some(ref children) = family.directory_children {
+                    if fonts.regular.width(children, theme.grid.caption_size) > grid.photo_width {
+                        issues.push(subject.issue(
+                            "warning",
+                            "caption_overflow",
+                            "Photo caption children line is wider than the photo".to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Member photos
+        for member in &fwm.members {
+            let member_name = format!("{} {}", member.first_name, member.last_name);
+            let member_subject = PreflightSubject::member(family.id, member.id, &member_name);
+
+            if let Some(ref photo_path) = member.photo_path {
+                preflight_photo(&photos_dir.join("members").join(photo_path), &member_subject, &mut issues);
+            }
+        }
+
+        // Family listing card
+        let card = family_card_data(fwm, &photos_dir);
+        if let Some(line) = overflowing_card_line(&card, card_width, &theme, &fonts, locale) {
+            issues.push(subject.issue(
+                "warning",
+                "card_overflow",
+                format!("Listing line \"{}\" is wider than its column", line),
+            ));
+        }
+        if card.height(&theme) > card_height {
+            issues.push(subject.issue(
+                "error",
+                "card_overflow",
+                "Listing is taller than a page column and will run off the page".to_string(),
+            ));
+        }
+    }
+
+    for (id, name, photo_path, photo_scale) in &staff {
+        let subject = PreflightSubject::staff(*id, name);
+        if let Some(ref photo_path) = photo_path {
+            if let Some((w, h)) = preflight_photo(&photos_dir.join("staff").join(photo_path), &subject, &mut issues) {
+                // Staff photos are center-cropped to 4:6
+                let crop_w = w.min((h as f32 * STAFF_PHOTO_ASPECT) as u32);
+                check_resolution(crop_w, theme.staff.photo_width * *photo_scale as f32, &subject, &mut issues);
+            }
+        }
+    }
+
+    Ok(PreflightReport {
+        family_count: families_with_members.len(),
+        issues,
+    })
+}
+
+/// The record a preflight issue is about
+struct PreflightSubject {
+    family_id: Option<i64>,
+    member_id: Option<i64>,
+    staff_id: Option<i64>,
+    name: String,
+}
+
+impl PreflightSubject {
+    fn image(name: &str) -> Self {
+        PreflightSubject { family_id: None, member_id: None, staff_id: None, name: name.to_string() }
+    }
+
+    fn font(name: &str) -> Self {
+        PreflightSubject { family_id: None, member_id: None, staff_id: None, name: name.to_string() }
+    }
+
+    fn family(family_id: i64, name: &str) -> Self {
+        PreflightSubject { family_id: Some(family_id), member_id: None, staff_id: None, name: name.to_string() }
+    }
+
+    fn member(family_id: i64, member_id: i64, name: &str) -> Self {
+        PreflightSubject { family_id: Some(family_id), member_id: Some(member_id), staff_id: None, name: name.to_string() }
+    }
+
+    fn staff(staff_id: i64, name: &str) -> Self {
+        PreflightSubject { family_id: None, member_id: None, staff_id: Some(staff_id), name: name.to_string() }
+    }
+
+    fn issue(&self, severity: &str, category: &str, message: String) -> PreflightIssue {
+        PreflightIssue {
+            severity: severity.to_string(),
+            category: category.to_string(),
+            family_id: self.family_id,
+            member_id: self.member_id,
+            staff_id: self.staff_id,
+            subject: self.name.clone(),
+            message,
+        }
+    }
+}
+
+/// Check a cover image drawn by render_full_image. Its resolution is judged by
+/// the pixels left after cropping to fill the page when it is full bleed, or
+/// by the size it is scaled to inside the inset area otherwise.
+fn preflight_full_image(
+    path: &Path,
+    subject: &PreflightSubject,
+    theme: &PdfTheme,
+    bleed: f32,
+    issues: &mut Vec<PreflightIssue>,
+) {
+    let Some((w, h)) = preflight_photo(path, subject, issues) else {
+        return;
+    };
+    let (avail_width, avail_height) = full_image_area(theme, bleed);
+    if is_full_bleed(theme) {
+        // Full-bleed covers are cropped to fill the area, keeping fewer pixels across
+        let kept_width = (w as f32).min(h as f32 * avail_width / avail_height);
+        check_resolution(kept_width as u32, avail_width, subject, issues);
+    } else {
+        // Inset covers are scaled to fit inside the inset page area
+        let render_width = avail_width.min(avail_height * w as f32 / h as f32);
+        check_resolution(w, render_width, subject, issues);
+    }
+}
+
+/// Resolve and decode a photo the same way the renderers do, recording an
+/// issue if it is missing or unreadable. Returns its pixel size when it loads.
+fn preflight_photo(
+    base_path: &Path,
+    subject: &PreflightSubject,
+    issues: &mut Vec<PreflightIssue>,
+) -> Option<(u32, u32)> {
+    let full_res = get_full_resolution_path(base_path);
+    let path = if full_res.exists() { full_res } else { base_path.to_path_buf() };
+    let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    if !path.exists() {
+        issues.push(subject.issue(
+            "error",
+            "missing_photo",
+            format!("Photo file {} is missing and will be left out", filename),
+        ));
+        return None;
+    }
+
+    match image_crate::open(&path) {
+        Ok(img) => Some(image_crate::GenericImageView::dimensions(&img)),
+        Err(e) => {
+            issues.push(subject.issue(
+                "error",
+                "unreadable_photo",
+                format!("Photo file {} can't be read and will be left out: {}", filename, e),
+            ));
+            None
+        }
+    }
+}
+
+/// Flag a photo whose pixels are stretched below PREFLIGHT_MIN_DPI at print size
+fn check_resolution(
+    pixel_width: u32,
+    print_width_mm: f32,
+    subject: &PreflightSubject,
+    issues: &mut Vec<PreflightIssue>,
+) {
+    let dpi = pixel_width as f32 * 25.4 / print_width_mm;
+    if dpi < PREFLIGHT_MIN_DPI {
+        issues.push(subject.issue(
+            "warning",
+            "low_resolution",
+            format!(
+                "Photo prints at {:.0} DPI; at least {:.0} DPI is recommended for print",
+                dpi, PREFLIGHT_MIN_DPI
+            ),
+        ));
+    }
+}
+
+/// Return the first line of a family card that is wider than its column
+fn overflowing_card_line(
+    card: &FamilyCardData,
+    col_width: f32,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    locale: Locale,
+) -> Option<String> {
+    if fonts.bold.width(&card.name, theme.cards.name_size) > col_width {
+        return Some(card.name.clone());
+    }
+
+    let family_lines = [
+        card.mailing_name.clone(),
+        card.address.clone(),
+        card.city_state_zip.clone(),
+        card.alt_address.clone(),
+        card.alt_city_state_zip.clone(),
+        card.phone.as_ref().map(|p| locale.format(Text::HomePhone, p)),
+    ];
+    if let Some(line) = family_lines
+        .into_iter()
+        .flatten()
+        .find(|line| fonts.regular.width(line, theme.cards.text_size) > col_width)
+    {
+        return Some(line);
+    }
 
-            result.push(FamilyWithMembers { family, members });
+    // Member lines are indented past the photo column when any member has a photo
+    let any_member_has_photo = card.members.iter().any(|m| m.photo_path.is_some());
+    let member_width = if any_member_has_photo {
+        col_width - theme.cards.member_photo_size - 1.0
+    } else {
+        col_width
+    };
+    for member in &card.members {
+        if fonts.bold.width(&member.name, theme.cards.text_size) > member_width {
+            return Some(member.name.clone());
         }
-        result
+        let lines = [member.phone.as_ref().map(|p| locale.format(Text::CellPhone, p)), member.email.clone()];
+        if let Some(line) = lines
+            .into_iter()
+            .flatten()
+            .find(|line| fonts.regular.width(line, theme.cards.text_size) > member_width)
+        {
+            return Some(line);
+        }
+    }
+
+    None
+}
+
+/// Pass 1 of PDF generation: query the directory data and lay it out as
+/// booklet pages in reading order. A back cover is padded out with blanks
+/// so it lands on the booklet's last page.
+fn build_content_pages(
+    app_handle: &AppHandle,
+    db: &Database,
+    options: &PdfOptions,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    locale: Locale,
+) -> Result<Vec<ContentPage>, String> {
+    let photos_dir = Database::get_photos_dir(app_handle);
+
+    // Query all families with members, alphabetized the way the directory's
+    // language sorts names
+    let mut families_with_members = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        load_families_with_members(&conn)?
     };
+    families_with_members.sort_by(|a, b| locale.compare(&a.family.name, &b.family.name));
 
-    // Load staff data
-    let staff_entries: Vec<StaffEntry> = {
+    // Load staff data, grouped by category in page order
+    let staff_sections: Vec<StaffSection> = {
         let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let categories = query_staff_categories(&conn)?;
         let mut stmt = conn
             .prepare(
-                "SELECT name, title, role, photo_path FROM staff ORDER BY sort_order, name",
+                "SELECT name, title, role, photo_path, email, phone, office_hours, bio
+                 FROM staff ORDER BY sort_order, name",
             )
             .map_err(|e| e.to_string())?;
 
-        let rows: Vec<(String, String, String, Option<String>)> = stmt
+        let entries = stmt
             .query_map([], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, String>(2)?,
-                    row.get::<_, Option<String>>(3)?,
-                ))
-            })
-            .map_err(|e| e.to_string())?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| e.to_string())?;
-
-        rows.into_iter()
-            .map(|(name, title, role, photo_filename)| {
+                let photo_filename: Option<String> = row.get(3)?;
                 let photo_path = photo_filename.and_then(|f| {
                     let base = photos_dir.join("staff").join(&f);
                     let full_res = get_full_resolution_path(&base);
@@ -334,9 +1770,48 @@ pub fn generate_pdf(
                         None
                     }
                 });
-                StaffEntry { name, title, role, photo_path }
+                Ok(StaffEntry {
+                    name: row.get(0)?,
+                    title: row.get(1)?,
+                    role: row.get(2)?,
+                    photo_path,
+                    contact: staff_contact_lines(row.get(4)?, row.get(5)?, row.get(6)?, locale),
+                    bio: row.get::<_, Option<String>>(7)?.filter(|bio| !bio.trim().is_empty()),
+                })
             })
-            .collect()
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+
+        let known_keys: Vec<String> = categories.iter().map(|c| c.key.clone()).collect();
+        let mut sections: Vec<StaffSection> = categories
+            .into_iter()
+            .map(|category| StaffSection {
+                layout: StaffLayout::from_name(&category.layout),
+                photo_width: theme.staff.photo_width * category.photo_scale as f32,
+                photo_height: theme.staff.photo_height * category.photo_scale as f32,
+                title: category.title,
+                entries: entries.iter().filter(|e| e.role == category.key).cloned().collect(),
+            })
+            .collect();
+
+        // Staff whose category has gone missing still get printed
+        let mut missing: Vec<String> = Vec::new();
+        for entry in &entries {
+            if !known_keys.contains(&entry.role) && !missing.contains(&entry.role) {
+                missing.push(entry.role.clone());
+            }
+        }
+        for key in missing {
+            sections.push(StaffSection {
+                title: to_title_case(&key.replace('_', " ")),
+                layout: StaffLayout::PhotoGrid,
+                photo_width: theme.staff.photo_width,
+                photo_height: theme.staff.photo_height,
+                entries: entries.iter().filter(|e| e.role == key).cloned().collect(),
+            });
+        }
+        sections
     };
 
     // ========================================
@@ -344,22 +1819,40 @@ pub fn generate_pdf(
     // ========================================
     let mut content_pages: Vec<ContentPage> = Vec::new();
 
-    // Page 1: Cover image with title text
+    let bleed = print_bleed(options)?;
+
+    let church = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        ChurchIdentity::load(&conn, &options.church_name, &photos_dir)
+    };
+
+    // Page 1: Cover image with title text, or a cover composed from the
+    // church's logo and details
     {
-        let cover_base = resolve_image_page(&options.cover_image_path, &photos_dir);
+        let cover_base = resolve_image_page(&options.cover_image_path, &photos_dir, bleed);
         let line1 = options.cover_title_line1.clone().unwrap_or_default();
         let line2 = options.cover_title_line2.clone().unwrap_or_default();
         let color = options.cover_title_color.clone().unwrap_or_else(|| "#FFFFFF".to_string());
-        if let ContentPage::FullImage(path) = cover_base {
+        let layout = CoverLayout::from_name(options.cover_template.as_deref())?;
+        if let ContentPage::FullImage { image_path, bleed } = cover_base {
             content_pages.push(ContentPage::CoverImage {
-                image_path: path,
+                image_path,
+                bleed,
                 title_line1: line1,
                 title_line2: line2,
                 title_color: color,
             });
+        } else if let Some(layout) = layout {
+            content_pages.push(ContentPage::TemplateCover {
+                layout,
+                church: church.clone(),
+                title_line1: line1,
+                title_line2: line2,
+            });
         } else if !line1.is_empty() || !line2.is_empty() {
             content_pages.push(ContentPage::CoverImage {
                 image_path: PathBuf::new(),
+                bleed,
                 title_line1: line1,
                 title_line2: line2,
                 title_color: color,
@@ -369,91 +1862,95 @@ pub fn generate_pdf(
         }
     }
 
-    // Pastor letter + mission statement (inside front cover)
-    let has_pastor = options.pastor_letter.as_ref().map_or(false, |s| !s.is_empty());
-    let has_mission = options.mission_statement.as_ref().map_or(false, |s| !s.is_empty());
-    if has_pastor || has_mission {
-        content_pages.push(ContentPage::FirstInsidePage {
-            pastor_letter: options.pastor_letter.clone(),
-            mission_statement: options.mission_statement.clone(),
-        });
-    } else if let Some(ref markdown) = options.first_page_markdown {
-        if !markdown.is_empty() {
-            content_pages.push(ContentPage::MarkdownContent(markdown.clone()));
+    // Pastor letter + mission statement (inside front cover), continuing onto
+    // as many pages as the text needs
+    let markdown_images = photos_dir.join("directory");
+    let mut flow = MarkdownFlow::new(PAGE_WIDTH_MM - 2.0 * theme.markdown.margin_x, &theme.markdown, fonts);
+    let pastor_letter = options.pastor_letter.as_deref().filter(|s| !s.is_empty());
+    let mission_statement = options.mission_statement.as_deref().filter(|s| !s.is_empty());
+    if pastor_letter.is_some() || mission_statement.is_some() {
+        if let Some(letter) = pastor_letter {
+            flow.push_heading(locale.text(Text::LetterFromThePastor), false);
+            flow.push_markdown(letter, Some(&markdown_images));
+        }
+        if let Some(mission) = mission_statement {
+            if pastor_letter.is_some() {
+                flow.push_rule(6.0, 12.0);
+            }
+            flow.push_heading(locale.text(Text::MissionStatement), true);
+            flow.push_centered_lines(mission);
         }
+    } else if let Some(ref markdown) = options.first_page_markdown {
+        flow.push_markdown(markdown, Some(&markdown_images));
+    }
+    let markdown_height = PAGE_HEIGHT_MM - theme.markdown.margin_top - content_bottom(theme);
+    for items in flow.paginate(markdown_height, markdown_height) {
+        content_pages.push(ContentPage::Markdown(items));
+    }
+
+    // Table of contents — placeholder until the section start pages are known
+    let toc_idx = if options.include_table_of_contents {
+        content_pages.push(ContentPage::TableOfContents(Vec::new()));
+        Some(content_pages.len() - 1)
+    } else {
+        None
+    };
+
+    // Content page index where each section begins, in reading order
+    let mut section_starts: Vec<(&str, usize)> = Vec::new();
+
+    // Staff, by category
+    let staff_bios: Vec<StaffEntry> = if options.include_staff_bios {
+        staff_sections
+            .iter()
+            .flat_map(|section| &section.entries)
+            .filter(|entry| entry.bio.is_some())
+            .cloned()
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let staff_pages = paginate_staff(staff_sections, theme, locale);
+    if !staff_pages.is_empty() {
+        section_starts.push((locale.text(Text::Staff), content_pages.len()));
+        content_pages.extend(staff_pages.into_iter().map(ContentPage::StaffPage));
     }
 
-    // Pastor, Elders & Staff
-    if !staff_entries.is_empty() {
-        content_pages.push(ContentPage::StaffPage(staff_entries));
+    // Meet Our Staff
+    let bio_pages = paginate_staff_bios(staff_bios, theme, fonts);
+    if !bio_pages.is_empty() {
+        section_starts.push((locale.text(Text::MeetOurStaff), content_pages.len()));
+        content_pages.extend(bio_pages.into_iter().map(ContentPage::StaffBios));
     }
 
-    // Page 4: Ministry Team Leadership
+    // Ministry Team Leadership, in the user's order
     let leadership_entries: Vec<LeadershipEntry> = {
         let conn = db.conn.lock().map_err(|e| e.to_string())?;
         let mut stmt = conn
-            .prepare("SELECT ministry, names FROM leadership ORDER BY ministry COLLATE NOCASE")
+            .prepare("SELECT id, ministry, contact FROM leadership ORDER BY sort_order, ministry COLLATE NOCASE")
             .map_err(|e| e.to_string())?;
 
-        let rows: Vec<(String, String)> = stmt
-            .query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-            })
+        let rows: Vec<(i64, String, Option<String>)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
             .map_err(|e| e.to_string())?
             .collect::<Result<Vec<_>, _>>()
             .map_err(|e| e.to_string())?;
 
         rows.into_iter()
-            .map(|(ministry, names)| LeadershipEntry { ministry, names })
-            .collect()
+            .map(|(id, ministry, contact)| {
+                let names = leadership_people(&conn, id)?.into_iter().map(|person| person.name).collect();
+                let contact = contact.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+                Ok(LeadershipEntry { ministry, names, contact })
+            })
+            .collect::<Result<_, String>>()?
     };
 
     if !leadership_entries.is_empty() {
-        // Build contact info lines from settings
-        let (contact_left, contact_right) = {
-            let conn = db.conn.lock().map_err(|e| e.to_string())?;
-            let mut left_lines = Vec::new();
-            let mut right_lines = Vec::new();
-
-            // Left column: address + phone
-            for key in &["church_address", "church_phone"] {
-                if let Ok(value) = conn.query_row(
-                    "SELECT value FROM settings WHERE key = ?",
-                    params![key],
-                    |row| row.get::<_, String>(0),
-                ) {
-                    if !value.is_empty() {
-                        for line in value.lines() {
-                            let trimmed = line.trim();
-                            if !trimmed.is_empty() {
-                                left_lines.push(trimmed.to_string());
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Right column: email + website
-            for key in &["church_email", "church_website"] {
-                if let Ok(value) = conn.query_row(
-                    "SELECT value FROM settings WHERE key = ?",
-                    params![key],
-                    |row| row.get::<_, String>(0),
-                ) {
-                    if !value.is_empty() {
-                        right_lines.push(value);
-                    }
-                }
-            }
-
-            (left_lines, right_lines)
-        };
-
-        content_pages.push(ContentPage::LeadershipPage {
-            entries: leadership_entries,
-            contact_left,
-            contact_right,
-        });
+        let (contact_left, contact_right) = church.contact_columns();
+        section_starts.push((locale.text(Text::Leadership), content_pages.len()));
+        for page in paginate_leadership(leadership_entries, contact_left, contact_right, theme, fonts) {
+            content_pages.push(ContentPage::LeadershipPage(page));
+        }
     }
 
     // Pages 5+: Photo grid (only families with photos and include_photo_in_directory enabled)
@@ -479,6 +1976,7 @@ pub fn generate_pdf(
                     .unwrap_or_else(|| fwm.family.name.clone());
                 grid_entries.push(PhotoGridEntry {
                     photo_path: photo_file,
+                    photo_crop: fwm.family.photo_crop,
                     family_name: fwm.family.name.clone(),
                     display_name,
                     directory_adults: fwm.family.directory_adults.clone(),
@@ -492,11 +1990,15 @@ pub fn generate_pdf(
     // so alphabetical order reads top-to-bottom within each column.
     let grid_rows = options.photo_grid_rows.unwrap_or(4);
     let entries_per_page = GRID_COLS * grid_rows;
+    if !grid_entries.is_empty() {
+        section_starts.push((locale.text(Text::PhotoDirectory), content_pages.len()));
+    }
     for chunk in grid_entries.chunks(entries_per_page) {
         let page_entries: Vec<PhotoGridEntry> = chunk
             .iter()
             .map(|e| PhotoGridEntry {
                 photo_path: e.photo_path.clone(),
+                photo_crop: e.photo_crop,
                 family_name: e.family_name.clone(),
                 display_name: e.display_name.clone(),
                 directory_adults: e.directory_adults.clone(),
@@ -510,131 +2012,20 @@ pub fn generate_pdf(
     // Text-only card pages for ALL families
     let card_data: Vec<FamilyCardData> = families_with_members
         .iter()
-        .map(|fwm| {
-            let mut city_state_zip_parts: Vec<String> = Vec::new();
-            if let Some(ref city) = fwm.family.city.as_ref().filter(|s| !s.trim().is_empty()) {
-                city_state_zip_parts.push(city.to_string());
-            }
-            if let Some(ref state) = fwm.family.state.as_ref().filter(|s| !s.trim().is_empty()) {
-                if let Some(last) = city_state_zip_parts.pop() {
-                    city_state_zip_parts.push(format!("{}, {}", last, state));
-                } else {
-                    city_state_zip_parts.push(state.to_string());
-                }
-            }
-            if let Some(ref zip) = fwm.family.zip.as_ref().filter(|s| !s.trim().is_empty()) {
-                city_state_zip_parts.push(zip.to_string());
-            }
-            let city_state_zip = if city_state_zip_parts.is_empty() {
-                None
-            } else {
-                let joined = city_state_zip_parts.join(" ");
-                let trimmed = joined.trim().trim_matches(',').trim();
-                if trimmed.is_empty() { None } else { Some(joined) }
-            };
-
-            // Build alt city/state/zip
-            let mut alt_csz_parts: Vec<String> = Vec::new();
-            if let Some(ref city) = fwm.family.alt_city.as_ref().filter(|s| !s.trim().is_empty()) {
-                alt_csz_parts.push(city.to_string());
-            }
-            if let Some(ref state) = fwm.family.alt_state.as_ref().filter(|s| !s.trim().is_empty()) {
-                if let Some(last) = alt_csz_parts.pop() {
-                    alt_csz_parts.push(format!("{}, {}", last, state));
-                } else {
-                    alt_csz_parts.push(state.to_string());
-                }
-            }
-            if let Some(ref zip) = fwm.family.alt_zip.as_ref().filter(|s| !s.trim().is_empty()) {
-                alt_csz_parts.push(zip.to_string());
-            }
-            let alt_city_state_zip = if alt_csz_parts.is_empty() {
-                None
-            } else {
-                let joined = alt_csz_parts.join(" ");
-                let trimmed = joined.trim().trim_matches(',').trim();
-                if trimmed.is_empty() { None } else { Some(joined) }
-            };
-
-            let members: Vec<MemberCardInfo> = fwm.members.iter().map(|m| {
-                let photo_path = m.photo_path.as_ref().and_then(|pp| {
-                    let base = photos_dir.join("members").join(pp);
-                    let full = get_full_resolution_path(&base);
-                    let resolved = if full.exists() { full } else { base };
-                    if resolved.exists() { Some(resolved) } else { None }
-                });
-                MemberCardInfo {
-                    name: format!("{} {}", m.first_name, m.last_name),
-                    phone: m.phone.clone().filter(|s| !s.trim().is_empty()),
-                    email: m.email.clone().filter(|s| !s.trim().is_empty()),
-                    photo_path,
-                }
-            }).collect();
-
-            FamilyCardData {
-                name: fwm.family.name.clone(),
-                mailing_name: fwm.family.mailing_name.clone().filter(|s| !s.trim().is_empty() && s.trim() != ","),
-                address: fwm.family.address.clone().filter(|s| !s.trim().is_empty()),
-                city_state_zip,
-                alt_address: fwm.family.alt_address.clone().filter(|s| !s.trim().is_empty()),
-                alt_city_state_zip,
-                phone: fwm.family.phone.clone().filter(|s| !s.trim().is_empty()),
-                members,
-            }
-        })
+        .map(|fwm| family_card_data(fwm, &photos_dir))
         .collect();
 
     // Paginate cards into pages
-    let card_pages = paginate_cards(&card_data);
+    let card_pages = paginate_cards(&card_data, theme);
+    if !card_pages.is_empty() {
+        section_starts.push((locale.text(Text::FamilyListings), content_pages.len()));
+    }
     for page_cards in card_pages {
         content_pages.push(ContentPage::TextCards(page_cards));
     }
 
     // Build birthday & anniversary celebration pages
-    let month_names = [
-        "January", "February", "March", "April", "May", "June",
-        "July", "August", "September", "October", "November", "December",
-    ];
-    let mut celebration_months: Vec<CelebrationMonth> = (0..12)
-        .map(|i| CelebrationMonth {
-            month_name: month_names[i].to_string(),
-            birthdays: Vec::new(),
-            anniversaries: Vec::new(),
-        })
-        .collect();
-
-    for fwm in &families_with_members {
-        for member in &fwm.members {
-            if let Some(ref bd) = member.birth_date {
-                if let Some((month_idx, day, display)) = parse_month_day(bd) {
-                    celebration_months[month_idx].birthdays.push(CelebrationEntry {
-                        name: format!("{} {}", member.first_name, member.last_name),
-                        day,
-                        display_date: display,
-                    });
-                }
-            }
-            if let Some(ref wd) = member.wedding_date {
-                if let Some((month_idx, day, display)) = parse_month_day(wd) {
-                    // Use family name for anniversaries (couple event)
-                    let ann_name = fwm.family.mailing_name.clone()
-                        .unwrap_or_else(|| fwm.family.name.clone());
-                    celebration_months[month_idx].anniversaries.push(CelebrationEntry {
-                        name: ann_name,
-                        day,
-                        display_date: display,
-                    });
-                }
-            }
-        }
-    }
-
-    // Sort entries by day within each month and deduplicate anniversaries
-    for month in &mut celebration_months {
-        month.birthdays.sort_by_key(|e| e.day);
-        month.anniversaries.sort_by_key(|e| e.day);
-        month.anniversaries.dedup_by(|a, b| a.name == b.name && a.day == b.day);
-    }
+    let celebration_months = gather_celebrations(&families_with_members, options, locale);
 
     // Add celebration pages immediately after directory content (no blank gap)
     let has_celebrations = celebration_months.iter().any(|m| !m.birthdays.is_empty() || !m.anniversaries.is_empty());
@@ -647,199 +2038,192 @@ pub fn generate_pdf(
             if path.exists() { Some(path) } else { None }
         });
 
-        let celeb_pages = paginate_celebrations(&celebration_months, celeb_image);
+        let celeb_pages = paginate_celebrations(&celebration_months, celeb_image, theme, locale);
+        section_starts.push((locale.text(Text::Celebrations), content_pages.len()));
         for page in celeb_pages {
             content_pages.push(ContentPage::CelebrationPages(page));
         }
     }
 
-    // Pad with blanks at the end to reach a multiple of 4
-    while content_pages.len() % 4 != 0 {
-        content_pages.push(ContentPage::Blank);
+    // Fill in the table of contents now that every section's start page is known.
+    // Page numbers follow reading order (the cover is page 1), not sheet order.
+    if let Some(idx) = toc_idx {
+        let toc_entries = section_starts
+            .iter()
+            .map(|(title, start)| TocEntry {
+                title: title.to_string(),
+                page: start + 1,
+            })
+            .collect();
+        content_pages[idx] = ContentPage::TableOfContents(toc_entries);
     }
 
-    // ========================================
-    // Pass 2: Imposition and PDF rendering
-    // ========================================
-    let total = content_pages.len();
-    let num_sheets = total / 4;
-    let current_year = chrono::Utc::now().format("%Y").to_string();
-
-    let (doc, page1, layer1) = PdfDocument::new(
-        &options.church_name,
-        Mm(SHEET_WIDTH_MM),
-        Mm(SHEET_HEIGHT_MM),
-        "Layer 1",
-    );
-
-    let font = doc
-        .add_builtin_font(BuiltinFont::Helvetica)
-        .map_err(|e| format!("Font error: {:?}", e))?;
-    let font_bold = doc
-        .add_builtin_font(BuiltinFont::HelveticaBold)
-        .map_err(|e| format!("Font error: {:?}", e))?;
-
-    for s in 0..num_sheets {
-        // Front side of sheet
-        let left_idx = total - 1 - 2 * s;
-        let right_idx = 2 * s;
-
-        let front_layer = if s == 0 {
-            doc.get_page(page1).get_layer(layer1)
-        } else {
-            let (new_page, new_layer) =
-                doc.add_page(Mm(SHEET_WIDTH_MM), Mm(SHEET_HEIGHT_MM), "Sheet");
-            doc.get_page(new_page).get_layer(new_layer)
-        };
-
-        render_content_page(
-            &doc,
-            &front_layer,
-            &content_pages[left_idx],
-            0.0,
-            true,
-            &font,
-            &font_bold,
-        );
-        if content_pages[left_idx].needs_footer() {
-            render_footer(&front_layer, 0.0, &options.church_name, &current_year, &font);
-        }
-        render_content_page(
-            &doc,
-            &front_layer,
-            &content_pages[right_idx],
-            PAGE_WIDTH_MM,
-            false,
-            &font,
-            &font_bold,
-        );
-        if content_pages[right_idx].needs_footer() {
-            render_footer(&front_layer, PAGE_WIDTH_MM, &options.church_name, &current_year, &font);
-        }
-
-        // Back side of sheet
-        let left_idx = 2 * s + 1;
-        let right_idx = total - 2 - 2 * s;
-
-        let (back_page, back_layer_id) =
-            doc.add_page(Mm(SHEET_WIDTH_MM), Mm(SHEET_HEIGHT_MM), "Sheet");
-        let back_layer = doc.get_page(back_page).get_layer(back_layer_id);
-
-        render_content_page(
-            &doc,
-            &back_layer,
-            &content_pages[left_idx],
-            0.0,
-            true,
-            &font,
-            &font_bold,
-        );
-        if content_pages[left_idx].needs_footer() {
-            render_footer(&back_layer, 0.0, &options.church_name, &current_year, &font);
-        }
-        render_content_page(
-            &doc,
-            &back_layer,
-            &content_pages[right_idx],
-            PAGE_WIDTH_MM,
-            false,
-            &font,
-            &font_bold,
-        );
-        if content_pages[right_idx].needs_footer() {
-            render_footer(&back_layer, PAGE_WIDTH_MM, &options.church_name, &current_year, &font);
-        }
+    // Back cover image, or one composed from the church's logo and details,
+    // on the outside of the last sheet
+    let back_cover = match resolve_image_page(&options.back_cover_image_path, &photos_dir, bleed) {
+        ContentPage::Blank => CoverLayout::from_name(options.back_cover_template.as_deref())?
+            .map(|layout| ContentPage::TemplateBackCover { layout, church }),
+        image => Some(image),
+    };
+    if let Some(back_cover) = back_cover {
+        let imposition = Imposition::from_name(options.imposition.as_deref(), options.signature_sheets)?;
+        let page_count = imposition.padded_page_count(content_pages.len() + 1, options.duplex.unwrap_or(true));
+        content_pages.resize_with(page_count - 1, || ContentPage::Blank);
+        content_pages.push(back_cover);
     }
 
-    let file = File::create(&output_path).map_err(|e| e.to_string())?;
-    let mut buf_writer = BufWriter::new(file);
-    doc.save(&mut buf_writer)
-        .map_err(|e| format!("Save error: {:?}", e))?;
-
-    Ok(output_path)
+    Ok(content_pages)
 }
 
-// Page border (1/4 inch = 6.35mm)
-const PAGE_BORDER_INSET: f32 = 6.35;
-const FOOTER_MARGIN: f32 = 10.0;
-const FOOTER_FONT_SIZE: f32 = 7.0;
-const FOOTER_Y: f32 = 10.0;
-
 fn render_footer(
-    layer: &PdfLayerReference,
+    canvas: &dyn PageCanvas,
     x_offset: f32,
     church_name: &str,
     year: &str,
-    font: &IndirectFontRef,
+    page_number: usize,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
 ) {
-    layer.set_fill_color(Color::Greyscale(Greyscale::new(0.35, None)));
+    canvas.set_fill_color(&theme.colors.muted);
     // Left: church name
-    layer.use_text(
+    canvas.text(
         church_name,
-        FOOTER_FONT_SIZE,
-        Mm(x_offset + FOOTER_MARGIN),
-        Mm(FOOTER_Y),
-        font,
+        theme.footer.font_size,
+        x_offset + theme.footer.margin_x,
+        theme.footer.baseline,
+        &fonts.regular,
+    );
+    // Center: page number in reading order
+    let page_text = page_number.to_string();
+    let page_width = fonts.regular.width(&page_text, theme.footer.font_size);
+    canvas.text(
+        &page_text,
+        theme.footer.font_size,
+        x_offset + (PAGE_WIDTH_MM - page_width) / 2.0,
+        theme.footer.baseline,
+        &fonts.regular,
     );
-    // Right: year — estimate text width for right-alignment
-    let year_width = year.len() as f32 * FOOTER_FONT_SIZE * 0.22;
-    layer.use_text(
+    // Right: year
+    let year_width = fonts.regular.width(year, theme.footer.font_size);
+    canvas.text(
         year,
-        FOOTER_FONT_SIZE,
-        Mm(x_offset + PAGE_WIDTH_MM - FOOTER_MARGIN - year_width),
-        Mm(FOOTER_Y),
-        font,
+        theme.footer.font_size,
+        x_offset + PAGE_WIDTH_MM - theme.footer.margin_x - year_width,
+        theme.footer.baseline,
+        &fonts.regular,
     );
-    // Reset fill color to black
-    layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-}
-
-fn ordinal_suffix(day: u32) -> &'static str {
-    match (day % 10, day % 100) {
-        (1, 11) => "th",
-        (2, 12) => "th",
-        (3, 13) => "th",
-        (1, _) => "st",
-        (2, _) => "nd",
-        (3, _) => "rd",
-        _ => "th",
-    }
-}
-
-/// Parse a date string into (month_index 0-11, day, display_string like "March 15th")
-fn parse_month_day(date_str: &str) -> Option<(usize, u32, String)> {
-    let parts: Vec<&str> = date_str.split('-').collect();
-    match parts.len() {
-        2 => {
-            // MM-DD format
-            let month: u32 = parts[0].parse().ok()?;
-            let day: u32 = parts[1].parse().ok()?;
-            if month >= 1 && month <= 12 && day >= 1 && day <= 31 {
-                let month_idx = (month - 1) as usize;
-                Some((month_idx, day, format!("{}{}", day, ordinal_suffix(day))))
-            } else {
-                None
+    // Reset fill color to body text
+    canvas.set_fill_color(&theme.colors.text);
+}
+
+/// Birthdays and anniversaries by month, each month sorted by day then name
+pub fn gather_celebrations(families: &[FamilyWithMembers], options: &PdfOptions, locale: Locale) -> Vec<CelebrationMonth> {
+    let mut celebration_months: Vec<CelebrationMonth> = (0..12)
+        .map(|i| CelebrationMonth {
+            month_name: locale.month_name(i).to_string(),
+            birthdays: Vec::new(),
+            anniversaries: Vec::new(),
+        })
+        .collect();
+
+    let celebrations_year = options.celebrations_year.unwrap_or_else(|| Local::now().year());
+    for fwm in families {
+        for member in &fwm.members {
+            if let Some(bd) = member.birth_date {
+                let age = bd
+                    .years_in(celebrations_year)
+                    .filter(|&age| options.highlight_birthday_milestones && (age >= 100 || BIRTHDAY_MILESTONES.contains(&age)));
+                celebration_months[bd.month_idx()].birthdays.push(CelebrationEntry {
+                    name: format!("{} {}", member.first_name, member.last_name),
+                    date: bd,
+                    member_ids: vec![member.id],
+                    display_date: locale.day(bd.day),
+                    note: age.map(|age| locale.format(Text::BirthdayAge, &locale.ordinal(age))),
+                    milestone: age.is_some(),
+                });
             }
         }
-        3 => {
-            // YYYY-MM-DD format — ignore year
-            let month: u32 = parts[1].parse().ok()?;
-            let day: u32 = parts[2].parse().ok()?;
-            if month >= 1 && month <= 12 && day >= 1 && day <= 31 {
-                let month_idx = (month - 1) as usize;
-                Some((month_idx, day, format!("{}{}", day, ordinal_suffix(day))))
-            } else {
-                None
+        for (wd, couple) in family_anniversaries(fwm) {
+            let years = wd.years_in(celebrations_year);
+            let milestone = years.is_some_and(|years| ANNIVERSARY_MILESTONES.contains(&years));
+            celebration_months[wd.month_idx()].anniversaries.push(CelebrationEntry {
+                name: couple_name(&couple),
+                date: wd,
+                member_ids: couple.iter().map(|m| m.id).collect(),
+                display_date: locale.day(wd.day),
+                note: years
+                    .filter(|_| milestone || options.show_anniversary_years)
+                    .map(|years| locale.format(Text::AnniversaryYears, &locale.ordinal(years))),
+                milestone,
+            });
+        }
+    }
+
+    // Sort entries by day within each month
+    for month in &mut celebration_months {
+        month.birthdays.sort_by(|a, b| a.date.day.cmp(&b.date.day).then_with(|| locale.compare(&a.name, &b.name)));
+        month.anniversaries.sort_by(|a, b| a.date.day.cmp(&b.date.day).then_with(|| locale.compare(&a.name, &b.name)));
+    }
+    celebration_months
+}
+
+/// A family's anniversaries, each with the members married on that date.
+/// Members who share a wedding date are one couple; when only one spouse has
+/// the date, the family's only other head of household or spouse is paired
+/// with them.
+fn family_anniversaries(fwm: &FamilyWithMembers) -> Vec<(PartialDate, Vec<&Member>)> {
+    let mut couples: Vec<(PartialDate, Vec<&Member>)> = Vec::new();
+    for member in &fwm.members {
+        let Some(date) = member.wedding_date else {
+            continue;
+        };
+        match couples.iter_mut().find(|(d, _)| d.month == date.month && d.day == date.day) {
+            Some((d, members)) => {
+                d.year = d.year.or(date.year);
+                members.push(member);
             }
+            None => couples.push((date, vec![member])),
+        }
+    }
+
+    let partners: Vec<&Member> = fwm
+        .members
+        .iter()
+        .filter(|m| m.wedding_date.is_none() && is_partner_role(m.role.as_deref()))
+        .collect();
+    if let ([(_, members)], [partner]) = (couples.as_mut_slice(), partners.as_slice()) {
+        if members.len() == 1 && is_partner_role(members[0].role.as_deref()) {
+            members.push(partner);
+            members.sort_by_key(|m| m.sort_order);
         }
-        _ => None,
+    }
+    couples
+}
+
+fn is_partner_role(role: Option<&str>) -> bool {
+    let role = role.unwrap_or("").to_lowercase();
+    ["head", "spouse", "husband", "wife"].iter().any(|r| role.contains(r))
+}
+
+/// "John & Mary Smith", or "John Smith & Mary Jones" when the last names differ
+fn couple_name(members: &[&Member]) -> String {
+    let last_name = &members[0].last_name;
+    if members.iter().all(|m| &m.last_name == last_name) {
+        let first_names: Vec<&str> = members.iter().map(|m| m.first_name.as_str()).collect();
+        format!("{} {}", first_names.join(" & "), last_name)
+    } else {
+        members
+            .iter()
+            .map(|m| format!("{} {}", m.first_name, m.last_name))
+            .collect::<Vec<_>>()
+            .join(" & ")
     }
 }
 
 /// Estimate the height of a celebration month block in mm
 
 /// Flatten celebration months into a linear stream of renderable items
-fn flatten_celebrations(months: &[CelebrationMonth]) -> Vec<CelebItem> {
+fn flatten_celebrations(months: &[CelebrationMonth], locale: Locale) -> Vec<CelebItem> {
     let mut items: Vec<CelebItem> = Vec::new();
     for month in months {
         if month.birthdays.is_empty() && month.anniversaries.is_empty() {
@@ -847,20 +2231,24 @@ fn flatten_celebrations(months: &[CelebrationMonth]) -> Vec<CelebItem> {
         }
         items.push(CelebItem::MonthHeader { name: month.month_name.clone(), continued: false });
         if !month.birthdays.is_empty() {
-            items.push(CelebItem::SectionHeader("Birthdays".to_string()));
+            items.push(CelebItem::SectionHeader(locale.text(Text::Birthdays).to_string()));
             for entry in &month.birthdays {
                 items.push(CelebItem::Entry {
                     display_date: entry.display_date.clone(),
                     name: entry.name.clone(),
+                    note: entry.note.clone(),
+                    milestone: entry.milestone,
                 });
             }
         }
         if !month.anniversaries.is_empty() {
-            items.push(CelebItem::SectionHeader("Anniversaries".to_string()));
+            items.push(CelebItem::SectionHeader(locale.text(Text::Anniversaries).to_string()));
             for entry in &month.anniversaries {
                 items.push(CelebItem::Entry {
                     display_date: entry.display_date.clone(),
                     name: entry.name.clone(),
+                    note: entry.note.clone(),
+                    milestone: entry.milestone,
                 });
             }
         }
@@ -888,10 +2276,10 @@ fn has_remaining_entries(items: &[CelebItem], from_idx: usize) -> bool {
 
 /// Calculate the minimum height needed to show a useful start of the next content:
 /// the current item plus enough room for at least one Entry line.
-fn min_useful_height(items: &[CelebItem], from_idx: usize) -> f32 {
+fn min_useful_height(items: &[CelebItem], from_idx: usize, theme: &PdfTheme) -> f32 {
     let mut h = 0.0;
     for item in &items[from_idx..] {
-        h += item.height();
+        h += item.height(theme);
         if matches!(item, CelebItem::Entry { .. }) {
             return h;
         }
@@ -907,13 +2295,14 @@ fn fill_column(
     max_height: f32,
     column: &mut Vec<CelebItem>,
     current_month_name: &mut Option<String>,
+    theme: &PdfTheme,
 ) -> usize {
     let mut idx = start_idx;
-    let mut h: f32 = column.iter().map(|i| i.height()).sum();
+    let mut h: f32 = column.iter().map(|i| i.height(theme)).sum();
 
     while idx < items.len() {
         let item = &items[idx];
-        let item_h = item.height();
+        let item_h = item.height(theme);
 
         // If column is non-empty and this item won't fit, stop
         if h + item_h > max_height && !column.is_empty() {
@@ -923,7 +2312,7 @@ fn fill_column(
         // Before adding a MonthHeader or SectionHeader, ensure there's room
         // for it PLUS at least one Entry line. Otherwise, defer to next column.
         if matches!(item, CelebItem::MonthHeader { .. } | CelebItem::SectionHeader(_)) && !column.is_empty() {
-            let needed = min_useful_height(items, idx);
+            let needed = min_useful_height(items, idx, theme);
             if h + needed > max_height {
                 break;
             }
@@ -947,12 +2336,17 @@ fn fill_column(
 
 /// Paginate celebration items into pages with two flowing columns per page.
 /// When a month spans into a new column, a "(Continued)" header is inserted.
-fn paginate_celebrations(months: &[CelebrationMonth], image_path: Option<PathBuf>) -> Vec<CelebPage> {
-    let title_block_height = CELEB_TITLE_SIZE * 0.4 + 8.0; // title + line + gap
-    let col_height_first = PAGE_HEIGHT_MM - CELEB_MARGIN_TOP - CELEB_MARGIN_BOTTOM - title_block_height;
-    let col_height_rest = PAGE_HEIGHT_MM - CELEB_MARGIN_TOP - CELEB_MARGIN_BOTTOM;
-
-    let items = flatten_celebrations(months);
+fn paginate_celebrations(
+    months: &[CelebrationMonth],
+    image_path: Option<PathBuf>,
+    theme: &PdfTheme,
+    locale: Locale,
+) -> Vec<CelebPage> {
+    let title_block_height = theme.celebrations.title_size * 0.4 + 8.0; // title + line + gap
+    let col_height_first = PAGE_HEIGHT_MM - theme.celebrations.margin_top - theme.celebrations.margin_bottom - title_block_height;
+    let col_height_rest = PAGE_HEIGHT_MM - theme.celebrations.margin_top - theme.celebrations.margin_bottom;
+
+    let items = flatten_celebrations(months, locale);
     let mut pages: Vec<CelebPage> = Vec::new();
     let mut idx = 0;
     let mut is_first = true;
@@ -965,7 +2359,7 @@ fn paginate_celebrations(months: &[CelebrationMonth], image_path: Option<PathBuf
 
         // Fill left column
         let mut current_month_name: Option<String> = None;
-        idx = fill_column(&items, idx, max_h, &mut left, &mut current_month_name);
+        idx = fill_column(&items, idx, max_h, &mut left, &mut current_month_name, theme);
 
         // Fill right column if there are more items
         if idx < items.len() {
@@ -987,7 +2381,7 @@ fn paginate_celebrations(months: &[CelebrationMonth], image_path: Option<PathBuf
                 idx += 1;
             }
 
-            idx = fill_column(&items, idx, max_h, &mut right, &mut current_month_name);
+            idx = fill_column(&items, idx, max_h, &mut right, &mut current_month_name, theme);
         }
 
         pages.push(CelebPage {
@@ -1024,131 +2418,122 @@ fn paginate_celebrations(months: &[CelebrationMonth], image_path: Option<PathBuf
 
 /// Render a single column of celebration items
 fn render_celeb_column(
-    layer: &PdfLayerReference,
+    canvas: &dyn PageCanvas,
     items: &[CelebItem],
     col_x: f32,
     start_y: f32,
-    font: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    locale: Locale,
 ) {
     let mut y = start_y;
     for item in items {
         match item {
             CelebItem::MonthHeader { name, continued } => {
                 let heading = if *continued {
-                    format!("{} (Continued)", name)
+                    locale.format(Text::Continued, name)
                 } else {
                     name.clone()
                 };
-                layer.use_text(&heading, CELEB_MONTH_SIZE, Mm(col_x), Mm(y), font_bold);
+                canvas.set_fill_color(&theme.colors.heading);
+                canvas.text(&heading, theme.celebrations.month_size, col_x, y, &fonts.bold);
                 y -= 2.0;
                 // Short accent line under month name
-                let accent_points = vec![
-                    (Point::new(Mm(col_x), Mm(y)), false),
-                    (Point::new(Mm(col_x + 20.0), Mm(y)), false),
-                ];
-                let accent_line = Line {
-                    points: accent_points,
-                    is_closed: false,
-                };
-                layer.set_outline_color(Color::Greyscale(Greyscale::new(0.7, None)));
-                layer.set_outline_thickness(0.25);
-                layer.add_line(accent_line);
+                canvas.set_outline_color(&theme.colors.accent);
+                canvas.set_outline_thickness(0.25);
+                canvas.line(col_x, y, col_x + 20.0, y);
                 y -= 5.0;
             }
             CelebItem::SectionHeader(label) => {
-                layer.use_text(label, CELEB_SECTION_SIZE, Mm(col_x), Mm(y), font_bold);
-                y -= CELEB_SECTION_SIZE * 0.4 + CELEB_SECTION_SPACING * 0.5;
+                canvas.set_fill_color(&theme.colors.heading);
+                canvas.text(label, theme.celebrations.section_size, col_x, y, &fonts.bold);
+                y -= theme.celebrations.section_size * 0.4 + theme.celebrations.section_spacing * 0.5;
             }
-            CelebItem::Entry { display_date, name } => {
+            CelebItem::Entry { display_date, name, note, milestone } => {
+                let size = theme.celebrations.text_size;
                 let date_text = format!("{}  ", display_date);
-                layer.use_text(&date_text, CELEB_TEXT_SIZE, Mm(col_x), Mm(y), font);
-                layer.use_text(name, CELEB_TEXT_SIZE, Mm(col_x + 8.0), Mm(y), font);
-                y -= CELEB_LINE_HEIGHT;
+                canvas.set_fill_color(&theme.colors.text);
+                canvas.text(&date_text, size, col_x, y, &fonts.regular);
+                let name_font = if *milestone { &fonts.bold } else { &fonts.regular };
+                canvas.text(name, size, col_x + 8.0, y, name_font);
+                if let Some(note) = note {
+                    let note_x = col_x + 8.0 + name_font.width(name, size) + 2.0;
+                    let color = if *milestone { &theme.colors.accent } else { &theme.colors.muted };
+                    canvas.set_fill_color(color);
+                    canvas.text(note, size, note_x, y, &fonts.italic);
+                }
+                y -= theme.celebrations.line_height;
             }
             CelebItem::MonthGap => {
-                y -= CELEB_MONTH_SPACING;
+                y -= theme.celebrations.month_spacing;
             }
         }
     }
 }
 
+/// Printed height of the header image beside the celebrations title
+fn celebration_image_height(theme: &PdfTheme) -> f32 {
+    theme.celebrations.title_size * 1.8
+}
+
 /// Render celebration pages with flowing two-column layout
 fn render_celebrations(
-    _doc: &PdfDocumentReference,
-    layer: &PdfLayerReference,
+    canvas: &dyn PageCanvas,
     celeb_page: &CelebPage,
     x_offset: f32,
-    font: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    images: &PreparedImages,
+    locale: Locale,
 ) {
-    let usable_width = PAGE_WIDTH_MM - 2.0 * CELEB_MARGIN_TOP;
-    let col_width = (usable_width - CELEB_COL_GAP) / 2.0;
+    let usable_width = PAGE_WIDTH_MM - 2.0 * theme.celebrations.margin_x;
+    let col_width = (usable_width - theme.celebrations.col_gap) / 2.0;
 
-    let mut y = PAGE_HEIGHT_MM - CELEB_MARGIN_TOP;
+    let mut y = PAGE_HEIGHT_MM - theme.celebrations.margin_top;
 
     if celeb_page.is_first {
         // Centered title
-        let title = "Birthdays & Anniversaries";
-        let title_width = measure_helvetica_width(title, CELEB_TITLE_SIZE) * 1.07;
-        let title_x = x_offset + CELEB_MARGIN_TOP + (usable_width - title_width) / 2.0;
-        layer.use_text(title, CELEB_TITLE_SIZE, Mm(title_x), Mm(y), font_bold);
+        let title = locale.text(Text::BirthdaysAndAnniversaries);
+        let title_width = fonts.bold.width(title, theme.celebrations.title_size);
+        let title_x = x_offset + theme.celebrations.margin_x + (usable_width - title_width) / 2.0;
+        canvas.set_fill_color(&theme.colors.heading);
+        canvas.text(title, theme.celebrations.title_size, title_x, y, &fonts.bold);
 
         // Header image placed just to the right of the title text
-        if let Some(ref img_path) = celeb_page.image_path {
-            if let Ok(img) = image_crate::open(img_path) {
-                use image_crate::GenericImageView;
-                let (img_w, img_h) = img.dimensions();
-                let img_aspect = img_w as f32 / img_h as f32;
-                let img_render_h = CELEB_TITLE_SIZE * 1.8;
-                let img_render_w = img_render_h * img_aspect;
-                let img_x = title_x + title_width + 6.0;
-                let img_y = y - img_render_h + CELEB_TITLE_SIZE * 0.5 + 6.0;
-                let dpi = (img_w as f32 * 25.4) / img_render_w;
-                let image_xobject = image_to_xobject_with_transparency(&img);
-                let image = Image::from(image_xobject);
-                image.add_to_layer(
-                    layer.clone(),
-                    ImageTransform {
-                        translate_x: Some(Mm(img_x)),
-                        translate_y: Some(Mm(img_y)),
-                        dpi: Some(dpi),
-                        ..Default::default()
-                    },
-                );
-            }
+        let header_image = celeb_page
+            .image_path
+            .as_ref()
+            .and_then(|path| images.get(path, ImageTreatment::Flattened));
+        if let Some(image) = header_image {
+            let img_render_h = celebration_image_height(theme);
+            let img_render_w = img_render_h * image.aspect();
+            let img_x = title_x + title_width + 6.0;
+            let img_y = y - img_render_h + theme.celebrations.title_size * 0.5 + 6.0;
+            canvas.image(image, img_x, img_y, image.dpi_for_width(img_render_w));
         }
-        y -= CELEB_TITLE_SIZE * 0.4;
+        y -= theme.celebrations.title_size * 0.4;
 
         // Decorative line centered under title
         let line_y = y;
         let line_width = title_width * 0.8;
-        let line_start = x_offset + CELEB_MARGIN_TOP + (usable_width - line_width) / 2.0;
+        let line_start = x_offset + theme.celebrations.margin_x + (usable_width - line_width) / 2.0;
         let line_end = line_start + line_width;
-        let points = vec![
-            (Point::new(Mm(line_start), Mm(line_y)), false),
-            (Point::new(Mm(line_end), Mm(line_y)), false),
-        ];
-        let line = Line {
-            points,
-            is_closed: false,
-        };
-        layer.set_outline_color(Color::Greyscale(Greyscale::new(0.6, None)));
-        layer.set_outline_thickness(0.3);
-        layer.add_line(line);
+        canvas.set_outline_color(&theme.colors.divider);
+        canvas.set_outline_thickness(0.3);
+        canvas.line(line_start, line_y, line_end, line_y);
         y -= 8.0;
     }
 
     // Render left column
-    let left_x = x_offset + CELEB_MARGIN_TOP;
-    render_celeb_column(layer, &celeb_page.left, left_x, y, font, font_bold);
+    let left_x = x_offset + theme.celebrations.margin_x;
+    render_celeb_column(canvas, &celeb_page.left, left_x, y, theme, fonts, locale);
 
     // Render right column — starts at same y as left (below title on first page)
-    let right_x = x_offset + CELEB_MARGIN_TOP + col_width + CELEB_COL_GAP;
-    render_celeb_column(layer, &celeb_page.right, right_x, y, font, font_bold);
+    let right_x = x_offset + theme.celebrations.margin_x + col_width + theme.celebrations.col_gap;
+    render_celeb_column(canvas, &celeb_page.right, right_x, y, theme, fonts, locale);
 }
 
-fn resolve_image_page(path: &Option<String>, photos_dir: &PathBuf) -> ContentPage {
+fn resolve_image_page(path: &Option<String>, photos_dir: &PathBuf, bleed: f32) -> ContentPage {
     if let Some(ref filename) = path {
         let full_path = photos_dir.join("directory").join(filename);
         let full_res = get_full_resolution_path(&full_path);
@@ -1158,399 +2543,80 @@ fn resolve_image_page(path: &Option<String>, photos_dir: &PathBuf) -> ContentPag
             full_path
         };
         if image_path.exists() {
-            return ContentPage::FullImage(image_path);
+            return ContentPage::FullImage { image_path, bleed };
         }
     }
     ContentPage::Blank
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_content_page(
-    doc: &PdfDocumentReference,
-    layer: &PdfLayerReference,
+    canvas: &dyn PageCanvas,
     page: &ContentPage,
     x_offset: f32,
     is_left_half: bool,
-    font: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    images: &PreparedImages,
+    locale: Locale,
 ) {
     match page {
-        ContentPage::FullImage(path) => {
-            render_full_image(doc, layer, path, x_offset, is_left_half);
+        ContentPage::FullImage { image_path, bleed } => {
+            let image = images.get(image_path, full_image_treatment(theme, *bleed));
+            render_full_image(canvas, image, x_offset, is_left_half, *bleed, theme);
         }
-        ContentPage::CoverImage { image_path, title_line1, title_line2, title_color } => {
-            if image_path.exists() {
-                render_full_image(doc, layer, image_path, x_offset, is_left_half);
-            }
-            render_cover_title(layer, title_line1, title_line2, title_color, x_offset, font_bold);
+        ContentPage::CoverImage { image_path, bleed, title_line1, title_line2, title_color } => {
+            let image = images.get(image_path, full_image_treatment(theme, *bleed));
+            render_full_image(canvas, image, x_offset, is_left_half, *bleed, theme);
+            render_cover_title(canvas, title_line1, title_line2, title_color, x_offset, theme, fonts, locale);
         }
         ContentPage::PhotoGrid { entries, grid_rows } => {
-            render_photo_grid(doc, layer, entries, x_offset, font, font_bold, *grid_rows);
-        }
-        ContentPage::TextCards(cards) => {
-            render_text_cards(layer, cards, x_offset, font, font_bold);
-        }
-        ContentPage::CelebrationPages(celeb_page) => {
-            render_celebrations(doc, layer, celeb_page, x_offset, font, font_bold);
-        }
-        ContentPage::MarkdownContent(text) => {
-            render_markdown(layer, text, x_offset, font, font_bold);
-        }
-        ContentPage::FirstInsidePage { pastor_letter, mission_statement } => {
-            render_first_inside_page(layer, pastor_letter.as_deref(), mission_statement.as_deref(), x_offset, font, font_bold);
-        }
-        ContentPage::StaffPage(entries) => {
-            render_staff_page(doc, layer, entries, x_offset, font, font_bold);
-        }
-        ContentPage::LeadershipPage { entries, contact_left, contact_right } => {
-            render_leadership_page(layer, entries, contact_left, contact_right, x_offset, font, font_bold);
-        }
-        ContentPage::Blank => {}
-    }
-}
-
-// Markdown rendering constants
-const MD_MARGIN_X: f32 = 15.0;
-const MD_MARGIN_TOP: f32 = 20.0;
-const MD_H1_SIZE: f32 = 20.0;
-const MD_H2_SIZE: f32 = 16.0;
-const MD_H3_SIZE: f32 = 13.0;
-const MD_BODY_SIZE: f32 = 11.0;
-const MD_H1_LINE_HEIGHT: f32 = 9.0;
-const MD_H2_LINE_HEIGHT: f32 = 7.5;
-const MD_H3_LINE_HEIGHT: f32 = 6.5;
-const MD_BODY_LINE_HEIGHT: f32 = 5.5;
-const MD_PARAGRAPH_SPACING: f32 = 4.0;
-const MD_HEADING_SPACING_BEFORE: f32 = 6.0;
-const MD_LIST_INDENT: f32 = 5.0;
-
-fn render_markdown(
-    layer: &PdfLayerReference,
-    markdown: &str,
-    x_offset: f32,
-    font: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
-) {
-    let left = x_offset + MD_MARGIN_X;
-    let right_bound = x_offset + PAGE_WIDTH_MM - MD_MARGIN_X;
-    let max_width = right_bound - left;
-    let mut y = PAGE_HEIGHT_MM - MD_MARGIN_TOP;
-
-    let lines: Vec<&str> = markdown.lines().collect();
-    let mut i = 0;
-    let mut prev_was_blank = false;
-    let mut centered = false; // Content after --- is centered
-    let mut has_rendered_content = false; // Track if we've rendered any content yet
-
-    while i < lines.len() {
-        let line = lines[i].trim_end();
-
-        // Blank line = paragraph break
-        if line.is_empty() {
-            if !prev_was_blank {
-                y -= MD_PARAGRAPH_SPACING;
-            }
-            prev_was_blank = true;
-            i += 1;
-            continue;
-        }
-        prev_was_blank = false;
-
-        // Horizontal rule: --- or more dashes
-        if line.trim().len() >= 3 && line.trim().chars().all(|c| c == '-') {
-            y -= MD_PARAGRAPH_SPACING;
-            let rule_inset = max_width * 0.15;
-            let line_start = left + rule_inset;
-            let line_end = left + max_width - rule_inset;
-            let points = vec![
-                (Point::new(Mm(line_start), Mm(y)), false),
-                (Point::new(Mm(line_end), Mm(y)), false),
-            ];
-            let hr = Line {
-                points,
-                is_closed: false,
-            };
-            layer.set_outline_color(Color::Greyscale(Greyscale::new(0.4, None)));
-            layer.set_outline_thickness(0.75);
-            layer.add_line(hr);
-            y -= MD_PARAGRAPH_SPACING + 2.0;
-            centered = true;
-            i += 1;
-            continue;
-        }
-
-        if let Some(heading) = line.strip_prefix("### ") {
-            y -= MD_HEADING_SPACING_BEFORE;
-            let wrapped = wrap_text(heading.trim(), MD_H3_SIZE, max_width);
-            for wl in &wrapped {
-                let text_x = if centered {
-                    let tw = wl.len() as f32 * MD_H3_SIZE * 0.19;
-                    left + (max_width - tw) / 2.0
-                } else { left };
-                layer.use_text(wl, MD_H3_SIZE, Mm(text_x), Mm(y), font_bold);
-                y -= MD_H3_LINE_HEIGHT;
-            }
-        } else if let Some(heading) = line.strip_prefix("## ") {
-            y -= MD_HEADING_SPACING_BEFORE;
-            let wrapped = wrap_text(heading.trim(), MD_H2_SIZE, max_width);
-            for wl in &wrapped {
-                let text_x = if centered {
-                    let tw = wl.len() as f32 * MD_H2_SIZE * 0.19;
-                    left + (max_width - tw) / 2.0
-                } else { left };
-                layer.use_text(wl, MD_H2_SIZE, Mm(text_x), Mm(y), font_bold);
-                y -= MD_H2_LINE_HEIGHT;
-            }
-        } else if let Some(heading) = line.strip_prefix("# ") {
-            // Draw a decorative line before any H1 that isn't the first content
-            if has_rendered_content {
-                y -= MD_PARAGRAPH_SPACING;
-                let rule_inset = max_width * 0.15;
-                let line_start = left + rule_inset;
-                let line_end = left + max_width - rule_inset;
-                let points = vec![
-                    (Point::new(Mm(line_start), Mm(y)), false),
-                    (Point::new(Mm(line_end), Mm(y)), false),
-                ];
-                let hr = Line {
-                    points,
-                    is_closed: false,
-                };
-                layer.set_outline_color(Color::Greyscale(Greyscale::new(0.4, None)));
-                layer.set_outline_thickness(0.75);
-                layer.add_line(hr);
-                y -= MD_PARAGRAPH_SPACING;
-                centered = true;
-            }
-            y -= MD_HEADING_SPACING_BEFORE;
-            let wrapped = wrap_text(heading.trim(), MD_H1_SIZE, max_width);
-            for wl in &wrapped {
-                let text_x = if centered {
-                    let tw = wl.len() as f32 * MD_H1_SIZE * 0.19;
-                    left + (max_width - tw) / 2.0
-                } else { left };
-                layer.use_text(wl, MD_H1_SIZE, Mm(text_x), Mm(y), font_bold);
-                y -= MD_H1_LINE_HEIGHT;
-            }
-        } else if line.starts_with("- ") || line.starts_with("* ") {
-            let content = &line[2..];
-            let bullet_x = left + MD_LIST_INDENT;
-            let text_x = bullet_x + 4.0;
-            let text_max_width = max_width - MD_LIST_INDENT - 4.0;
-
-            // Render inline markdown (bold/italic) for list items
-            let segments = parse_inline_markdown(content);
-            let wrapped = wrap_inline_segments(&segments, MD_BODY_SIZE, text_max_width);
-
-            for (li, segs) in wrapped.iter().enumerate() {
-                if li == 0 {
-                    layer.use_text("\u{2022}", MD_BODY_SIZE, Mm(bullet_x), Mm(y), font);
-                }
-                render_inline_segments(layer, segs, MD_BODY_SIZE, text_x, y, font, font_bold);
-                y -= MD_BODY_LINE_HEIGHT;
-            }
-        } else {
-            // Regular paragraph text with inline markdown
-            let segments = parse_inline_markdown(line);
-            let wrapped = wrap_inline_segments(&segments, MD_BODY_SIZE, max_width);
-
-            for segs in &wrapped {
-                if centered {
-                    let total_width = estimate_inline_width(segs, MD_BODY_SIZE);
-                    let text_x = left + (max_width - total_width) / 2.0;
-                    render_inline_segments(layer, segs, MD_BODY_SIZE, text_x, y, font, font_bold);
-                } else {
-                    render_inline_segments(layer, segs, MD_BODY_SIZE, left, y, font, font_bold);
-                }
-                y -= MD_BODY_LINE_HEIGHT;
-            }
-        }
-
-        has_rendered_content = true;
-        i += 1;
-    }
-}
-
-/// Render the first inside page with pastor letter and mission statement
-fn render_first_inside_page(
-    layer: &PdfLayerReference,
-    pastor_letter: Option<&str>,
-    mission_statement: Option<&str>,
-    x_offset: f32,
-    font: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
-) {
-    let left = x_offset + MD_MARGIN_X;
-    let max_width = PAGE_WIDTH_MM - 2.0 * MD_MARGIN_X;
-    let mut y = PAGE_HEIGHT_MM - MD_MARGIN_TOP;
-
-    // "Letter From the Pastor" heading
-    if let Some(letter) = pastor_letter {
-        if !letter.is_empty() {
-            layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-            layer.use_text("Letter From the Pastor", MD_H1_SIZE, Mm(left), Mm(y), font_bold);
-            y -= MD_H1_LINE_HEIGHT + MD_PARAGRAPH_SPACING;
-
-            // Render letter body as wrapped paragraphs
-            for paragraph in letter.split("\n\n") {
-                let trimmed = paragraph.trim();
-                if trimmed.is_empty() {
-                    continue;
-                }
-                // Join single newlines within a paragraph into one block
-                let block = trimmed.lines()
-                    .map(|l| l.trim())
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                let wrapped = wrap_text(&block, MD_BODY_SIZE, max_width);
-                for wl in &wrapped {
-                    layer.use_text(wl, MD_BODY_SIZE, Mm(left), Mm(y), font);
-                    y -= MD_BODY_LINE_HEIGHT;
-                }
-                y -= MD_PARAGRAPH_SPACING;
-            }
-        }
-    }
-
-    // Decorative line — centered in the gap, full width minus margins
-    if pastor_letter.map_or(false, |s| !s.is_empty()) && mission_statement.map_or(false, |s| !s.is_empty()) {
-        y += MD_PARAGRAPH_SPACING; // reclaim trailing paragraph spacing
-        let gap_above = 6.0;
-        let gap_below = 12.0;
-        y -= gap_above;
-        let line_start = left;
-        let line_end = left + max_width;
-        let points = vec![
-            (Point::new(Mm(line_start), Mm(y)), false),
-            (Point::new(Mm(line_end), Mm(y)), false),
-        ];
-        let hr = Line {
-            points,
-            is_closed: false,
-        };
-        layer.set_outline_color(Color::Greyscale(Greyscale::new(0.4, None)));
-        layer.set_outline_thickness(0.75);
-        layer.add_line(hr);
-        y -= gap_below;
-    }
-
-    // "Mission Statement" heading (centered) + body text (each line centered)
-    if let Some(mission) = mission_statement {
-        if !mission.is_empty() {
-            layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-
-            // Centered heading
-            let heading = "Mission Statement";
-            let heading_width = measure_helvetica_width(heading, MD_H1_SIZE);
-            let heading_x = left + (max_width - heading_width) / 2.0;
-            layer.use_text(heading, MD_H1_SIZE, Mm(heading_x), Mm(y), font_bold);
-            y -= MD_H1_LINE_HEIGHT + MD_PARAGRAPH_SPACING;
-
-            // Each line individually centered using accurate font metrics
-            let lines: Vec<&str> = mission.lines()
-                .map(|l| l.trim())
-                .filter(|l| !l.is_empty())
-                .collect();
-
-            for line in &lines {
-                let line_width = measure_helvetica_width(line, MD_BODY_SIZE);
-                let line_x = left + (max_width - line_width) / 2.0;
-                layer.use_text(*line, MD_BODY_SIZE, Mm(line_x), Mm(y), font);
-                y -= MD_BODY_LINE_HEIGHT;
-            }
-        }
-    }
-}
-
-/// Estimate the rendered width of inline segments
-fn estimate_inline_width(segments: &[InlineSegment], font_size: f32) -> f32 {
-    let char_width = font_size * 0.19;
-    segments.iter().map(|seg| {
-        let text = match seg {
-            InlineSegment::Plain(t) | InlineSegment::Bold(t) | InlineSegment::Italic(t) => t,
-        };
-        text.len() as f32 * char_width
-    }).sum()
-}
-
-/// Measure text width using standard Helvetica character widths.
-/// Widths are in 1/1000 of a unit; multiply by font_size and convert to mm.
-fn measure_helvetica_width(text: &str, font_size: f32) -> f32 {
-    let width_units: f32 = text.chars().map(|c| helvetica_char_width(c) as f32).sum();
-    // 1 PDF point = 1/72 inch = 25.4/72 mm ≈ 0.3528 mm
-    // font_size is in points, char widths are in 1/1000 of font_size
-    width_units / 1000.0 * font_size * 25.4 / 72.0
-}
-
-/// Standard Helvetica character widths (in 1/1000 units of font size)
-fn helvetica_char_width(c: char) -> u32 {
-    match c {
-        ' ' => 278, '!' => 278, '"' => 355, '#' => 556, '$' => 556,
-        '%' => 889, '&' => 667, '\'' => 191, '(' => 333, ')' => 333,
-        '*' => 389, '+' => 584, ',' => 278, '-' => 333, '.' => 278,
-        '/' => 278, '0' => 556, '1' => 556, '2' => 556, '3' => 556,
-        '4' => 556, '5' => 556, '6' => 556, '7' => 556, '8' => 556,
-        '9' => 556, ':' => 278, ';' => 278, '<' => 584, '=' => 584,
-        '>' => 584, '?' => 556, '@' => 1015, 'A' => 667, 'B' => 667,
-        'C' => 722, 'D' => 722, 'E' => 611, 'F' => 556, 'G' => 778,
-        'H' => 722, 'I' => 278, 'J' => 500, 'K' => 667, 'L' => 556,
-        'M' => 833, 'N' => 722, 'O' => 778, 'P' => 667, 'Q' => 778,
-        'R' => 722, 'S' => 667, 'T' => 611, 'U' => 722, 'V' => 667,
-        'W' => 944, 'X' => 667, 'Y' => 667, 'Z' => 611, '[' => 278,
-        '\\' => 278, ']' => 278, '^' => 469, '_' => 556, '`' => 333,
-        'a' => 556, 'b' => 556, 'c' => 500, 'd' => 556, 'e' => 556,
-        'f' => 278, 'g' => 556, 'h' => 556, 'i' => 222, 'j' => 222,
-        'k' => 500, 'l' => 222, 'm' => 833, 'n' => 556, 'o' => 556,
-        'p' => 556, 'q' => 556, 'r' => 333, 's' => 500, 't' => 278,
-        'u' => 556, 'v' => 500, 'w' => 722, 'x' => 500, 'y' => 500,
-        'z' => 500, '{' => 334, '|' => 260, '}' => 334, '~' => 584,
-        _ => 556, // default for unknown chars
-    }
-}
-
-#[derive(Clone, Debug)]
-enum InlineSegment {
-    Plain(String),
-    Bold(String),
-    Italic(String),
-}
-
-fn parse_inline_markdown(text: &str) -> Vec<InlineSegment> {
-    let mut segments = Vec::new();
-    let mut remaining = text;
-
-    while !remaining.is_empty() {
-        // Look for **bold**
-        if let Some(start) = remaining.find("**") {
-            if start > 0 {
-                segments.push(InlineSegment::Plain(remaining[..start].to_string()));
-            }
-            let after_start = &remaining[start + 2..];
-            if let Some(end) = after_start.find("**") {
-                segments.push(InlineSegment::Bold(after_start[..end].to_string()));
-                remaining = &after_start[end + 2..];
-            } else {
-                segments.push(InlineSegment::Plain(remaining[start..].to_string()));
-                break;
-            }
-        } else if let Some(start) = remaining.find('*') {
-            if start > 0 {
-                segments.push(InlineSegment::Plain(remaining[..start].to_string()));
-            }
-            let after_start = &remaining[start + 1..];
-            if let Some(end) = after_start.find('*') {
-                segments.push(InlineSegment::Italic(after_start[..end].to_string()));
-                remaining = &after_start[end + 1..];
-            } else {
-                segments.push(InlineSegment::Plain(remaining[start..].to_string()));
-                break;
-            }
-        } else {
-            segments.push(InlineSegment::Plain(remaining.to_string()));
-            break;
+            render_photo_grid(canvas, entries, x_offset, theme, fonts, *grid_rows, images);
         }
+        ContentPage::TextCards(cards) => {
+            render_text_cards(canvas, cards, x_offset, theme, fonts, images, locale);
+        }
+        ContentPage::CelebrationPages(celeb_page) => {
+            render_celebrations(canvas, celeb_page, x_offset, theme, fonts, images, locale);
+        }
+        ContentPage::Markdown(items) => {
+            render_markdown(canvas, items, x_offset, theme, fonts, images);
+        }
+        ContentPage::StaffPage(blocks) => {
+            render_staff_page(canvas, blocks, x_offset, theme, fonts, images);
+        }
+        ContentPage::StaffBios(bio_page) => {
+            render_staff_bio_page(canvas, bio_page, x_offset, theme, fonts, images, locale);
+        }
+        ContentPage::TableOfContents(entries) => {
+            render_table_of_contents(canvas, entries, x_offset, theme, fonts, locale);
+        }
+        ContentPage::LeadershipPage(leadership_page) => {
+            render_leadership_page(canvas, leadership_page, x_offset, theme, fonts, locale);
+        }
+        ContentPage::TemplateCover { layout, church, title_line1, title_line2 } => {
+            render_template_cover(
+                canvas, *layout, church, title_line1, title_line2, x_offset, theme, fonts, images, locale,
+            );
+        }
+        ContentPage::TemplateBackCover { layout, church } => {
+            render_template_back_cover(canvas, *layout, church, x_offset, theme, fonts, images);
+        }
+        ContentPage::Blank => {}
     }
+}
 
-    segments
+fn render_markdown(
+    canvas: &dyn PageCanvas,
+    items: &[MarkdownItem],
+    x_offset: f32,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    images: &PreparedImages,
+) {
+    let left = x_offset + theme.markdown.margin_x;
+    let top = PAGE_HEIGHT_MM - theme.markdown.margin_top;
+    draw_items(canvas, items, left, top, theme, fonts, images);
 }
 
 fn to_title_case(s: &str) -> String {
@@ -1569,31 +2635,7 @@ fn to_title_case(s: &str) -> String {
         .join(" ")
 }
 
-fn estimate_text_width(text: &str, font_size: f32) -> f32 {
-    // Per-character width estimates for Helvetica, as fraction of font size in mm.
-    // font_size is in pt; 1pt = 0.3528mm. Helvetica average char ≈ 0.5em.
-    // So 1 char ≈ 0.5 * font_size * 0.3528 mm ≈ font_size * 0.176 mm.
-    // Use per-character widths for better accuracy.
-    let width: f32 = text
-        .chars()
-        .map(|c| match c {
-            ' ' | 'i' | 'l' | '!' | '|' | '.' | ',' | ':' | ';' | '\'' => 0.10,
-            'f' | 'j' | 'r' | 't' | '(' | ')' | '[' | ']' => 0.13,
-            'a' | 'b' | 'c' | 'd' | 'e' | 'g' | 'h' | 'k' | 'n' | 'o' | 'p' | 'q'
-            | 's' | 'u' | 'v' | 'x' | 'y' | 'z' | '-' => 0.18,
-            'w' | 'm' => 0.25,
-            'A' | 'B' | 'C' | 'D' | 'E' | 'F' | 'G' | 'H' | 'K' | 'N' | 'O' | 'P'
-            | 'Q' | 'R' | 'S' | 'T' | 'U' | 'V' | 'X' | 'Y' | 'Z' => 0.22,
-            'M' | 'W' => 0.27,
-            'I' | 'J' | 'L' => 0.15,
-            '0'..='9' => 0.18,
-            _ => 0.18,
-        })
-        .sum();
-    width * font_size
-}
-
-fn wrap_text(text: &str, font_size: f32, max_width: f32) -> Vec<String> {
+fn wrap_text(text: &str, font: &LoadedFont, font_size: f32, max_width: f32) -> Vec<String> {
     let words: Vec<&str> = text.split_whitespace().collect();
     let mut lines = Vec::new();
     let mut current_line = String::new();
@@ -1605,7 +2647,7 @@ fn wrap_text(text: &str, font_size: f32, max_width: f32) -> Vec<String> {
             format!("{} {}", current_line, word)
         };
 
-        if estimate_text_width(&test, font_size) > max_width && !current_line.is_empty() {
+        if font.width(&test, font_size) > max_width && !current_line.is_empty() {
             lines.push(current_line);
             current_line = word.to_string();
         } else {
@@ -1624,582 +2666,383 @@ fn wrap_text(text: &str, font_size: f32, max_width: f32) -> Vec<String> {
     lines
 }
 
-/// Represents a style type for merging adjacent segments.
-#[derive(Clone, Copy, PartialEq)]
-enum InlineStyle {
-    Plain,
-    Bold,
-    Italic,
-}
-
-fn segment_style(seg: &InlineSegment) -> InlineStyle {
-    match seg {
-        InlineSegment::Plain(_) => InlineStyle::Plain,
-        InlineSegment::Bold(_) => InlineStyle::Bold,
-        InlineSegment::Italic(_) => InlineStyle::Italic,
-    }
+fn render_staff_page(
+    canvas: &dyn PageCanvas,
+    blocks: &[StaffBlock],
+    x_offset: f32,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    images: &PreparedImages,
+) {
+    let left = x_offset + theme.staff.margin_x;
+    let top = PAGE_HEIGHT_MM - theme.staff.margin_top;
+    let usable_width = PAGE_WIDTH_MM - 2.0 * theme.staff.margin_x;
+    draw_staff_blocks(canvas, blocks, left, top, usable_width, theme, fonts, images);
 }
 
-fn wrap_inline_segments(
-    segments: &[InlineSegment],
-    font_size: f32,
-    max_width: f32,
-) -> Vec<Vec<InlineSegment>> {
-    // Flatten segments into words with style info, then re-wrap
-    let mut styled_words: Vec<(String, InlineStyle)> = Vec::new();
-
-    for seg in segments {
-        let (text, style) = match seg {
-            InlineSegment::Plain(t) => (t.as_str(), InlineStyle::Plain),
-            InlineSegment::Bold(t) => (t.as_str(), InlineStyle::Bold),
-            InlineSegment::Italic(t) => (t.as_str(), InlineStyle::Italic),
-        };
-        for word in text.split_whitespace() {
-            styled_words.push((word.to_string(), style));
-        }
-    }
-
-    let mut lines: Vec<Vec<(String, InlineStyle)>> = Vec::new();
-    let mut current_words: Vec<(String, InlineStyle)> = Vec::new();
-    let mut current_width: f32 = 0.0;
-    let space_width = estimate_text_width(" ", font_size);
-
-    for (word, style) in &styled_words {
-        let word_width = estimate_text_width(word, font_size);
-        let needed = if current_words.is_empty() {
-            word_width
-        } else {
-            space_width + word_width
-        };
-
-        if current_width + needed > max_width && !current_words.is_empty() {
-            lines.push(current_words);
-            current_words = Vec::new();
-            current_width = 0.0;
-        }
-
-        current_words.push((word.clone(), *style));
-        current_width += needed;
-    }
-
-    if !current_words.is_empty() {
-        lines.push(current_words);
-    }
-
-    if lines.is_empty() {
-        lines.push(Vec::new());
-    }
-
-    // Merge adjacent words of the same style into single segments per line
-    lines
-        .into_iter()
-        .map(|words| {
-            let mut merged: Vec<InlineSegment> = Vec::new();
-            for (word, style) in words {
-                let can_merge = merged.last().map_or(false, |last| segment_style(last) == style);
-                if can_merge {
-                    // Append word (with space) to the last segment
-                    match merged.last_mut().unwrap() {
-                        InlineSegment::Plain(ref mut t)
-                        | InlineSegment::Bold(ref mut t)
-                        | InlineSegment::Italic(ref mut t) => {
-                            t.push(' ');
-                            t.push_str(&word);
-                        }
-                    }
-                } else {
-                    // Start a new segment; add leading space if not first
-                    let text = if merged.is_empty() {
-                        word
+/// Draw staff blocks down a column `width` wide, starting from `top`
+#[allow(clippy::too_many_arguments)]
+fn draw_staff_blocks(
+    canvas: &dyn PageCanvas,
+    blocks: &[StaffBlock],
+    left: f32,
+    top: f32,
+    width: f32,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    images: &PreparedImages,
+) {
+    let mut y = top;
+    for block in blocks {
+        match block {
+            StaffBlock::Heading { title, .. } => {
+                canvas.set_fill_color(&theme.colors.heading);
+                canvas.text(title, theme.staff.section_title_size, left, y, &fonts.bold);
+            }
+            StaffBlock::Photos { entries, width: photo_width, height: photo_height, .. } => {
+                let photo_y = y - photo_height;
+                let count = entries.len();
+
+                for (i, entry) in entries.iter().enumerate() {
+                    // First photo left-justified, last right-justified, the rest spread between
+                    let photo_x = if count == 1 {
+                        left
                     } else {
-                        format!(" {}", word)
+                        left + (width - photo_width) * i as f32 / (count - 1) as f32
                     };
-                    merged.push(match style {
-                        InlineStyle::Plain => InlineSegment::Plain(text),
-                        InlineStyle::Bold => InlineSegment::Bold(text),
-                        InlineStyle::Italic => InlineSegment::Italic(text),
-                    });
-                }
-            }
-            merged
-        })
-        .collect()
-}
 
-fn render_inline_segments(
-    layer: &PdfLayerReference,
-    segments: &[InlineSegment],
-    font_size: f32,
-    start_x: f32,
-    y: f32,
-    font: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
-) {
-    let mut x = start_x;
-
-    for seg in segments {
-        let (text, used_font) = match seg {
-            InlineSegment::Plain(t) => (t.as_str(), font),
-            InlineSegment::Bold(t) => (t.as_str(), font_bold),
-            // Italic falls back to regular font since we only have regular and bold
-            InlineSegment::Italic(t) => (t.as_str(), font),
-        };
+                    if let Some(image) = staff_photo(entry, images, theme) {
+                        render_staff_photo(canvas, image, photo_x, photo_y, *photo_width, *photo_height, &theme.photos);
+                    }
 
-        if !text.is_empty() {
-            layer.use_text(text, font_size, Mm(x), Mm(y), used_font);
-            x += estimate_text_width(text, font_size);
+                    // Name centered below photo in title case
+                    let caption = to_title_case(&entry.name);
+                    let caption_width = fonts.regular.width(&caption, theme.staff.name_size);
+                    let caption_x = photo_x + (photo_width - caption_width) / 2.0;
+                    canvas.set_fill_color(&theme.colors.text);
+                    canvas.text(&caption, theme.staff.name_size, caption_x, photo_y - 5.0, &fonts.regular);
+                }
+            }
+            StaffBlock::Listing(entry) => {
+                canvas.set_fill_color(&theme.colors.text);
+                let mut name_x = left;
+                if !entry.title.is_empty() {
+                    let title_upper = entry.title.to_uppercase();
+                    canvas.text(&title_upper, theme.staff.title_size, left, y, &fonts.bold);
+                    name_x += fonts.bold.width(&title_upper, theme.staff.title_size) + 6.0;
+                }
+                canvas.text(&entry.name, theme.staff.title_size, name_x, y, &fonts.regular);
+            }
+            StaffBlock::Beside { left: featured, right: list } => {
+                let column = width * STAFF_FEATURED_COLUMN;
+                draw_staff_blocks(canvas, featured, left, y, column, theme, fonts, images);
+                draw_staff_blocks(canvas, list, left + column, y, width - column, theme, fonts, images);
+            }
         }
+        y -= block.height();
     }
 }
 
-// Staff page layout constants
-const STAFF_MARGIN_X: f32 = 12.0;
-const STAFF_MARGIN_TOP: f32 = 20.0;
-const STAFF_SECTION_TITLE_SIZE: f32 = 18.0;
-const STAFF_NAME_SIZE: f32 = 11.0;
-const STAFF_TITLE_SIZE: f32 = 10.0;
-const STAFF_PASTOR_PHOTO_W: f32 = 40.0;
-const STAFF_PASTOR_PHOTO_H: f32 = 60.0; // 4:6 aspect
-const STAFF_ELDER_PHOTO_W: f32 = 30.0;
-const STAFF_ELDER_PHOTO_H: f32 = 45.0; // 4:6 aspect
-
-fn render_staff_page(
-    _doc: &PdfDocumentReference,
-    layer: &PdfLayerReference,
-    entries: &[StaffEntry],
+fn render_staff_bio_page(
+    canvas: &dyn PageCanvas,
+    bio_page: &StaffBioPage,
     x_offset: f32,
-    font: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    images: &PreparedImages,
+    locale: Locale,
 ) {
-    let left = x_offset + STAFF_MARGIN_X;
-    let mut y = PAGE_HEIGHT_MM - STAFF_MARGIN_TOP;
-
-    let pastor: Vec<&StaffEntry> = entries.iter().filter(|e| e.role == "pastor").collect();
-    let elders: Vec<&StaffEntry> = entries.iter().filter(|e| e.role == "elder").collect();
-    let staff: Vec<&StaffEntry> = entries.iter().filter(|e| e.role == "staff").collect();
-
-    let usable_width = PAGE_WIDTH_MM - 2.0 * STAFF_MARGIN_X;
-
-    // Pastor takes left 1/3, Staff takes right 2/3 — rendered side by side
-    let staff_col_left = left + usable_width * 0.4;
-    let section_top_y = y;
-
-    // === PASTOR SECTION (left 1/3) ===
-    if !pastor.is_empty() {
-        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-        layer.use_text("Pastor", STAFF_SECTION_TITLE_SIZE, Mm(left), Mm(y), font_bold);
-        y -= 8.0;
-
-        for p in &pastor {
-            let photo_x = left;
-            let photo_y = y - STAFF_ELDER_PHOTO_H;
-
-            if let Some(ref path) = p.photo_path {
-                render_staff_photo(layer, path, photo_x, photo_y, STAFF_ELDER_PHOTO_W, STAFF_ELDER_PHOTO_H);
-            }
+    let left = x_offset + theme.markdown.margin_x;
+    let mut y = PAGE_HEIGHT_MM - theme.markdown.margin_top;
+
+    let title = locale.text(Text::MeetOurStaff);
+    let heading = if bio_page.continued { locale.format(Text::Continued, title) } else { title.to_string() };
+    canvas.set_fill_color(&theme.colors.heading);
+    canvas.text(&heading, theme.markdown.h1_size, left, y, &fonts.bold);
+    y -= staff_bio_heading_height(theme);
+
+    let text_left = left + theme.staff.photo_width + STAFF_BIO_PHOTO_GAP;
+    let text_width = staff_bio_text_width(theme);
+    for entry in &bio_page.entries {
+        let photo_y = y - theme.staff.photo_height;
+        if let Some(image) = staff_photo(entry, images, theme) {
+            render_staff_photo(canvas, image, left, photo_y, theme.staff.photo_width, theme.staff.photo_height, &theme.photos);
+        }
 
-            // Name centered below photo in title case
-            let caption = to_title_case(&p.name);
-            let caption_width = measure_helvetica_width(&caption, STAFF_NAME_SIZE);
-            let caption_x = photo_x + (STAFF_ELDER_PHOTO_W - caption_width) / 2.0;
-            layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-            layer.use_text(&caption, STAFF_NAME_SIZE, Mm(caption_x), Mm(photo_y - 5.0), font);
+        let text_bottom = draw_staff_bio_text(canvas, entry, text_left, y, text_width, theme, fonts);
+        y = text_bottom.min(photo_y) - STAFF_BIO_GAP;
+    }
+}
 
-            y = photo_y - 18.0;
+/// Name, title, contact lines and bio of a staff member, down a column from
+/// `top`. Returns the y below the last line.
+fn draw_staff_bio_text(
+    canvas: &dyn PageCanvas,
+    entry: &StaffEntry,
+    left: f32,
+    top: f32,
+    width: f32,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+) -> f32 {
+    let md = &theme.markdown;
+    // First baseline sits a cap height below the top of the photo
+    let mut y = top - md.h3_size * 0.7 * 25.4 / 72.0;
+
+    canvas.set_fill_color(&theme.colors.heading);
+    for line in wrap_text(&entry.name, &fonts.bold, md.h3_size, width) {
+        canvas.text(&line, md.h3_size, left, y, &fonts.bold);
+        y -= md.h3_line_height;
+    }
+
+    canvas.set_fill_color(&theme.colors.text);
+    if !entry.title.is_empty() {
+        for line in wrap_text(&entry.title, &fonts.italic, md.body_size, width) {
+            canvas.text(&line, md.body_size, left, y, &fonts.italic);
+            y -= md.body_line_height;
         }
     }
-
-    let pastor_bottom_y = y;
-
-    // === STAFF SECTION (right 2/3, same vertical start as Pastor) ===
-    if !staff.is_empty() {
-        let mut staff_y = section_top_y;
-        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-        layer.use_text("Staff", STAFF_SECTION_TITLE_SIZE, Mm(staff_col_left), Mm(staff_y), font_bold);
-        staff_y -= 10.0;
-
-        for s in &staff {
-            layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-            let title_upper = s.title.to_uppercase();
-            layer.use_text(&title_upper, STAFF_TITLE_SIZE, Mm(staff_col_left), Mm(staff_y), font_bold);
-            let title_w = estimate_text_width(&title_upper, STAFF_TITLE_SIZE);
-            layer.use_text(&s.name, STAFF_TITLE_SIZE, Mm(staff_col_left + title_w + 6.0), Mm(staff_y), font);
-            staff_y -= 7.0;
+    for contact in &entry.contact {
+        for line in wrap_text(contact, &fonts.regular, theme.staff.title_size, width) {
+            canvas.text(&line, theme.staff.title_size, left, y, &fonts.regular);
+            y -= md.body_line_height;
         }
     }
 
-    // Continue y from whichever section went lower
-    y = pastor_bottom_y;
-
-    // === ELDERS SECTION ===
-    if !elders.is_empty() {
-        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-        layer.use_text("Elders", STAFF_SECTION_TITLE_SIZE, Mm(left), Mm(y), font_bold);
-        y -= 8.0;
-
-        // Layout elders in a row, up to 3 per row
-        let max_per_row = 3;
-        let col_width = usable_width / max_per_row as f32;
-
-        for row_elders in elders.chunks(max_per_row) {
-            let photo_y = y - STAFF_ELDER_PHOTO_H;
-            let row_count = row_elders.len();
-
-            for (i, elder) in row_elders.iter().enumerate() {
-                // First photo left-justified, last right-justified, middle centered
-                let photo_x = if row_count == 1 {
-                    left
-                } else if i == 0 {
-                    left
-                } else if i == row_count - 1 {
-                    left + usable_width - STAFF_ELDER_PHOTO_W
-                } else {
-                    left + (usable_width - STAFF_ELDER_PHOTO_W) / 2.0
-                };
-
-                if let Some(ref path) = elder.photo_path {
-                    render_staff_photo(layer, path, photo_x, photo_y, STAFF_ELDER_PHOTO_W, STAFF_ELDER_PHOTO_H);
-                }
-
-                // Name centered below photo in title case
-                let name_tc = to_title_case(&elder.name);
-                let name_width = measure_helvetica_width(&name_tc, STAFF_NAME_SIZE);
-                let name_x = photo_x + (STAFF_ELDER_PHOTO_W - name_width) / 2.0;
-                layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-                layer.use_text(&name_tc, STAFF_NAME_SIZE, Mm(name_x), Mm(photo_y - 5.0), font);
-            }
+    if let Some(ref bio) = entry.bio {
+        y -= md.paragraph_spacing;
+        let mut flow = MarkdownFlow::new(width, md, fonts);
+        flow.push_markdown(bio, None);
+        let height = flow.height();
+        draw_items(canvas, &flow.into_items(), left, y, theme, fonts, &PreparedImages::default());
+        y -= height;
+    }
+    y
+}
 
-            y = photo_y - 14.0;
+fn render_table_of_contents(
+    canvas: &dyn PageCanvas,
+    entries: &[TocEntry],
+    x_offset: f32,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    locale: Locale,
+) {
+    let left = x_offset + theme.toc.margin_x;
+    let usable_width = PAGE_WIDTH_MM - 2.0 * theme.toc.margin_x;
+    let right_edge = left + usable_width;
+    let mut y = PAGE_HEIGHT_MM - theme.toc.margin_top;
+
+    // Centered title with a rule beneath
+    canvas.set_fill_color(&theme.colors.heading);
+    let title = locale.text(Text::Contents);
+    let title_width = fonts.bold.width(title, theme.toc.title_size);
+    canvas.text(title, theme.toc.title_size, left + (usable_width - title_width) / 2.0, y, &fonts.bold);
+    y -= 6.0;
+
+    let rule_inset = usable_width * 0.25;
+    canvas.set_outline_color(&theme.colors.divider);
+    canvas.set_outline_thickness(0.3);
+    canvas.line(left + rule_inset, y, right_edge - rule_inset, y);
+    y -= 16.0;
+
+    // Section title on the left, page number right-aligned, dotted leader between
+    canvas.set_fill_color(&theme.colors.text);
+    let dot_width = fonts.regular.width(".", theme.toc.entry_size);
+    for entry in entries {
+        let page_text = entry.page.to_string();
+        let title_width = fonts.bold.width(&entry.title, theme.toc.entry_size);
+        let page_width = fonts.regular.width(&page_text, theme.toc.entry_size);
+        let page_x = right_edge - page_width;
+
+        canvas.text(&entry.title, theme.toc.entry_size, left, y, &fonts.bold);
+        canvas.text(&page_text, theme.toc.entry_size, page_x, y, &fonts.regular);
+
+        let leader_start = left + title_width + 2.0;
+        let leader_end = page_x - 2.0;
+        if leader_end > leader_start {
+            let dots = ((leader_end - leader_start) / (dot_width * 2.0)) as usize;
+            let leader = ". ".repeat(dots);
+            let leader_width = fonts.regular.width(leader.trim_end(), theme.toc.entry_size);
+            canvas.set_fill_color(&theme.colors.muted);
+            canvas.text(leader.trim_end(), theme.toc.entry_size, leader_end - leader_width, y, &fonts.regular);
+            canvas.set_fill_color(&theme.colors.text);
         }
+
+        y -= theme.toc.row_spacing;
     }
 }
 
-// Leadership page layout constants
-const LEAD_MARGIN_X: f32 = 12.0;
-const LEAD_MARGIN_TOP: f32 = 22.0;
-const LEAD_TITLE_SIZE: f32 = 18.0;
-const LEAD_MINISTRY_SIZE: f32 = 14.0;
-const LEAD_NAMES_SIZE: f32 = 11.0;
-const LEAD_ROW_SPACING: f32 = 10.0;
-const LEAD_NAMES_INDENT: f32 = 75.0;
-
 fn render_leadership_page(
-    layer: &PdfLayerReference,
-    entries: &[LeadershipEntry],
-    contact_left: &[String],
-    contact_right: &[String],
+    canvas: &dyn PageCanvas,
+    page: &LeadershipPage,
     x_offset: f32,
-    font: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    locale: Locale,
 ) {
-    let left = x_offset + LEAD_MARGIN_X;
-    let usable_width = PAGE_WIDTH_MM - 2.0 * LEAD_MARGIN_X;
-    let mut y = PAGE_HEIGHT_MM - LEAD_MARGIN_TOP;
+    let left = x_offset + theme.leadership.margin_x;
+    let usable_width = PAGE_WIDTH_MM - 2.0 * theme.leadership.margin_x;
+    let mut y = PAGE_HEIGHT_MM - theme.leadership.margin_top;
 
     // Title
-    layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-    let lead_heading = "Ministry Team Leadership";
-    let lead_heading_width = measure_helvetica_width(lead_heading, LEAD_TITLE_SIZE) * 1.07;
+    canvas.set_fill_color(&theme.colors.heading);
+    let title = locale.text(Text::MinistryTeamLeadership);
+    let lead_heading = if page.continued { locale.format(Text::Continued, title) } else { title.to_string() };
+    let lead_heading_width = fonts.bold.width(&lead_heading, theme.leadership.title_size);
     let lead_heading_x = left + (usable_width - lead_heading_width) / 2.0;
-    layer.use_text(lead_heading, LEAD_TITLE_SIZE, Mm(lead_heading_x), Mm(y), font_bold);
-    y -= 14.0;
+    canvas.text(&lead_heading, theme.leadership.title_size, lead_heading_x, y, &fonts.bold);
+    y = leadership_first_row_y(theme);
 
-    let gap_between = 4.0;
-    let names_x = left + LEAD_NAMES_INDENT;
-    let ministry_right_edge = names_x - gap_between;
-    let names_max_width = PAGE_WIDTH_MM - LEAD_MARGIN_X * 2.0 - LEAD_NAMES_INDENT;
+    let columns = &page.columns;
+    let names_x = left + columns.names_x;
+    let ministry_right_edge = names_x - LeadershipColumns::GAP;
 
-    for entry in entries {
+    for entry in &page.entries {
         let ministry_upper = entry.ministry.to_uppercase();
-        let ministry_width = measure_helvetica_width(&ministry_upper, LEAD_MINISTRY_SIZE) * 1.07;
+        let ministry_width = fonts.bold.width(&ministry_upper, theme.leadership.ministry_size);
         let ministry_x = ministry_right_edge - ministry_width;
-        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-        layer.use_text(&ministry_upper, LEAD_MINISTRY_SIZE, Mm(ministry_x), Mm(y), font_bold);
-
-        // Names on the same line, wrapping if needed
-        // Convert newlines to comma-separated
-        let names_text = entry.names
-            .lines()
-            .map(|l| l.trim())
-            .filter(|l| !l.is_empty())
-            .collect::<Vec<_>>()
-            .join(", ");
-
-        let wrapped = wrap_text(&names_text, LEAD_NAMES_SIZE, names_max_width);
-        for (i, line) in wrapped.iter().enumerate() {
-            let line_y = y - (i as f32 * 4.5);
-            layer.use_text(line, LEAD_NAMES_SIZE, Mm(names_x), Mm(line_y), font);
+        canvas.set_fill_color(&theme.colors.heading);
+        canvas.text(&ministry_upper, theme.leadership.ministry_size, ministry_x, y, &fonts.bold);
+
+        // Names on the same line, wrapping if needed, with the contact beside them
+        let (names, contact) = entry.lines(columns, theme, fonts);
+        canvas.set_fill_color(&theme.colors.text);
+        for (i, line) in names.iter().enumerate() {
+            let line_y = y - (i as f32 * theme.leadership.names_line_height);
+            canvas.text(line, theme.leadership.names_size, names_x, line_y, &fonts.regular);
+        }
+        canvas.set_fill_color(&theme.colors.muted);
+        for (i, line) in contact.iter().enumerate() {
+            let line_y = y - (i as f32 * theme.leadership.names_line_height);
+            canvas.text(line, theme.leadership.names_size, left + columns.contact_x, line_y, &fonts.regular);
         }
 
-        let extra_lines = if wrapped.len() > 1 { (wrapped.len() - 1) as f32 * 4.5 } else { 0.0 };
-        y -= LEAD_ROW_SPACING + extra_lines;
+        y -= entry.height(columns, theme, fonts);
     }
 
     // Decorative line and contact info
-    if !contact_left.is_empty() || !contact_right.is_empty() {
+    if !page.contact_left.is_empty() || !page.contact_right.is_empty() {
         y -= 4.0;
         let line_start = left;
         let line_end = left + usable_width;
-        let points = vec![
-            (Point::new(Mm(line_start), Mm(y)), false),
-            (Point::new(Mm(line_end), Mm(y)), false),
-        ];
-        let hr = Line {
-            points,
-            is_closed: false,
-        };
-        layer.set_outline_color(Color::Greyscale(Greyscale::new(0.4, None)));
-        layer.set_outline_thickness(0.75);
-        layer.add_line(hr);
+        canvas.set_outline_color(&theme.colors.rule);
+        canvas.set_outline_thickness(0.75);
+        canvas.line(line_start, y, line_end, y);
         y -= 10.0;
 
         // "Contact Info" heading
-        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
-        layer.use_text("Contact Info", LEAD_TITLE_SIZE, Mm(left), Mm(y), font_bold);
+        canvas.set_fill_color(&theme.colors.heading);
+        canvas.text(locale.text(Text::ContactInfo), theme.leadership.title_size, left, y, &fonts.bold);
         y -= 12.0;
 
         // Two-column contact info
-        let contact_size = 11.0;
-        let contact_line_height = 5.5;
+        let contact_size = theme.leadership.contact_size;
+        let contact_line_height = theme.leadership.contact_line_height;
         let col2_x = left + usable_width / 2.0;
-        layer.set_fill_color(Color::Greyscale(Greyscale::new(0.0, None)));
+        canvas.set_fill_color(&theme.colors.text);
 
-        let max_lines = contact_left.len().max(contact_right.len());
+        let max_lines = page.contact_left.len().max(page.contact_right.len());
         for i in 0..max_lines {
-            if let Some(line) = contact_left.get(i) {
-                layer.use_text(line, contact_size, Mm(left), Mm(y), font);
+            if let Some(line) = page.contact_left.get(i) {
+                canvas.text(line, contact_size, left, y, &fonts.regular);
             }
-            if let Some(line) = contact_right.get(i) {
-                layer.use_text(line, contact_size, Mm(col2_x), Mm(y), font);
+            if let Some(line) = page.contact_right.get(i) {
+                canvas.text(line, contact_size, col2_x, y, &fonts.regular);
             }
             y -= contact_line_height;
         }
     }
 }
 
-/// Apply rounded corners to an image by painting corner pixels white.
-fn round_image_corners(img: &mut image_crate::RgbImage, radius_fraction: f32) {
-    let (w, h) = (img.width(), img.height());
-    let radius = (w.min(h) as f32 * radius_fraction) as u32;
-    if radius == 0 {
-        return;
-    }
-    let white = image_crate::Rgb([255u8, 255, 255]);
-    let r = radius as f32;
-
-    for corner_y in 0..radius {
-        for corner_x in 0..radius {
-            let dx = r - corner_x as f32 - 0.5;
-            let dy = r - corner_y as f32 - 0.5;
-            if dx * dx + dy * dy > r * r {
-                // Top-left
-                img.put_pixel(corner_x, corner_y, white);
-                // Top-right
-                img.put_pixel(w - 1 - corner_x, corner_y, white);
-                // Bottom-left
-                img.put_pixel(corner_x, h - 1 - corner_y, white);
-                // Bottom-right
-                img.put_pixel(w - 1 - corner_x, h - 1 - corner_y, white);
-            }
-        }
-    }
-}
-
-/// Draw a rounded rectangle border on the PDF layer.
+/// Draw a rounded rectangle border around a photo.
 fn draw_rounded_rect_border(
-    layer: &PdfLayerReference,
+    canvas: &dyn PageCanvas,
     x: f32,
     y: f32,
     w: f32,
     h: f32,
     radius: f32,
-    line_width: f32,
+    style: &PhotoTheme,
 ) {
-    layer.set_outline_color(Color::Greyscale(Greyscale::new(0.0, None)));
-    layer.set_outline_thickness(line_width);
-
-    // Kappa for cubic Bezier approximation of quarter circle
-    let k = radius * 0.5523;
-
-    let points = vec![
-        // Start at bottom-left, just above the corner
-        (Point::new(Mm(x), Mm(y + radius)), false),
-        // Bottom-left corner
-        (Point::new(Mm(x), Mm(y + radius - k)), true),
-        (Point::new(Mm(x + radius - k), Mm(y)), true),
-        (Point::new(Mm(x + radius), Mm(y)), false),
-        // Bottom edge to bottom-right corner
-        (Point::new(Mm(x + w - radius), Mm(y)), false),
-        // Bottom-right corner
-        (Point::new(Mm(x + w - radius + k), Mm(y)), true),
-        (Point::new(Mm(x + w), Mm(y + radius - k)), true),
-        (Point::new(Mm(x + w), Mm(y + radius)), false),
-        // Right edge to top-right corner
-        (Point::new(Mm(x + w), Mm(y + h - radius)), false),
-        // Top-right corner
-        (Point::new(Mm(x + w), Mm(y + h - radius + k)), true),
-        (Point::new(Mm(x + w - radius + k), Mm(y + h)), true),
-        (Point::new(Mm(x + w - radius), Mm(y + h)), false),
-        // Top edge to top-left corner
-        (Point::new(Mm(x + radius), Mm(y + h)), false),
-        // Top-left corner
-        (Point::new(Mm(x + radius - k), Mm(y + h)), true),
-        (Point::new(Mm(x), Mm(y + h - radius + k)), true),
-        (Point::new(Mm(x), Mm(y + h - radius)), false),
-    ];
+    canvas.set_outline_color(&style.border_color);
+    canvas.set_outline_thickness(style.border_width);
 
-    let line = Line {
-        points,
-        is_closed: true,
-    };
+    canvas.rounded_rect(x, y, w, h, radius);
+}
 
-    layer.add_line(line);
+/// Prepared portrait for a staff entry, if it has a photo
+fn staff_photo<'a>(entry: &StaffEntry, images: &'a PreparedImages, theme: &PdfTheme) -> Option<&'a PreparedImage> {
+    let path = entry.photo_path.as_ref()?;
+    images.get(path, ImageTreatment::staff(theme.photos.corner_radius))
 }
 
 fn render_staff_photo(
-    layer: &PdfLayerReference,
-    image_path: &PathBuf,
+    canvas: &dyn PageCanvas,
+    image: &PreparedImage,
     x: f32,
     y: f32,
     w: f32,
     h: f32,
+    style: &PhotoTheme,
 ) {
-    if let Ok(img) = image_crate::open(image_path) {
-        let (img_width, img_height) = image_crate::GenericImageView::dimensions(&img);
+    canvas.image(image, x, y, image.dpi_for_width(w));
 
-        // Crop to 4:6 aspect ratio (center crop)
-        let target_aspect = 4.0 / 6.0;
-        let img_aspect = img_width as f32 / img_height as f32;
-
-        let cropped = if (img_aspect - target_aspect).abs() > 0.01 {
-            let (crop_w, crop_h) = if img_aspect > target_aspect {
-                let cw = (img_height as f32 * target_aspect) as u32;
-                (cw, img_height)
-            } else {
-                let ch = (img_width as f32 / target_aspect) as u32;
-                (img_width, ch)
-            };
-            let cx = (img_width - crop_w) / 2;
-            let cy = (img_height - crop_h) / 2;
-            img.crop_imm(cx, cy, crop_w, crop_h)
-        } else {
-            img
-        };
-
-        // Round the corners on the image pixels
-        let mut rgb = cropped.to_rgb8();
-        round_image_corners(&mut rgb, 0.06);
-
-        let (cw, _ch) = (rgb.width(), rgb.height());
-        let rounded_img = image_crate::DynamicImage::ImageRgb8(rgb);
-        let image_xobject = image_to_xobject(&rounded_img);
-        let image = Image::from(image_xobject);
-        let dpi = (cw as f32 * 25.4) / w;
-
-        image.add_to_layer(
-            layer.clone(),
-            ImageTransform {
-                translate_x: Some(Mm(x)),
-                translate_y: Some(Mm(y)),
-                dpi: Some(dpi),
-                ..Default::default()
-            },
-        );
-
-        // Draw rounded rectangle border
-        let corner_radius = w * 0.06;
-        draw_rounded_rect_border(layer, x, y, w, h, corner_radius, 0.5);
-    }
-}
-
-/// Convert a DynamicImage to an ImageXObject with correct RGB channel ordering.
-/// printpdf 0.7's `from_dynamic_image` can swap R and B channels, producing a pink tint.
-fn image_to_xobject(img: &image_crate::DynamicImage) -> ImageXObject {
-    let rgb = img.to_rgb8();
-    let (w, h) = (rgb.width(), rgb.height());
-    let data = rgb.into_raw();
-
-    ImageXObject {
-        width: Px(w as usize),
-        height: Px(h as usize),
-        color_space: ColorSpace::Rgb,
-        bits_per_component: ColorBits::Bit8,
-        interpolate: true,
-        image_data: data,
-        image_filter: None,
-        clipping_bbox: None,
-        smask: None,
-    }
-}
-
-/// Convert a DynamicImage to an ImageXObject, trimming transparent edges
-/// and compositing alpha onto a white background.
-fn image_to_xobject_with_transparency(img: &image_crate::DynamicImage) -> ImageXObject {
-    let rgba = img.to_rgba8();
-    let (orig_w, orig_h) = (rgba.width(), rgba.height());
-
-    // Find bounding box of non-transparent pixels (alpha > 10)
-    let mut min_x = orig_w;
-    let mut min_y = orig_h;
-    let mut max_x = 0u32;
-    let mut max_y = 0u32;
-    for py in 0..orig_h {
-        for px in 0..orig_w {
-            if rgba.get_pixel(px, py).0[3] > 10 {
-                min_x = min_x.min(px);
-                min_y = min_y.min(py);
-                max_x = max_x.max(px);
-                max_y = max_y.max(py);
-            }
-        }
+    // Draw rounded rectangle border
+    if style.border_style == BorderStyle::Solid {
+        let corner_radius = w * style.corner_radius;
+        draw_rounded_rect_border(canvas, x, y, w, h, corner_radius, style);
     }
+}
 
-    // If no opaque pixels found, use full image
-    let (crop_x, crop_y, w, h) = if max_x >= min_x && max_y >= min_y {
-        (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)
-    } else {
-        (0, 0, orig_w, orig_h)
-    };
-
-    let cropped = img.crop_imm(crop_x, crop_y, w, h);
-    let cropped_rgba = cropped.to_rgba8();
-    let mut rgb_data = Vec::with_capacity((w * h * 3) as usize);
+/// Full-page images with no inset are full-bleed: they fill the page and run
+/// `bleed` past the trim on the outer edges, but not across the fold
+fn is_full_bleed(theme: &PdfTheme) -> bool {
+    theme.cover.image_inset <= 0.0
+}
 
-    for pixel in cropped_rgba.pixels() {
-        let [r, g, b, a] = pixel.0;
-        let alpha = a as f32 / 255.0;
-        let inv = 1.0 - alpha;
-        rgb_data.push((r as f32 * alpha + 255.0 * inv) as u8);
-        rgb_data.push((g as f32 * alpha + 255.0 * inv) as u8);
-        rgb_data.push((b as f32 * alpha + 255.0 * inv) as u8);
+/// Size in mm of the area a full-page image is drawn into
+fn full_image_area(theme: &PdfTheme, bleed: f32) -> (f32, f32) {
+    if is_full_bleed(theme) {
+        (PAGE_WIDTH_MM + bleed, PAGE_HEIGHT_MM + 2.0 * bleed)
+    } else {
+        let inset = theme.cover.image_inset;
+        (PAGE_WIDTH_MM - 2.0 * inset, PAGE_HEIGHT_MM - 2.0 * inset)
     }
+}
 
-    ImageXObject {
-        width: Px(w as usize),
-        height: Px(h as usize),
-        color_space: ColorSpace::Rgb,
-        bits_per_component: ColorBits::Bit8,
-        interpolate: true,
-        image_data: rgb_data,
-        image_filter: None,
-        clipping_bbox: None,
-        smask: None,
+/// Full-bleed images are cropped to cover their area; inset images are
+/// scaled to fit inside it
+fn full_image_treatment(theme: &PdfTheme, bleed: f32) -> ImageTreatment {
+    if is_full_bleed(theme) {
+        let (width, height) = full_image_area(theme, bleed);
+        ImageTreatment::fill(width / height)
+    } else {
+        ImageTreatment::Full
     }
 }
 
 fn render_full_image(
-    _doc: &PdfDocumentReference,
-    layer: &PdfLayerReference,
-    image_path: &PathBuf,
+    canvas: &dyn PageCanvas,
+    image: Option<&PreparedImage>,
     x_offset: f32,
-    _is_left_half: bool,
+    is_left_half: bool,
+    bleed: f32,
+    theme: &PdfTheme,
 ) {
-    if let Ok(img) = image_crate::open(image_path) {
-        let (img_width, img_height) = image_crate::GenericImageView::dimensions(&img);
-        let img_aspect = img_width as f32 / img_height as f32;
+    if let Some(image) = image {
+        if is_full_bleed(theme) {
+            // Cropped to the bleed area's aspect, so it covers the area exactly
+            let (area_width, _) = full_image_area(theme, bleed);
+            let x = if is_left_half { x_offset - bleed } else { x_offset };
+            canvas.image(image, x, -bleed, image.dpi_for_width(area_width));
+            return;
+        }
+
+        let img_aspect = image.aspect();
 
         // 1/4 inch inset on all sides
-        let inset = PAGE_BORDER_INSET;
+        let inset = theme.cover.image_inset;
         let avail_width = PAGE_WIDTH_MM - 2.0 * inset;
         let avail_height = PAGE_HEIGHT_MM - 2.0 * inset;
         let avail_aspect = avail_width / avail_height;
@@ -2211,191 +3054,370 @@ fn render_full_image(
             (avail_height * img_aspect, avail_height)
         };
 
-        let image_xobject = image_to_xobject(&img);
-        let image = Image::from(image_xobject);
-
         // Center within the inset area
         let offset_x = x_offset + inset + (avail_width - render_width_mm) / 2.0;
         let offset_y = inset + (avail_height - render_height_mm) / 2.0;
 
-        let dpi = (img_width as f32 * 25.4) / render_width_mm;
+        let dpi = image.dpi_for_width(render_width_mm);
 
-        image.add_to_layer(
-            layer.clone(),
-            ImageTransform {
-                translate_x: Some(Mm(offset_x)),
-                translate_y: Some(Mm(offset_y)),
-                dpi: Some(dpi),
-                ..Default::default()
-            },
-        );
+        canvas.image(image, offset_x, offset_y, dpi);
     }
 }
 
-fn parse_hex_color(hex: &str) -> (f32, f32, f32) {
-    let hex = hex.trim_start_matches('#');
-    if hex.len() >= 6 {
-        let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or(255) as f32 / 255.0;
-        let g = u8::from_str_radix(&hex[2..4], 16).unwrap_or(255) as f32 / 255.0;
-        let b = u8::from_str_radix(&hex[4..6], 16).unwrap_or(255) as f32 / 255.0;
-        (r, g, b)
-    } else {
-        (1.0, 1.0, 1.0) // default white
-    }
+/// The current month and year, in the directory language
+fn cover_date_line(locale: Locale) -> String {
+    let now = Local::now();
+    locale.month_and_year(now.month0() as usize, now.year())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_cover_title(
-    layer: &PdfLayerReference,
+    canvas: &dyn PageCanvas,
     title_line1: &str,
     title_line2: &str,
     title_color: &str,
     x_offset: f32,
-    font_bold: &IndirectFontRef,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    locale: Locale,
 ) {
-    const COVER_TITLE_SIZE: f32 = 22.0;
-    const COVER_DATE_SIZE: f32 = 16.0;
-    const LINE_SPACING: f32 = 10.0;
-
-    // Build the current month and year string
-    let now = chrono::Local::now();
-    let date_line = now.format("%B %Y").to_string();
+    let date_line = cover_date_line(locale);
 
     // Collect non-empty lines
     let mut lines: Vec<(&str, f32)> = Vec::new();
     if !title_line1.is_empty() {
-        lines.push((title_line1, COVER_TITLE_SIZE));
+        lines.push((title_line1, theme.cover.title_size));
     }
     if !title_line2.is_empty() {
-        lines.push((title_line2, COVER_TITLE_SIZE));
+        lines.push((title_line2, theme.cover.title_size));
     }
-    lines.push((&date_line, COVER_DATE_SIZE));
+    lines.push((&date_line, theme.cover.date_size));
 
     if lines.is_empty() {
         return;
     }
 
     // Position title near the top of the page, below the inset
-    let start_y = PAGE_HEIGHT_MM - PAGE_BORDER_INSET - 40.4; // ~1 inch lower
+    let start_y = PAGE_HEIGHT_MM - theme.cover.image_inset - theme.cover.title_offset;
 
-    // Parse and set the title color
-    let (r, g, b) = parse_hex_color(title_color);
-    layer.set_fill_color(Color::Rgb(Rgb::new(r, g, b, None)));
+    canvas.set_fill_color(title_color);
 
     let page_center = x_offset + PAGE_WIDTH_MM / 2.0;
     let mut y = start_y;
 
     for (text, size) in &lines {
-        let text_width = estimate_text_width(text, *size) * 1.07; // bold correction
+        let text_width = fonts.bold.width(text, *size);
         let text_x = page_center - text_width / 2.0;
-        layer.use_text(*text, *size, Mm(text_x), Mm(y), font_bold);
-        y -= LINE_SPACING;
+        canvas.text(text, *size, text_x, y, &fonts.bold);
+        y -= theme.cover.line_spacing;
+    }
+}
+
+/// One row of a generated cover, stacked top to bottom
+enum CoverRow<'a> {
+    Logo { image: &'a PreparedImage, width: f32, height: f32 },
+    Text { text: String, size: f32, bold: bool, color: &'a str, leading: f32 },
+    Rule { color: &'a str, width: f32 },
+    Gap(f32),
+}
+
+impl CoverRow<'_> {
+    fn height(&self) -> f32 {
+        match self {
+            CoverRow::Logo { height, .. } => *height,
+            CoverRow::Text { leading, .. } => *leading,
+            CoverRow::Rule { .. } => 0.0,
+            CoverRow::Gap(gap) => *gap,
+        }
+    }
+}
+
+fn cover_rows_height(rows: &[CoverRow]) -> f32 {
+    rows.iter().map(CoverRow::height).sum()
+}
+
+/// The church logo scaled to fit the cover's logo height and text width
+fn cover_logo_row<'a>(church: &ChurchIdentity, theme: &PdfTheme, images: &'a PreparedImages) -> Option<CoverRow<'a>> {
+    let image = images.get(church.logo_path.as_ref()?, ImageTreatment::Flattened)?;
+    let max_width = PAGE_WIDTH_MM - 2.0 * theme.cover.margin;
+    let max_height = theme.cover.logo_height;
+    let (width, height) = if image.aspect() > max_width / max_height {
+        (max_width, max_width / image.aspect())
+    } else {
+        (max_height * image.aspect(), max_height)
+    };
+    Some(CoverRow::Logo { image, width, height })
+}
+
+/// Text wrapped to the cover's width, one row per line
+#[allow(clippy::too_many_arguments)]
+fn push_cover_text<'a>(
+    rows: &mut Vec<CoverRow<'a>>,
+    text: &str,
+    size: f32,
+    bold: bool,
+    color: &'a str,
+    leading: f32,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+) {
+    let font = if bold { &fonts.bold } else { &fonts.regular };
+    let width = PAGE_WIDTH_MM - 2.0 * theme.cover.margin;
+    for line in wrap_text(text, font, size, width) {
+        rows.push(CoverRow::Text { text: line, size, bold, color, leading });
+    }
+}
+
+/// The church name, wrapping onto as many lines as it needs
+fn push_cover_name<'a>(rows: &mut Vec<CoverRow<'a>>, church: &ChurchIdentity, theme: &'a PdfTheme, fonts: &PdfFonts) {
+    let size = theme.cover.name_size;
+    push_cover_text(rows, &church.name, size, true, &theme.colors.heading, size * COVER_NAME_LEADING, theme, fonts);
+}
+
+/// Draw cover rows downward from `top`, centered or flush with the left margin
+fn draw_cover_rows(
+    canvas: &dyn PageCanvas,
+    rows: &[CoverRow],
+    layout: CoverLayout,
+    x_offset: f32,
+    top: f32,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+) {
+    let row_x = |width: f32| match layout {
+        CoverLayout::Centered => x_offset + (PAGE_WIDTH_MM - width) / 2.0,
+        CoverLayout::LeftAligned => x_offset + theme.cover.margin,
+    };
+
+    let mut y = top;
+    for row in rows {
+        match row {
+            CoverRow::Logo { image, width, height } => {
+                canvas.image(image, row_x(*width), y - height, image.dpi_for_width(*width));
+            }
+            CoverRow::Text { text, size, bold, color, leading } => {
+                let font = if *bold { &fonts.bold } else { &fonts.regular };
+                canvas.set_fill_color(color);
+                // Baseline sits a fifth of the line height above the row's bottom
+                canvas.text(text, *size, row_x(font.width(text, *size)), y - leading * 0.8, font);
+            }
+            CoverRow::Rule { color, width } => {
+                let x = row_x(*width);
+                canvas.set_outline_color(color);
+                canvas.set_outline_thickness(0.75);
+                canvas.line(x, y, x + width, y);
+            }
+            CoverRow::Gap(_) => {}
+        }
+        y -= row.height();
+    }
+}
+
+/// Accent bar down the left margin of left-aligned covers
+fn draw_cover_accent_bar(canvas: &dyn PageCanvas, x_offset: f32, theme: &PdfTheme) {
+    let margin = theme.cover.margin;
+    let x = x_offset + margin / 2.0;
+    canvas.set_outline_color(&theme.colors.accent);
+    canvas.set_outline_thickness(3.0);
+    canvas.line(x, margin, x, PAGE_HEIGHT_MM - margin);
+}
+
+/// Front cover with the logo and church name at the top, the title lines and
+/// date below them, and the church's contact details along the bottom.
+#[allow(clippy::too_many_arguments)]
+fn render_template_cover(
+    canvas: &dyn PageCanvas,
+    layout: CoverLayout,
+    church: &ChurchIdentity,
+    title_line1: &str,
+    title_line2: &str,
+    x_offset: f32,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    images: &PreparedImages,
+    locale: Locale,
+) {
+    let cover = &theme.cover;
+    let text_width = PAGE_WIDTH_MM - 2.0 * cover.margin;
+    if layout == CoverLayout::LeftAligned {
+        draw_cover_accent_bar(canvas, x_offset, theme);
+    }
+
+    // Logo and name
+    let mut header = Vec::new();
+    if let Some(logo) = cover_logo_row(church, theme, images) {
+        header.push(logo);
+        header.push(CoverRow::Gap(8.0));
+    }
+    push_cover_name(&mut header, church, theme, fonts);
+    let header_top = PAGE_HEIGHT_MM - cover.margin;
+    draw_cover_rows(canvas, &header, layout, x_offset, header_top, theme, fonts);
+
+    // Title lines and date, no higher than just above the middle of the page
+    let date_line = cover_date_line(locale);
+    let mut title = vec![CoverRow::Rule { color: &theme.colors.accent, width: text_width * 0.3 }, CoverRow::Gap(4.0)];
+    for line in [title_line1, title_line2].into_iter().filter(|line| !line.is_empty()) {
+        push_cover_text(&mut title, line, cover.title_size, true, &theme.colors.heading, cover.line_spacing, theme, fonts);
+    }
+    push_cover_text(&mut title, &date_line, cover.date_size, false, &theme.colors.muted, cover.line_spacing, theme, fonts);
+    let title_top = (header_top - cover_rows_height(&header) - 16.0).min(PAGE_HEIGHT_MM * 0.55);
+    draw_cover_rows(canvas, &title, layout, x_offset, title_top, theme, fonts);
+
+    // Contact details: two lines when centered, one item per line when left-aligned
+    let contact_lines = match layout {
+        CoverLayout::Centered => {
+            let reach = church.phone_lines.iter().chain(&church.email).chain(&church.website);
+            [church.address_lines.join(", "), reach.cloned().collect::<Vec<_>>().join("  |  ")]
+                .into_iter()
+                .filter(|line| !line.is_empty())
+                .collect()
+        }
+        CoverLayout::LeftAligned => church.contact_lines(),
+    };
+    let mut contact = Vec::new();
+    for line in &contact_lines {
+        push_cover_text(&mut contact, line, cover.contact_size, false, &theme.colors.text, cover.contact_line_height, theme, fonts);
+    }
+    let contact_top = cover.margin + cover_rows_height(&contact);
+    draw_cover_rows(canvas, &contact, layout, x_offset, contact_top, theme, fonts);
+}
+
+/// Back cover with the logo, church name and contact details in one block,
+/// centered on the page or set in the bottom-left corner below an accent rule.
+fn render_template_back_cover(
+    canvas: &dyn PageCanvas,
+    layout: CoverLayout,
+    church: &ChurchIdentity,
+    x_offset: f32,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    images: &PreparedImages,
+) {
+    let cover = &theme.cover;
+    let text_width = PAGE_WIDTH_MM - 2.0 * cover.margin;
+
+    let mut rows = Vec::new();
+    if layout == CoverLayout::LeftAligned {
+        rows.push(CoverRow::Rule { color: &theme.colors.accent, width: text_width * 0.3 });
+        rows.push(CoverRow::Gap(8.0));
+    }
+    if let Some(logo) = cover_logo_row(church, theme, images) {
+        rows.push(logo);
+        rows.push(CoverRow::Gap(8.0));
+    }
+    push_cover_name(&mut rows, church, theme, fonts);
+    rows.push(CoverRow::Gap(4.0));
+    for line in church.contact_lines() {
+        push_cover_text(&mut rows, &line, cover.contact_size, false, &theme.colors.text, cover.contact_line_height, theme, fonts);
+    }
+
+    let height = cover_rows_height(&rows);
+    let top = match layout {
+        CoverLayout::Centered => (PAGE_HEIGHT_MM + height) / 2.0,
+        CoverLayout::LeftAligned => cover.margin + height,
+    };
+    draw_cover_rows(canvas, &rows, layout, x_offset, top, theme, fonts);
+}
+
+/// Cell and photo dimensions in mm for a photo grid page
+struct GridLayout {
+    cell_width: f32,
+    cell_height: f32,
+    photo_area_width: f32,
+    photo_area_height: f32,
+    /// Rendered photo size after fitting GRID_PHOTO_ASPECT within the photo area
+    photo_width: f32,
+    photo_height: f32,
+}
+
+impl GridLayout {
+    fn new(theme: &PdfTheme, grid_rows: usize) -> Self {
+        let usable_width = PAGE_WIDTH_MM - 2.0 * theme.grid.margin_x;
+        let usable_height = PAGE_HEIGHT_MM - theme.grid.margin_top - theme.grid.margin_bottom - theme.footer.baseline - theme.footer.font_size;
+
+        let cell_width =
+            (usable_width - (GRID_COLS as f32 - 1.0) * theme.grid.col_spacing) / GRID_COLS as f32;
+        let cell_height =
+            (usable_height - (grid_rows as f32 - 1.0) * theme.grid.row_spacing) / grid_rows as f32;
+        // Photo area is the cell minus caption below and padding
+        let photo_area_width = cell_width - 2.0 * theme.grid.cell_padding;
+        let photo_area_height =
+            cell_height - theme.grid.caption_height - 2.0 * theme.grid.cell_padding;
+
+        // Fit the cropped image (at GRID_PHOTO_ASPECT) within the photo area
+        let area_aspect = photo_area_width / photo_area_height;
+        let (photo_width, photo_height) = if GRID_PHOTO_ASPECT > area_aspect {
+            (photo_area_width, photo_area_width / GRID_PHOTO_ASPECT)
+        } else {
+            (photo_area_height * GRID_PHOTO_ASPECT, photo_area_height)
+        };
+
+        GridLayout {
+            cell_width,
+            cell_height,
+            photo_area_width,
+            photo_area_height,
+            photo_width,
+            photo_height,
+        }
     }
 }
 
 fn render_photo_grid(
-    _doc: &PdfDocumentReference,
-    layer: &PdfLayerReference,
+    canvas: &dyn PageCanvas,
     entries: &[PhotoGridEntry],
     x_offset: f32,
-    font: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
     grid_rows: usize,
+    images: &PreparedImages,
 ) {
-    let usable_width = PAGE_WIDTH_MM - 2.0 * GRID_MARGIN_X;
-    let usable_height = PAGE_HEIGHT_MM - GRID_MARGIN_TOP - GRID_MARGIN_BOTTOM - FOOTER_Y - FOOTER_FONT_SIZE;
-
-    let cell_width =
-        (usable_width - (GRID_COLS as f32 - 1.0) * GRID_COL_SPACING) / GRID_COLS as f32;
-    let cell_height =
-        (usable_height - (grid_rows as f32 - 1.0) * GRID_ROW_SPACING) / grid_rows as f32;
-    // Photo area is the cell minus caption below and padding
-    let photo_area_width = cell_width - 2.0 * GRID_CELL_PADDING;
-    let photo_area_height =
-        cell_height - GRID_CAPTION_HEIGHT - 2.0 * GRID_CELL_PADDING;
+    let GridLayout {
+        cell_width,
+        cell_height,
+        photo_area_width,
+        photo_area_height,
+        photo_width: render_w,
+        photo_height: render_h,
+    } = GridLayout::new(theme, grid_rows);
 
     for (i, entry) in entries.iter().enumerate() {
         // Column-major layout: fill down left column first, then right
         let col = i / grid_rows;
         let row = i % grid_rows;
 
-        let cell_x = x_offset + GRID_MARGIN_X + col as f32 * (cell_width + GRID_COL_SPACING);
+        let cell_x = x_offset + theme.grid.margin_x + col as f32 * (cell_width + theme.grid.col_spacing);
         let cell_top =
-            PAGE_HEIGHT_MM - GRID_MARGIN_TOP - row as f32 * (cell_height + GRID_ROW_SPACING);
+            PAGE_HEIGHT_MM - theme.grid.margin_top - row as f32 * (cell_height + theme.grid.row_spacing);
 
         // Track rendered photo position for aligning text
-        let mut rendered_img_x = cell_x + GRID_CELL_PADDING;
-
-        // 1. Render photo — center-crop to the uniform grid aspect ratio,
-        //    then fit the cropped image within the photo area.
-        if let Ok(img) = image_crate::open(&entry.photo_path) {
-            use image_crate::GenericImageView;
-            let (img_width, img_height) = img.dimensions();
-            let src_aspect = img_width as f32 / img_height as f32;
-
-            // Crop source to match GRID_PHOTO_ASPECT.
-            // Horizontal: center crop. Vertical: crop from the top (heads are up there).
-            let cropped = if (src_aspect - GRID_PHOTO_ASPECT).abs() > 0.001 {
-                let (crop_w, crop_h) = if src_aspect > GRID_PHOTO_ASPECT {
-                    // Source is wider — crop width
-                    let cw = (img_height as f32 * GRID_PHOTO_ASPECT) as u32;
-                    (cw, img_height)
-                } else {
-                    // Source is taller — crop height
-                    let ch = (img_width as f32 / GRID_PHOTO_ASPECT) as u32;
-                    (img_width, ch)
-                };
-                let cx = (img_width - crop_w) / 2;
-                // For vertical cropping, anchor to the top (cy = 0) to preserve heads
-                let cy = 0;
-                img.crop_imm(cx, cy, crop_w, crop_h)
-            } else {
-                img
-            };
-            let (cropped_w, cropped_h) = cropped.dimensions();
-
-            // Fit the cropped image (now at GRID_PHOTO_ASPECT) within the photo area
-            let area_aspect = photo_area_width / photo_area_height;
-            let (render_w, render_h) = if GRID_PHOTO_ASPECT > area_aspect {
-                (photo_area_width, photo_area_width / GRID_PHOTO_ASPECT)
-            } else {
-                (photo_area_height * GRID_PHOTO_ASPECT, photo_area_height)
-            };
+        let mut rendered_img_x = cell_x + theme.grid.cell_padding;
 
+        // 1. Render photo — prepared at the uniform grid aspect ratio, so it
+        //    fits the photo area exactly.
+        if let Some(image) = images.get(&entry.photo_path, ImageTreatment::Grid { crop: entry.photo_crop }) {
             // Photo area starts at top of cell
-            let photo_area_x = cell_x + GRID_CELL_PADDING;
-            let photo_area_top = cell_top - GRID_CELL_PADDING;
+            let photo_area_x = cell_x + theme.grid.cell_padding;
+            let photo_area_top = cell_top - theme.grid.cell_padding;
 
             let img_x = photo_area_x + (photo_area_width - render_w) / 2.0;
             let img_y = photo_area_top - render_h - (photo_area_height - render_h) / 2.0;
 
             rendered_img_x = img_x;
 
-            let dpi = (cropped_w as f32 * 25.4) / render_w;
-
-            let image_xobject = image_to_xobject(&cropped);
-            let image = Image::from(image_xobject);
-
-            image.add_to_layer(
-                layer.clone(),
-                ImageTransform {
-                    translate_x: Some(Mm(img_x)),
-                    translate_y: Some(Mm(img_y)),
-                    dpi: Some(dpi),
-                    ..Default::default()
-                },
-            );
+            canvas.image(image, img_x, img_y, image.dpi_for_width(render_w));
         }
 
         // 2. Render caption lines below photo: "LastName, Adults" then children
-        let caption_top = cell_top - cell_height + GRID_CAPTION_HEIGHT;
-        let caption_size = GRID_NAME_SIZE;
-        let line_spacing = 3.5;
+        let caption_top = cell_top - cell_height + theme.grid.caption_height;
+        let caption_size = theme.grid.caption_size;
+        let line_spacing = theme.grid.caption_line_spacing;
         let mut caption_y = caption_top - 2.5;
 
         // Family last name (bold) followed by adults on the same line.
         // Include the trailing ", " in the bold segment so the comma is drawn
-        // as part of the bold text — then use accurate Helvetica metrics
-        // (with a bold-width correction) to place the adults text.
+        // as part of the bold text — then measure it with the bold font
+        // to place the adults text.
         let has_adults = entry
             .directory_adults
             .as_ref()
@@ -2406,23 +3428,23 @@ fn render_photo_grid(
         } else {
             entry.family_name.clone()
         };
-        layer.use_text(
+        canvas.text(
             &bold_segment,
             caption_size,
-            Mm(rendered_img_x),
-            Mm(caption_y),
-            font_bold,
+            rendered_img_x,
+            caption_y,
+            &fonts.bold,
         );
 
         if has_adults {
-            let bold_width = measure_helvetica_width(&bold_segment, caption_size) * 1.07;
+            let bold_width = fonts.bold.width(&bold_segment, caption_size);
             if let Some(ref adults) = entry.directory_adults {
-                layer.use_text(
+                canvas.text(
                     adults,
                     caption_size,
-                    Mm(rendered_img_x + bold_width),
-                    Mm(caption_y),
-                    font,
+                    rendered_img_x + bold_width,
+                    caption_y,
+                    &fonts.regular,
                 );
             }
         }
@@ -2431,98 +3453,64 @@ fn render_photo_grid(
         // Children line
         if let Some(ref children) = entry.directory_children {
             if !children.is_empty() {
-                layer.use_text(children, caption_size, Mm(rendered_img_x), Mm(caption_y), font);
-            }
-        }
-    }
-}
-
-/// Crop an image to a circle: resize to a square, then composite onto a white background.
-fn crop_to_circle(img: &image_crate::DynamicImage, size: u32) -> image_crate::DynamicImage {
-    use image_crate::GenericImageView;
-    let (w, h) = img.dimensions();
-    // Center-crop to square first
-    let side = w.min(h);
-    let crop_x = (w - side) / 2;
-    let crop_y = (h - side) / 2;
-    let cropped = img.crop_imm(crop_x, crop_y, side, side);
-    let resized = cropped.resize_exact(size, size, image_crate::imageops::FilterType::Lanczos3);
-    let rgba = resized.to_rgba8();
-    let center = size as f32 / 2.0;
-    let radius_sq = center * center;
-    // Composite onto white background as RGB (PDF doesn't support alpha well)
-    let mut rgb = image_crate::RgbImage::new(size, size);
-    for py in 0..size {
-        for px in 0..size {
-            let dx = px as f32 - center;
-            let dy = py as f32 - center;
-            if dx * dx + dy * dy <= radius_sq {
-                let p = rgba.get_pixel(px, py);
-                rgb.put_pixel(px, py, image_crate::Rgb([p.0[0], p.0[1], p.0[2]]));
-            } else {
-                rgb.put_pixel(px, py, image_crate::Rgb([255, 255, 255]));
+                canvas.text(children, caption_size, rendered_img_x, caption_y, &fonts.regular);
             }
         }
     }
-    image_crate::DynamicImage::ImageRgb8(rgb)
 }
 
-/// Size of the circular member photo in mm
-const MEMBER_PHOTO_SIZE_MM: f32 = 8.0;
-/// Pixel size for the circular crop (higher = better quality)
-const MEMBER_PHOTO_PIXELS: u32 = 192;
-
 fn render_text_cards(
-    layer: &PdfLayerReference,
+    canvas: &dyn PageCanvas,
     columns: &[Vec<FamilyCardData>; 2],
     x_offset: f32,
-    font: &IndirectFontRef,
-    font_bold: &IndirectFontRef,
+    theme: &PdfTheme,
+    fonts: &PdfFonts,
+    images: &PreparedImages,
+    locale: Locale,
 ) {
-    let usable_width = PAGE_WIDTH_MM - 2.0 * CARD_MARGIN;
-    let col_width = (usable_width - CARD_COL_GAP) / CARD_COLS as f32;
+    let col_width = card_column_width(theme);
 
     for (col_idx, col_cards) in columns.iter().enumerate() {
-        let col_x = x_offset + CARD_MARGIN + col_idx as f32 * (col_width + CARD_COL_GAP);
-        let mut y = PAGE_HEIGHT_MM - CARD_MARGIN;
+        let col_x = x_offset + theme.cards.margin + col_idx as f32 * (col_width + theme.cards.col_gap);
+        let mut y = PAGE_HEIGHT_MM - theme.cards.margin;
 
         for card in col_cards.iter() {
             // Name (bold)
-            layer.use_text(&card.name, CARD_NAME_SIZE, Mm(col_x), Mm(y), font_bold);
-            y -= CARD_NAME_SIZE * 0.6;
+            canvas.text(&card.name, theme.cards.name_size, col_x, y, &fonts.bold);
+            y -= theme.cards.name_size * 0.6;
 
             if let Some(ref mailing_name) = card.mailing_name {
-                layer.use_text(mailing_name, CARD_TEXT_SIZE, Mm(col_x), Mm(y), font);
-                y -= CARD_LINE_HEIGHT;
+                canvas.text(mailing_name, theme.cards.text_size, col_x, y, &fonts.regular);
+                y -= theme.cards.line_height;
             }
             if let Some(ref address) = card.address {
-                layer.use_text(address, CARD_TEXT_SIZE, Mm(col_x), Mm(y), font);
-                y -= CARD_LINE_HEIGHT;
+                canvas.text(address, theme.cards.text_size, col_x, y, &fonts.regular);
+                y -= theme.cards.line_height;
             }
             if let Some(ref csz) = card.city_state_zip {
-                layer.use_text(csz, CARD_TEXT_SIZE, Mm(col_x), Mm(y), font);
-                y -= CARD_LINE_HEIGHT;
+                canvas.text(csz, theme.cards.text_size, col_x, y, &fonts.regular);
+                y -= theme.cards.line_height;
             }
             if card.alt_address.is_some() || card.alt_city_state_zip.is_some() {
-                layer.use_text("Alt Address:", CARD_TEXT_SIZE, Mm(col_x), Mm(y), font_bold);
-                y -= CARD_LINE_HEIGHT;
+                canvas.text(locale.text(Text::AltAddress), theme.cards.text_size, col_x, y, &fonts.bold);
+                y -= theme.cards.line_height;
                 if let Some(ref alt_address) = card.alt_address {
-                    layer.use_text(alt_address, CARD_TEXT_SIZE, Mm(col_x), Mm(y), font);
-                    y -= CARD_LINE_HEIGHT;
+                    canvas.text(alt_address, theme.cards.text_size, col_x, y, &fonts.regular);
+                    y -= theme.cards.line_height;
                 }
                 if let Some(ref alt_csz) = card.alt_city_state_zip {
-                    layer.use_text(alt_csz, CARD_TEXT_SIZE, Mm(col_x), Mm(y), font);
-                    y -= CARD_LINE_HEIGHT;
+                    canvas.text(alt_csz, theme.cards.text_size, col_x, y, &fonts.regular);
+                    y -= theme.cards.line_height;
                 }
             }
             if let Some(ref phone) = card.phone {
-                let home_phone = format!("H: {}", phone);
-                layer.use_text(&home_phone, CARD_TEXT_SIZE, Mm(col_x), Mm(y), font);
-                y -= CARD_LINE_HEIGHT;
+                let home_phone = locale.format(Text::HomePhone, phone);
+                canvas.text(&home_phone, theme.cards.text_size, col_x, y, &fonts.regular);
+                y -= theme.cards.line_height;
             }
             // Check if any member in this family has a photo — if so, indent all names
             let any_member_has_photo = card.members.iter().any(|m| m.photo_path.is_some());
-            let indented_x = col_x + MEMBER_PHOTO_SIZE_MM + 1.0;
+            let indented_x = col_x + theme.cards.member_photo_size + 1.0;
 
             // Member info
             for member in &card.members {
@@ -2530,71 +3518,71 @@ fn render_text_cards(
                 let text_x = if any_member_has_photo { indented_x } else { col_x };
 
                 // Render photo if this member has one
-                if let Some(ref photo_path) = member.photo_path {
-                    if let Ok(img) = image_crate::open(photo_path) {
-                        let circle_img = crop_to_circle(&img, MEMBER_PHOTO_PIXELS);
-                        let dpi = (MEMBER_PHOTO_PIXELS as f32 * 25.4) / MEMBER_PHOTO_SIZE_MM;
-                        let image_xobject = image_to_xobject(&circle_img);
-                        let image = Image::from(image_xobject);
-                        let photo_y = y - MEMBER_PHOTO_SIZE_MM + CARD_TEXT_SIZE * 0.35;
-                        image.add_to_layer(
-                            layer.clone(),
-                            ImageTransform {
-                                translate_x: Some(Mm(col_x)),
-                                translate_y: Some(Mm(photo_y)),
-                                dpi: Some(dpi),
-                                ..Default::default()
-                            },
-                        );
-                    }
+                let avatar = member
+                    .photo_path
+                    .as_ref()
+                    .and_then(|path| images.get(path, ImageTreatment::Avatar));
+                if let Some(image) = avatar {
+                    let dpi = image.dpi_for_width(theme.cards.member_photo_size);
+                    let photo_y = y - theme.cards.member_photo_size + theme.cards.text_size * 0.35;
+                    canvas.image(image, col_x, photo_y, dpi);
                 }
 
                 // Name (bold)
-                layer.use_text(&member.name, CARD_TEXT_SIZE, Mm(text_x), Mm(y), font_bold);
-                y -= CARD_LINE_HEIGHT;
+                canvas.text(&member.name, theme.cards.text_size, text_x, y, &fonts.bold);
+                y -= theme.cards.line_height;
 
                 // Cell phone and email
                 if let Some(ref phone) = member.phone {
-                    let cell_phone = format!("C: {}", phone);
-                    layer.use_text(&cell_phone, CARD_TEXT_SIZE, Mm(text_x), Mm(y), font);
-                    y -= CARD_LINE_HEIGHT;
+                    let cell_phone = locale.format(Text::CellPhone, phone);
+                    canvas.text(&cell_phone, theme.cards.text_size, text_x, y, &fonts.regular);
+                    y -= theme.cards.line_height;
                 }
                 if let Some(ref email) = member.email {
-                    layer.use_text(email, CARD_TEXT_SIZE, Mm(text_x), Mm(y), font);
-                    y -= CARD_LINE_HEIGHT;
+                    canvas.text(email, theme.cards.text_size, text_x, y, &fonts.regular);
+                    y -= theme.cards.line_height;
                 }
 
                 // If family has photos, ensure y advances enough for photo height
                 if any_member_has_photo {
                     let text_used = member_start_y - y;
-                    let photo_height = MEMBER_PHOTO_SIZE_MM + 1.0;
+                    let photo_height = theme.cards.member_photo_size + 1.0;
                     if text_used < photo_height {
                         y = member_start_y - photo_height;
                     }
                 }
             }
 
-            y -= CARD_SPACING;
+            y -= theme.cards.spacing;
         }
     }
 }
 
+fn card_column_width(theme: &PdfTheme) -> f32 {
+    let usable_width = PAGE_WIDTH_MM - 2.0 * theme.cards.margin;
+    (usable_width - theme.cards.col_gap) / CARD_COLS as f32
+}
+
+/// Height available to cards in one column, reserving space for the footer
+fn card_column_height(theme: &PdfTheme) -> f32 {
+    PAGE_HEIGHT_MM - theme.cards.margin - theme.footer.baseline - theme.footer.font_size - 2.0
+}
+
 /// Paginate family cards into pages, with two columns per page.
 /// Returns Vec of [left_column, right_column] per page.
-fn paginate_cards(cards: &[FamilyCardData]) -> Vec<[Vec<FamilyCardData>; 2]> {
+fn paginate_cards(cards: &[FamilyCardData], theme: &PdfTheme) -> Vec<[Vec<FamilyCardData>; 2]> {
     if cards.is_empty() {
         return Vec::new();
     }
 
-    // Reserve space for footer
-    let usable_height = PAGE_HEIGHT_MM - CARD_MARGIN - FOOTER_Y - FOOTER_FONT_SIZE - 2.0;
+    let usable_height = card_column_height(theme);
     let mut pages: Vec<[Vec<FamilyCardData>; 2]> = Vec::new();
     let mut current_cols: [Vec<FamilyCardData>; 2] = [Vec::new(), Vec::new()];
     let mut col_heights = [0.0f32; 2];
     let mut current_col = 0; // Start with left column
 
     for card in cards {
-        let card_h = card.height();
+        let card_h = card.height(theme);
 
         // Check if card fits in the current column
         if col_heights[current_col] + card_h > usable_height {
@@ -2641,3 +3629,181 @@ pub fn get_family_count(db: State<'_, Database>) -> Result<usize, String> {
         .map_err(|e| e.to_string())?;
     Ok(count as usize)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::PreparedImage;
+    use serde_json::json;
+
+    fn member(id: i64, first_name: &str, last_name: &str, role: &str, birth: Option<&str>, wedding: Option<&str>) -> serde_json::Value {
+        json!({
+            "id": id, "family_id": 1, "first_name": first_name, "last_name": last_name, "role": role,
+            "birth_date": birth, "wedding_date": wedding, "sort_order": id,
+            "created_at": "", "updated_at": "",
+        })
+    }
+
+    fn family(id: i64, members: Vec<serde_json::Value>) -> FamilyWithMembers {
+        serde_json::from_value(json!({
+            "id": id, "family_id": format!("F{:03}", id), "name": "Family", "include_photo_in_directory": true,
+            "created_at": "", "updated_at": "", "members": members,
+        }))
+        .unwrap()
+    }
+
+    /// Celebrations counted for 2026, with `settings` merged into the options
+    fn celebrations(families: &[FamilyWithMembers], settings: serde_json::Value) -> Vec<CelebrationMonth> {
+        let mut options = json!({ "church_name": "First Church", "celebrations_year": 2026 });
+        options.as_object_mut().unwrap().extend(settings.as_object().unwrap().clone());
+        gather_celebrations(families, &serde_json::from_value(options).unwrap(), Locale::English)
+    }
+
+    /// (name, note, milestone) of every anniversary, or every birthday
+    fn entries(months: &[CelebrationMonth], anniversaries: bool) -> Vec<(String, Option<String>, bool)> {
+        months
+            .iter()
+            .flat_map(|month| if anniversaries { &month.anniversaries } else { &month.birthdays })
+            .map(|entry| (entry.name.clone(), entry.note.clone(), entry.milestone))
+            .collect()
+    }
+
+    fn font_data() -> FontData {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::migrations::migrate(&mut conn).unwrap();
+        FontData::load(&conn, Path::new(""))
+    }
+
+    fn color_jpeg() -> PreparedImage {
+        let pixels = image_crate::RgbImage::from_pixel(4, 4, image_crate::Rgb([200, 40, 40]));
+        let mut jpeg = Vec::new();
+        image_crate::codecs::jpeg::JpegEncoder::new(&mut jpeg).encode_image(&pixels).unwrap();
+        PreparedImage { width: 4, height: 4, grayscale: false, jpeg }
+    }
+
+    #[test]
+    fn pairs_a_spouse_missing_the_wedding_date() {
+        let smiths = family(1, vec![
+            member(1, "John", "Smith", "Head", None, Some("1976-06-12")),
+            member(2, "Mary", "Smith", "Spouse", None, None),
+            member(3, "Tim", "Smith", "Child", None, None),
+        ]);
+        let months = celebrations(&[smiths], json!({}));
+        assert_eq!(months[5].anniversaries.len(), 1);
+        assert_eq!(months[5].anniversaries[0].name, "John & Mary Smith");
+        assert_eq!(months[5].anniversaries[0].member_ids, [1, 2]);
+    }
+
+    #[test]
+    fn names_both_spouses_in_full_when_last_names_differ() {
+        let couple = family(1, vec![
+            member(1, "John", "Smith", "Head", None, Some("06-12")),
+            member(2, "Mary", "Jones", "Spouse", None, Some("06-12")),
+        ]);
+        let months = celebrations(&[couple], json!({}));
+        assert_eq!(entries(&months, true), [("John Smith & Mary Jones".to_string(), None, false)]);
+    }
+
+    #[test]
+    fn notes_milestone_anniversaries() {
+        let families: Vec<FamilyWithMembers> = [("A", "2001"), ("B", "1986"), ("C", "1976"), ("D", "2016")]
+            .iter()
+            .enumerate()
+            .map(|(i, (name, year))| {
+                let wedding = format!("{}-01-{:02}", year, i + 1);
+                family(i as i64 + 1, vec![member(i as i64 + 1, name, "Smith", "Head", None, Some(&wedding))])
+            })
+            .collect();
+        let note = |text: &str| Some(text.to_string());
+
+        let months = celebrations(&families, json!({}));
+        assert_eq!(
+            entries(&months, true),
+            [
+                ("A Smith".to_string(), note("25th anniversary"), true),
+                ("B Smith".to_string(), note("40th anniversary"), true),
+                ("C Smith".to_string(), note("50th anniversary"), true),
+                ("D Smith".to_string(), None, false),
+            ]
+        );
+
+        let months = celebrations(&families, json!({ "show_anniversary_years": true }));
+        assert_eq!(entries(&months, true)[3], ("D Smith".to_string(), note("10th anniversary"), false));
+    }
+
+    #[test]
+    fn highlights_milestone_birthdays() {
+        let family = family(1, vec![
+            member(1, "Ann", "Lee", "Head", Some("1946-03-01"), None),
+            member(2, "Bob", "Lee", "Spouse", Some("1936-03-02"), None),
+            member(3, "Cal", "Lee", "Parent", Some("1925-03-03"), None),
+            member(4, "Dee", "Lee", "Parent", Some("1956-03-04"), None),
+            member(5, "Eve", "Lee", "Parent", Some("03-05"), None),
+        ]);
+        let note = |text: &str| Some(text.to_string());
+
+        let months = celebrations(std::slice::from_ref(&family), json!({ "highlight_birthday_milestones": true }));
+        assert_eq!(
+            entries(&months, false),
+            [
+                ("Ann Lee".to_string(), note("80th birthday"), true),
+                ("Bob Lee".to_string(), note("90th birthday"), true),
+                ("Cal Lee".to_string(), note("101st birthday"), true),
+                ("Dee Lee".to_string(), None, false),
+                ("Eve Lee".to_string(), None, false),
+            ]
+        );
+
+        let months = celebrations(&[family], json!({}));
+        assert!(entries(&months, false).iter().all(|(_, note, milestone)| note.is_none() && !milestone));
+    }
+
+    #[test]
+    fn bundled_fonts_lack_hangul() {
+        let fonts = PdfFonts::load(&font_data()).unwrap();
+        for font in [&fonts.regular, &fonts.bold] {
+            assert!(font.metrics.missing_chars(&Locale::English.printed_text()).is_empty());
+            assert!(font.metrics.missing_chars(&Locale::Spanish.printed_text()).is_empty());
+            assert!(font.metrics.missing_chars(&Locale::Korean.printed_text()).contains(&'월'));
+        }
+    }
+
+    #[test]
+    fn pdf_x_output_is_cmyk_without_optional_content_or_links() {
+        let data = font_data();
+        let fonts = PdfFonts::load(&data).unwrap();
+        let (doc, page, layer) = PdfDocument::new("Test", Mm(100.0), Mm(100.0), "Layer 1");
+        let doc = doc.with_conformance(PdfConformance::X3_2002_PDF_1_3).with_trapping(false);
+        let embedded = EmbeddedFonts::embed(&doc, &data).unwrap();
+        let canvas = PdfCanvas::new(doc.get_page(page).get_layer(layer), &embedded).with_pdf_x(true);
+        canvas.set_fill_color("#336699");
+        canvas.set_outline_color("#000000");
+        canvas.text("Hello", 12.0, 10.0, 10.0, &fonts.regular);
+        canvas.line(10.0, 20.0, 50.0, 20.0);
+        canvas.image(&color_jpeg(), 10.0, 30.0, 72.0);
+        canvas.link(10.0, 10.0, 20.0, 5.0, "https://example.com");
+
+        let bytes = strip_optional_content(&doc.save_to_bytes().unwrap()).unwrap();
+        let pdf = lopdf::Document::load_mem(&bytes).unwrap();
+        let catalog = pdf.catalog().unwrap();
+        assert!(catalog.get(b"OCProperties").is_err());
+        assert!(catalog.get(b"OutputIntents").is_ok());
+
+        let page_id = pdf.get_pages()[&1];
+        let annotations = pdf.get_dictionary(page_id).unwrap().get(b"Annots").and_then(lopdf::Object::as_array);
+        assert!(annotations.map_or(true, |annotations| annotations.is_empty()));
+        let content = lopdf::content::Content::decode(&pdf.get_page_content(page_id).unwrap()).unwrap();
+        let operators: Vec<&str> = content.operations.iter().map(|op| op.operator.as_str()).collect();
+        assert!(operators.contains(&"k") && operators.contains(&"K"));
+        assert!(!operators.iter().any(|op| ["rg", "RG", "BDC", "EMC"].contains(op)));
+
+        let color_spaces: Vec<&[u8]> = pdf
+            .objects
+            .values()
+            .filter_map(|object| object.as_stream().ok())
+            .filter(|stream| stream.dict.get(b"Subtype").and_then(lopdf::Object::as_name).ok() == Some(b"Image"))
+            .filter_map(|stream| stream.dict.get(b"ColorSpace").and_then(lopdf::Object::as_name).ok())
+            .collect();
+        assert_eq!(color_spaces, [b"DeviceCMYK"]);
+    }
+}
@@ -1,15 +1,49 @@
 use crate::db::Database;
+use crate::markdown::image_file_names;
+use crate::models::{
+    MissingPhotoReference, PhotoCleanupResult, PhotoEdits, PhotoStorageReport, StoredPhotoFile,
+};
 use base64::{engine::general_purpose::STANDARD, Engine};
 use image::imageops::FilterType;
-use image::GenericImageView;
+use image::metadata::Orientation;
+use image::{DynamicImage, GenericImageView, ImageDecoder, ImageReader};
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::io::{Cursor, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tauri::{AppHandle, State};
 use uuid::Uuid;
+use walkdir::WalkDir;
 
 const MAX_IMAGE_SIZE: u32 = 1200;
 const THUMBNAIL_SIZE: u32 = 200;
 
+// Longest side of the edit dialog's live preview
+const EDIT_PREVIEW_SIZE: u32 = 600;
+
+// Unreferenced files newer than this are never treated as orphans. Uploads are
+// written before the row or setting that points at them is saved.
+const ORPHAN_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+// Settings that hold a photo filename: (key, folder under photos/, description)
+const SETTING_PHOTOS: [(&str, &str, &str); 4] = [
+    ("church_logo_path", "", "Church logo"),
+    ("cover_image_path", "directory", "Cover image"),
+    ("back_cover_image_path", "directory", "Back cover image"),
+    ("celebration_image_path", "directory", "Celebrations page image"),
+];
+
+// Folders under photos/ whose photos can be edited
+const EDITABLE_PHOTO_FOLDERS: [&str; 4] = ["families", "members", "staff", "directory"];
+
+// Settings holding Markdown whose inline images load from photos/directory:
+// (key, description)
+const SETTING_MARKDOWN: [(&str, &str); 2] = [
+    ("pastor_letter", "Pastor letter image"),
+    ("first_page_markdown", "First page image"),
+];
+
 #[tauri::command]
 pub fn save_family_photo(
     app_handle: AppHandle,
@@ -25,7 +59,7 @@ pub fn save_family_photo(
 
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
     conn.execute(
-        "UPDATE families SET photo_path = ?, updated_at = datetime('now') WHERE id = ?",
+        "UPDATE families SET photo_path = ?, photo_crop = NULL, updated_at = datetime('now') WHERE id = ?",
         rusqlite::params![saved_path, family_id],
     )
     .map_err(|e| e.to_string())?;
@@ -104,6 +138,15 @@ pub fn delete_photo(
         fs::remove_file(&full_res_path).map_err(|e| e.to_string())?;
     }
 
+    if let Some(original_path) = get_original_path(&full_path) {
+        fs::remove_file(&original_path).map_err(|e| e.to_string())?;
+    }
+
+    let edits_path = get_edits_path(&full_path);
+    if edits_path.exists() {
+        fs::remove_file(&edits_path).map_err(|e| e.to_string())?;
+    }
+
     Ok(())
 }
 
@@ -181,6 +224,10 @@ pub fn crop_family_photo_to_member(
         .save(&full_path)
         .map_err(|e| format!("Failed to save full resolution cropped image: {}", e))?;
 
+    // The crop is the member photo's original for later edits
+    fs::copy(&full_path, original_path_with_extension(&dest_path, "jpg"))
+        .map_err(|e| format!("Failed to save original image: {}", e))?;
+
     // Create thumbnail
     let thumbnail = cropped.resize_exact(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Lanczos3);
     let thumb_path = get_thumbnail_path(&dest_path);
@@ -243,9 +290,29 @@ fn process_and_save_image(
 ) -> Result<String, String> {
     // Read bytes first, then detect format from content (not extension) for robustness
     let data = fs::read(source_path).map_err(|e| format!("Failed to read image file: {}", e))?;
-    let img = image::load_from_memory(&data)
-        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let (img, orientation) = decode_with_orientation(&data)?;
+
+    let uuid = Uuid::new_v4();
+    let filename = format!("{}_{}.jpg", prefix, uuid);
+    let dest_path = dest_dir.join(&filename);
+
+    // Keep the untouched original so edits can be redone later
+    let extension = image::guess_format(&data)
+        .ok()
+        .and_then(|format| format.extensions_str().first().copied())
+        .unwrap_or("jpg");
+    fs::write(original_path_with_extension(&dest_path, extension), &data)
+        .map_err(|e| format!("Failed to save original image: {}", e))?;
+
+    let edited = apply_edits(img, orientation, &PhotoEdits::default());
+    save_variants(&edited, &dest_path)?;
+
+    Ok(filename)
+}
 
+/// Write the display (max 1200px for UI), full resolution and thumbnail
+/// versions of a photo
+fn save_variants(img: &DynamicImage, dest_path: &Path) -> Result<(), String> {
     let (width, height) = img.dimensions();
     let resized = if width > MAX_IMAGE_SIZE || height > MAX_IMAGE_SIZE {
         img.resize(MAX_IMAGE_SIZE, MAX_IMAGE_SIZE, FilterType::Lanczos3)
@@ -253,31 +320,480 @@ fn process_and_save_image(
         img.clone()
     };
 
-    let uuid = Uuid::new_v4();
-    let filename = format!("{}_{}.jpg", prefix, uuid);
-    let dest_path = dest_dir.join(&filename);
-
-    // Save display version (max 1200px for UI)
     resized
         .to_rgb8()
-        .save(&dest_path)
+        .save(dest_path)
         .map_err(|e| format!("Failed to save image: {}", e))?;
 
     // Save full resolution version for print quality PDFs
-    let full_path = get_full_resolution_path(&dest_path);
+    let full_path = get_full_resolution_path(dest_path);
     img.to_rgb8()
         .save(&full_path)
         .map_err(|e| format!("Failed to save full resolution image: {}", e))?;
 
     // Save thumbnail
     let thumbnail = img.resize_exact(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Lanczos3);
-    let thumb_path = get_thumbnail_path(&dest_path);
+    let thumb_path = get_thumbnail_path(dest_path);
     thumbnail
         .to_rgb8()
         .save(&thumb_path)
         .map_err(|e| format!("Failed to save thumbnail: {}", e))?;
 
-    Ok(filename)
+    Ok(())
+}
+
+/// Decode an image along with the orientation recorded in its EXIF data
+fn decode_with_orientation(data: &[u8]) -> Result<(DynamicImage, Orientation), String> {
+    let mut decoder = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let img = DynamicImage::from_decoder(decoder)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    Ok((img, orientation))
+}
+
+/// Produce an edited photo from its original
+fn apply_edits(mut img: DynamicImage, orientation: Orientation, edits: &PhotoEdits) -> DynamicImage {
+    if edits.auto_orient {
+        img.apply_orientation(orientation);
+    }
+
+    img = match edits.rotation {
+        90 => img.rotate90(),
+        180 => img.rotate180(),
+        270 => img.rotate270(),
+        _ => img,
+    };
+    if edits.flip_horizontal {
+        img = img.fliph();
+    }
+    if edits.flip_vertical {
+        img = img.flipv();
+    }
+
+    if let Some(crop) = edits.crop {
+        let (width, height) = img.dimensions();
+        let x = ((crop.x.clamp(0.0, 1.0) * width as f32) as u32).min(width - 1);
+        let y = ((crop.y.clamp(0.0, 1.0) * height as f32) as u32).min(height - 1);
+        let crop_width = ((crop.width * width as f32) as u32).clamp(1, width - x);
+        let crop_height = ((crop.height * height as f32) as u32).clamp(1, height - y);
+        img = img.crop_imm(x, y, crop_width, crop_height);
+    }
+
+    if edits.exposure != 0.0 {
+        let gain = 2f32.powf(edits.exposure);
+        let mut rgb = img.to_rgb8();
+        for channel in rgb.iter_mut() {
+            *channel = (*channel as f32 * gain).round().min(255.0) as u8;
+        }
+        img = DynamicImage::ImageRgb8(rgb);
+    }
+    if edits.contrast != 0.0 {
+        img = img.adjust_contrast(edits.contrast);
+    }
+
+    img
+}
+
+fn validate_edits(edits: &PhotoEdits) -> Result<(), String> {
+    if ![0, 90, 180, 270].contains(&edits.rotation) {
+        return Err(format!("Unsupported rotation: {} degrees", edits.rotation));
+    }
+    if !(-2.0..=2.0).contains(&edits.exposure) {
+        return Err("Exposure must be between -2 and 2 stops".to_string());
+    }
+    if !(-100.0..=100.0).contains(&edits.contrast) {
+        return Err("Contrast must be between -100 and 100".to_string());
+    }
+    Ok(())
+}
+
+/// Load a photo's original. Photos saved before originals were kept use their
+/// full resolution version, which is copied to become the original so that
+/// repeated edits never compound.
+fn load_original(photo_path: &Path) -> Result<(DynamicImage, Orientation), String> {
+    let original_path = match get_original_path(photo_path) {
+        Some(path) => path,
+        None => {
+            let full_path = get_full_resolution_path(photo_path);
+            let source = if full_path.exists() { full_path } else { photo_path.to_path_buf() };
+            let original_path = original_path_with_extension(photo_path, "jpg");
+            fs::copy(&source, &original_path)
+                .map_err(|e| format!("Failed to save original image: {}", e))?;
+            original_path
+        }
+    };
+    let data = fs::read(&original_path).map_err(|e| format!("Failed to read original image: {}", e))?;
+    decode_with_orientation(&data)
+}
+
+fn load_edits(photo_path: &Path) -> Result<PhotoEdits, String> {
+    let edits_path = get_edits_path(photo_path);
+    if !edits_path.exists() {
+        return Ok(PhotoEdits::default());
+    }
+    let json = fs::read_to_string(&edits_path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| format!("Invalid photo edits: {}", e))
+}
+
+fn existing_photo_path(app_handle: &AppHandle, photo_type: &str, filename: &str) -> Result<PathBuf, String> {
+    if !EDITABLE_PHOTO_FOLDERS.contains(&photo_type) {
+        return Err(format!("Unknown photo type: {}", photo_type));
+    }
+    if matches!(filename, "" | "." | "..") || filename.contains(['/', '\\', ':']) {
+        return Err(format!("Invalid photo filename: {}", filename));
+    }
+    let photo_path = Database::get_photos_dir(app_handle).join(photo_type).join(filename);
+    if !photo_path.exists() {
+        return Err(format!("Photo not found: {:?}", photo_path));
+    }
+    Ok(photo_path)
+}
+
+#[tauri::command]
+pub fn get_photo_edits(
+    app_handle: AppHandle,
+    photo_type: String,
+    filename: String,
+) -> Result<PhotoEdits, String> {
+    let photo_path = existing_photo_path(&app_handle, &photo_type, &filename)?;
+    load_edits(&photo_path)
+}
+
+/// Render a photo's original with the given edits as a small JPEG data URI,
+/// without saving anything
+#[tauri::command]
+pub fn preview_photo_edits(
+    app_handle: AppHandle,
+    photo_type: String,
+    filename: String,
+    edits: PhotoEdits,
+) -> Result<String, String> {
+    validate_edits(&edits)?;
+    let photo_path = existing_photo_path(&app_handle, &photo_type, &filename)?;
+    let (img, orientation) = load_original(&photo_path)?;
+
+    // Downscale first so sliders stay responsive; crops are relative so the result matches
+    let img = img.resize(EDIT_PREVIEW_SIZE * 2, EDIT_PREVIEW_SIZE * 2, FilterType::Triangle);
+    let edited = apply_edits(img, orientation, &edits);
+    let preview = edited.resize(EDIT_PREVIEW_SIZE, EDIT_PREVIEW_SIZE, FilterType::Triangle);
+
+    let mut jpeg = Vec::new();
+    preview
+        .to_rgb8()
+        .write_to(&mut Cursor::new(&mut jpeg), image::ImageFormat::Jpeg)
+        .map_err(|e| format!("Failed to encode preview: {}", e))?;
+    Ok(format!("data:image/jpeg;base64,{}", STANDARD.encode(&jpeg)))
+}
+
+/// Store a photo's edits and regenerate its display, `_full` and `_thumb`
+/// versions from the original. The filename is unchanged, but when the edits
+/// rotate, flip or crop the photo, families using it lose their framing just
+/// as they do when a new photo is uploaded.
+#[tauri::command]
+pub fn save_photo_edits(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    photo_type: String,
+    filename: String,
+    edits: PhotoEdits,
+) -> Result<(), String> {
+    validate_edits(&edits)?;
+    let photo_path = existing_photo_path(&app_handle, &photo_type, &filename)?;
+    let previous = load_edits(&photo_path).unwrap_or_default();
+    let (img, orientation) = load_original(&photo_path)?;
+
+    let edited = apply_edits(img, orientation, &edits);
+    save_variants(&edited, &photo_path)?;
+
+    let edits_path = get_edits_path(&photo_path);
+    if edits == PhotoEdits::default() {
+        if edits_path.exists() {
+            fs::remove_file(&edits_path).map_err(|e| e.to_string())?;
+        }
+    } else {
+        let json = serde_json::to_string_pretty(&edits).map_err(|e| e.to_string())?;
+        fs::write(&edits_path, json).map_err(|e| e.to_string())?;
+    }
+
+    // Crop fractions refer to the old geometry
+    if photo_type == "families" && changes_geometry(&previous, &edits) {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE families SET photo_crop = NULL, updated_at = datetime('now')
+             WHERE photo_path = ? AND photo_crop IS NOT NULL",
+            rusqlite::params![filename],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Whether two sets of edits frame the photo differently, rather than only
+/// changing its tone
+fn changes_geometry(a: &PhotoEdits, b: &PhotoEdits) -> bool {
+    a.auto_orient != b.auto_orient
+        || a.rotation != b.rotation
+        || a.flip_horizontal != b.flip_horizontal
+        || a.flip_vertical != b.flip_vertical
+        || a.crop != b.crop
+}
+
+/// List files under photos/ that no family, member, staff member, setting or
+/// Markdown image uses, along with records whose photo file is missing
+#[tauri::command]
+pub fn scan_photo_storage(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+) -> Result<PhotoStorageReport, String> {
+    let photos_dir = Database::get_photos_dir(&app_handle);
+    let references = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        photo_references(&conn)?
+    };
+    Ok(scan_photos_dir(&photos_dir, &references))
+}
+
+/// Delete the orphaned files found by a fresh scan. A dry run deletes nothing
+/// and reports what would be removed.
+#[tauri::command]
+pub fn clean_orphaned_photos(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    dry_run: bool,
+) -> Result<PhotoCleanupResult, String> {
+    let photos_dir = Database::get_photos_dir(&app_handle);
+
+    // Held until the deletes finish so no photo can be attached in between
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    clean_photos_dir(&photos_dir, &photo_references(&conn)?, dry_run)
+}
+
+fn clean_photos_dir(
+    photos_dir: &Path,
+    references: &[PhotoReference],
+    dry_run: bool,
+) -> Result<PhotoCleanupResult, String> {
+    let report = scan_photos_dir(photos_dir, references);
+
+    if !dry_run {
+        for file in &report.orphaned_files {
+            match fs::remove_file(photos_dir.join(&file.path)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(format!("Failed to delete {}: {}", file.path, e)),
+            }
+        }
+    }
+
+    Ok(PhotoCleanupResult {
+        dry_run,
+        bytes_reclaimed: report.orphaned_bytes,
+        removed_files: report.orphaned_files,
+    })
+}
+
+/// A photo filename stored in the database
+struct PhotoReference {
+    source: &'static str,
+    record_id: Option<i64>,
+    setting_key: Option<&'static str>,
+    subject: String,
+    /// Folder under photos/ the file lives in
+    folder: &'static str,
+    filename: String,
+}
+
+fn photo_references(conn: &rusqlite::Connection) -> Result<Vec<PhotoReference>, String> {
+    let tables = [
+        (
+            "family",
+            "families",
+            "SELECT id, name, photo_path FROM families WHERE photo_path IS NOT NULL AND photo_path != ''",
+        ),
+        (
+            "member",
+            "members",
+            "SELECT id, first_name || ' ' || last_name, photo_path FROM members WHERE photo_path IS NOT NULL AND photo_path != ''",
+        ),
+        (
+            "staff",
+            "staff",
+            "SELECT id, name, photo_path FROM staff WHERE photo_path IS NOT NULL AND photo_path != ''",
+        ),
+    ];
+
+    let mut references = Vec::new();
+    for (source, folder, sql) in tables {
+        let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(PhotoReference {
+                    source,
+                    record_id: Some(row.get(0)?),
+                    setting_key: None,
+                    subject: row.get(1)?,
+                    folder,
+                    filename: row.get(2)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        for row in rows {
+            references.push(row.map_err(|e| e.to_string())?);
+        }
+    }
+
+    for (key, folder, description) in SETTING_PHOTOS {
+        let filename: Option<String> = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?",
+                rusqlite::params![key],
+                |row| row.get(0),
+            )
+            .ok()
+            .filter(|s: &String| !s.is_empty());
+
+        if let Some(filename) = filename {
+            references.push(PhotoReference {
+                source: "setting",
+                record_id: None,
+                setting_key: Some(key),
+                subject: description.to_string(),
+                folder,
+                filename,
+            });
+        }
+    }
+
+    // Images placed inline in the pastor's letter, first page and staff bios
+    for (key, description) in SETTING_MARKDOWN {
+        let markdown: Option<String> = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?",
+                rusqlite::params![key],
+                |row| row.get(0),
+            )
+            .ok();
+
+        for filename in markdown.as_deref().map(image_file_names).unwrap_or_default() {
+            references.push(PhotoReference {
+                source: "setting",
+                record_id: None,
+                setting_key: Some(key),
+                subject: description.to_string(),
+                folder: "directory",
+                filename,
+            });
+        }
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT id, name, bio FROM staff WHERE bio IS NOT NULL AND bio != ''")
+        .map_err(|e| e.to_string())?;
+    let bios = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))
+        .map_err(|e| e.to_string())?;
+    for bio in bios {
+        let (id, name, bio) = bio.map_err(|e| e.to_string())?;
+        for filename in image_file_names(&bio) {
+            references.push(PhotoReference {
+                source: "staff",
+                record_id: Some(id),
+                setting_key: None,
+                subject: format!("{} (bio image)", name),
+                folder: "directory",
+                filename,
+            });
+        }
+    }
+
+    Ok(references)
+}
+
+fn scan_photos_dir(photos_dir: &Path, references: &[PhotoReference]) -> PhotoStorageReport {
+    let referenced: HashSet<PathBuf> = references
+        .iter()
+        .map(|r| photos_dir.join(r.folder).join(&r.filename))
+        .collect();
+    // Variants, originals and edits are named after the photo's stem
+    let referenced_stems: HashSet<PathBuf> = referenced.iter().map(|path| path.with_extension("")).collect();
+
+    let mut file_count = 0;
+    let mut total_bytes = 0;
+    let mut orphaned_files = Vec::new();
+
+    for entry in WalkDir::new(photos_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let path = entry.path();
+        let metadata = entry.metadata().ok();
+        let size_bytes = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+        file_count += 1;
+        total_bytes += size_bytes;
+
+        if referenced.contains(path) {
+            continue;
+        }
+        let owner_stem = path
+            .file_name()
+            .and_then(|name| variant_stem(&name.to_string_lossy()).map(str::to_string));
+        if let (Some(stem), Some(parent)) = (owner_stem, path.parent()) {
+            if referenced_stems.contains(&parent.join(stem)) {
+                continue;
+            }
+        }
+
+        let recent = metadata
+            .and_then(|m| m.modified().ok())
+            .and_then(|modified| modified.elapsed().ok())
+            .map(|age| age < ORPHAN_GRACE_PERIOD)
+            .unwrap_or(true);
+        if recent {
+            continue;
+        }
+
+        let relative_path = path.strip_prefix(photos_dir).unwrap_or(path);
+        orphaned_files.push(StoredPhotoFile {
+            path: relative_path.to_string_lossy().replace('\\', "/"),
+            size_bytes,
+        });
+    }
+    orphaned_files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let missing_files = references
+        .iter()
+        .filter(|r| !photos_dir.join(r.folder).join(&r.filename).is_file())
+        .map(|r| MissingPhotoReference {
+            source: r.source.to_string(),
+            record_id: r.record_id,
+            setting_key: r.setting_key.map(str::to_string),
+            subject: r.subject.clone(),
+            photo_path: r.filename.clone(),
+        })
+        .collect();
+
+    PhotoStorageReport {
+        file_count,
+        total_bytes,
+        orphaned_bytes: orphaned_files.iter().map(|f| f.size_bytes).sum(),
+        orphaned_files,
+        missing_files,
+    }
+}
+
+/// The stem of the photo a `_thumb`, `_full`, `_original` or `_edits` file
+/// belongs to
+fn variant_stem(file_name: &str) -> Option<&str> {
+    ["_thumb.jpg", "_full.jpg", "_edits.json"]
+        .iter()
+        .find_map(|suffix| file_name.strip_suffix(suffix))
+        .or_else(|| file_name.rsplit_once("_original.").map(|(stem, _)| stem))
 }
 
 fn get_thumbnail_path(image_path: &Path) -> std::path::PathBuf {
@@ -291,3 +807,80 @@ fn get_full_resolution_path(image_path: &Path) -> std::path::PathBuf {
     let parent = image_path.parent().unwrap_or(Path::new(""));
     parent.join(format!("{}_full.jpg", stem))
 }
+
+fn get_edits_path(image_path: &Path) -> PathBuf {
+    let stem = image_path.file_stem().unwrap_or_default().to_string_lossy();
+    let parent = image_path.parent().unwrap_or(Path::new(""));
+    parent.join(format!("{}_edits.json", stem))
+}
+
+fn original_path_with_extension(image_path: &Path, extension: &str) -> PathBuf {
+    let stem = image_path.file_stem().unwrap_or_default().to_string_lossy();
+    let parent = image_path.parent().unwrap_or(Path::new(""));
+    parent.join(format!("{}_original.{}", stem, extension))
+}
+
+/// The original kept for a photo, in whatever format it was uploaded
+fn get_original_path(image_path: &Path) -> Option<PathBuf> {
+    let stem = image_path.file_stem()?.to_string_lossy().to_string();
+    let prefix = format!("{}_original.", stem);
+    fs::read_dir(image_path.parent()?)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().starts_with(&prefix))
+                .unwrap_or(false)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::migrate;
+    use rusqlite::{params, Connection};
+    use std::fs::File;
+    use std::time::SystemTime;
+
+    /// Write an empty file dated past the orphan grace period
+    fn old_file(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = File::create(path).unwrap();
+        file.set_modified(SystemTime::now() - ORPHAN_GRACE_PERIOD * 2).unwrap();
+    }
+
+    #[test]
+    fn cleanup_keeps_markdown_images() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES ('pastor_letter', ?)",
+            params!["Welcome!\n\n![Our sanctuary](sanctuary.jpg) ![Remote](https://example.com/x.jpg)"],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO staff (name, bio) VALUES ('Pat Lee', ?)",
+            params!["Pat leads worship.\n\n![Pat](images/pat-choir.png)"],
+        )
+        .unwrap();
+
+        let photos_dir = std::env::temp_dir().join(format!("photo-cleanup-{}", Uuid::new_v4()));
+        let directory = photos_dir.join("directory");
+        for name in ["sanctuary.jpg", "sanctuary_full.jpg", "pat-choir.png", "stray.jpg"] {
+            old_file(&directory.join(name));
+        }
+
+        let references = photo_references(&conn).unwrap();
+        let result = clean_photos_dir(&photos_dir, &references, false).unwrap();
+        let removed: Vec<&str> = result.removed_files.iter().map(|f| f.path.as_str()).collect();
+        let kept: Vec<bool> = ["sanctuary.jpg", "sanctuary_full.jpg", "pat-choir.png", "stray.jpg"]
+            .iter()
+            .map(|name| directory.join(name).exists())
+            .collect();
+        fs::remove_dir_all(&photos_dir).unwrap();
+
+        assert_eq!(removed, ["directory/stray.jpg"]);
+        assert_eq!(kept, [true, true, true, false]);
+    }
+}
@@ -0,0 +1,656 @@
+use crate::db::Database;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+// Sizes are in points, all other lengths are in mm, colors are "#RRGGBB".
+// Every section uses #[serde(default)] so themes saved by older versions keep
+// loading when new fields are added.
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfTheme {
+    pub colors: ThemeColors,
+    pub photos: PhotoTheme,
+    pub footer: FooterTheme,
+    pub cover: CoverTheme,
+    pub grid: GridTheme,
+    pub cards: CardTheme,
+    pub celebrations: CelebrationTheme,
+    pub markdown: MarkdownTheme,
+    pub staff: StaffTheme,
+    pub toc: TocTheme,
+    pub leadership: LeadershipTheme,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeColors {
+    /// Body text
+    pub text: String,
+    /// Page titles and section headings
+    pub heading: String,
+    /// Footer text and table-of-contents leaders
+    pub muted: String,
+    /// Short accent line under each celebration month
+    pub accent: String,
+    /// Horizontal rules between content blocks
+    pub rule: String,
+    /// Thin underlines beneath page titles
+    pub divider: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderStyle {
+    None,
+    Solid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhotoTheme {
+    /// Corner radius of staff portraits, as a fraction of the photo width
+    pub corner_radius: f32,
+    pub border_style: BorderStyle,
+    pub border_width: f32,
+    pub border_color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FooterTheme {
+    pub font_size: f32,
+    pub margin_x: f32,
+    pub baseline: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoverTheme {
+    pub title_size: f32,
+    pub date_size: f32,
+    pub line_spacing: f32,
+    /// Distance from the top inset to the first title line
+    pub title_offset: f32,
+    /// Inset around full-page cover and back cover images. 0 makes them
+    /// full-bleed, extending into the print bleed.
+    pub image_inset: f32,
+    /// Margin around generated covers, used when no cover image is set
+    pub margin: f32,
+    /// Tallest the church logo is drawn on generated covers
+    pub logo_height: f32,
+    /// Church name on generated covers
+    pub name_size: f32,
+    /// Address, phone, email and website on generated covers
+    pub contact_size: f32,
+    pub contact_line_height: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridTheme {
+    pub margin_x: f32,
+    pub margin_top: f32,
+    pub margin_bottom: f32,
+    pub col_spacing: f32,
+    pub row_spacing: f32,
+    pub cell_padding: f32,
+    /// Space below each photo for the name and children lines
+    pub caption_height: f32,
+    pub caption_size: f32,
+    pub caption_line_spacing: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CardTheme {
+    pub margin: f32,
+    pub col_gap: f32,
+    pub spacing: f32,
+    pub name_size: f32,
+    pub text_size: f32,
+    pub line_height: f32,
+    pub member_photo_size: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CelebrationTheme {
+    pub margin_x: f32,
+    pub margin_top: f32,
+    pub margin_bottom: f32,
+    pub title_size: f32,
+    pub month_size: f32,
+    pub section_size: f32,
+    pub text_size: f32,
+    pub line_height: f32,
+    pub month_spacing: f32,
+    pub section_spacing: f32,
+    pub col_gap: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkdownTheme {
+    pub margin_x: f32,
+    pub margin_top: f32,
+    pub h1_size: f32,
+    pub h2_size: f32,
+    pub h3_size: f32,
+    pub body_size: f32,
+    pub h1_line_height: f32,
+    pub h2_line_height: f32,
+    pub h3_line_height: f32,
+    pub body_line_height: f32,
+    pub paragraph_spacing: f32,
+    pub heading_spacing_before: f32,
+    pub list_indent: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StaffTheme {
+    pub margin_x: f32,
+    pub margin_top: f32,
+    pub section_title_size: f32,
+    pub name_size: f32,
+    pub title_size: f32,
+    pub photo_width: f32,
+    pub photo_height: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TocTheme {
+    pub margin_x: f32,
+    pub margin_top: f32,
+    pub title_size: f32,
+    pub entry_size: f32,
+    pub row_spacing: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LeadershipTheme {
+    pub margin_x: f32,
+    pub margin_top: f32,
+    pub title_size: f32,
+    pub ministry_size: f32,
+    pub names_size: f32,
+    pub names_line_height: f32,
+    pub row_spacing: f32,
+    /// Offset of the names column from the left margin
+    pub names_indent: f32,
+    /// Right-hand column for each ministry's contact, when any has one
+    pub contact_column_width: f32,
+    pub contact_size: f32,
+    pub contact_line_height: f32,
+}
+
+impl Default for ThemeColors {
+    fn default() -> Self {
+        Self {
+            text: "#000000".to_string(),
+            heading: "#000000".to_string(),
+            muted: "#595959".to_string(),
+            accent: "#B3B3B3".to_string(),
+            rule: "#666666".to_string(),
+            divider: "#999999".to_string(),
+        }
+    }
+}
+
+impl Default for PhotoTheme {
+    fn default() -> Self {
+        Self {
+            corner_radius: 0.06,
+            border_style: BorderStyle::Solid,
+            border_width: 0.5,
+            border_color: "#000000".to_string(),
+        }
+    }
+}
+
+impl Default for FooterTheme {
+    fn default() -> Self {
+        Self {
+            font_size: 7.0,
+            margin_x: 10.0,
+            baseline: 10.0,
+        }
+    }
+}
+
+impl Default for CoverTheme {
+    fn default() -> Self {
+        Self {
+            title_size: 22.0,
+            date_size: 16.0,
+            line_spacing: 10.0,
+            title_offset: 40.4, // ~1 inch below the inset
+            image_inset: 6.35,  // 1/4 inch
+            margin: 19.05,      // 3/4 inch
+            logo_height: 35.0,
+            name_size: 24.0,
+            contact_size: 11.0,
+            contact_line_height: 5.5,
+        }
+    }
+}
+
+impl Default for GridTheme {
+    fn default() -> Self {
+        Self {
+            margin_x: 8.0,
+            margin_top: 8.0,
+            margin_bottom: 1.0,
+            col_spacing: 4.0,
+            row_spacing: 0.75,
+            cell_padding: 0.5,
+            caption_height: 9.0,
+            caption_size: 9.0,
+            caption_line_spacing: 3.5,
+        }
+    }
+}
+
+impl Default for CardTheme {
+    fn default() -> Self {
+        Self {
+            margin: 15.0,
+            col_gap: 10.0,
+            spacing: 6.0,
+            name_size: 11.0,
+            text_size: 9.0,
+            line_height: 4.5,
+            member_photo_size: 8.0,
+        }
+    }
+}
+
+impl Default for CelebrationTheme {
+    fn default() -> Self {
+        Self {
+            margin_x: 15.0,
+            margin_top: 15.0,
+            margin_bottom: 17.0,
+            title_size: 16.0,
+            month_size: 12.0,
+            section_size: 8.0,
+            text_size: 10.0,
+            line_height: 4.6,
+            month_spacing: 6.0,
+            section_spacing: 3.0,
+            col_gap: 8.0,
+        }
+    }
+}
+
+impl Default for MarkdownTheme {
+    fn default() -> Self {
+        Self {
+            margin_x: 15.0,
+            margin_top: 20.0,
+            h1_size: 20.0,
+            h2_size: 16.0,
+            h3_size: 13.0,
+            body_size: 11.0,
+            h1_line_height: 9.0,
+            h2_line_height: 7.5,
+            h3_line_height: 6.5,
+            body_line_height: 5.5,
+            paragraph_spacing: 4.0,
+            heading_spacing_before: 6.0,
+            list_indent: 5.0,
+        }
+    }
+}
+
+impl Default for StaffTheme {
+    fn default() -> Self {
+        Self {
+            margin_x: 12.0,
+            margin_top: 20.0,
+            section_title_size: 18.0,
+            name_size: 11.0,
+            title_size: 10.0,
+            photo_width: 30.0,
+            photo_height: 45.0, // 4:6 aspect
+        }
+    }
+}
+
+impl Default for TocTheme {
+    fn default() -> Self {
+        Self {
+            margin_x: 20.0,
+            margin_top: 30.0,
+            title_size: 20.0,
+            entry_size: 12.0,
+            row_spacing: 9.0,
+        }
+    }
+}
+
+impl Default for LeadershipTheme {
+    fn default() -> Self {
+        Self {
+            margin_x: 12.0,
+            margin_top: 22.0,
+            title_size: 18.0,
+            ministry_size: 14.0,
+            names_size: 11.0,
+            names_line_height: 4.5,
+            row_spacing: 10.0,
+            names_indent: 75.0,
+            contact_column_width: 45.0,
+            contact_size: 11.0,
+            contact_line_height: 5.5,
+        }
+    }
+}
+
+/// A named starting point the user can apply and then adjust.
+#[derive(Debug, Clone, Serialize)]
+pub struct PdfThemePreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub theme: PdfTheme,
+}
+
+impl PdfTheme {
+    /// The original directory look
+    pub fn classic() -> Self {
+        Self::default()
+    }
+
+    /// Navy headings, softer rules and borderless rounded portraits
+    pub fn modern() -> Self {
+        let mut theme = Self::default();
+        theme.colors.heading = "#1F3A5F".to_string();
+        theme.colors.accent = "#4A78B0".to_string();
+        theme.colors.rule = "#A0AEC0".to_string();
+        theme.colors.divider = "#4A78B0".to_string();
+        theme.colors.muted = "#718096".to_string();
+        theme.photos.corner_radius = 0.12;
+        theme.photos.border_style = BorderStyle::None;
+        theme
+    }
+
+    /// Larger text throughout for readers who need it; pagination adapts
+    pub fn large_print() -> Self {
+        let mut theme = Self::default();
+        theme.scale_text(1.25);
+        theme
+    }
+
+    /// Scale every font size along with the line heights and spacing that
+    /// depend on it, leaving margins alone.
+    fn scale_text(&mut self, factor: f32) {
+        let f = factor;
+        self.footer.font_size *= f;
+
+        self.cover.title_size *= f;
+        self.cover.date_size *= f;
+        self.cover.line_spacing *= f;
+        self.cover.name_size *= f;
+        self.cover.contact_size *= f;
+        self.cover.contact_line_height *= f;
+
+        self.grid.caption_size *= f;
+        self.grid.caption_line_spacing *= f;
+        self.grid.caption_height *= f;
+
+        self.cards.name_size *= f;
+        self.cards.text_size *= f;
+        self.cards.line_height *= f;
+
+        let c = &mut self.celebrations;
+        c.title_size *= f;
+        c.month_size *= f;
+        c.section_size *= f;
+        c.text_size *= f;
+        c.line_height *= f;
+
+        let m = &mut self.markdown;
+        m.h1_size *= f;
+        m.h2_size *= f;
+        m.h3_size *= f;
+        m.body_size *= f;
+        m.h1_line_height *= f;
+        m.h2_line_height *= f;
+        m.h3_line_height *= f;
+        m.body_line_height *= f;
+
+        self.staff.section_title_size *= f;
+        self.staff.name_size *= f;
+        self.staff.title_size *= f;
+
+        self.toc.title_size *= f;
+        self.toc.entry_size *= f;
+        self.toc.row_spacing *= f;
+
+        let l = &mut self.leadership;
+        l.title_size *= f;
+        l.ministry_size *= f;
+        l.names_size *= f;
+        l.names_line_height *= f;
+        l.row_spacing *= f;
+        l.contact_size *= f;
+        l.contact_line_height *= f;
+    }
+
+    pub fn presets() -> Vec<PdfThemePreset> {
+        vec![
+            PdfThemePreset { id: "classic", name: "Classic", theme: Self::classic() },
+            PdfThemePreset { id: "modern", name: "Modern", theme: Self::modern() },
+            PdfThemePreset { id: "large_print", name: "Large Print", theme: Self::large_print() },
+        ]
+    }
+
+    /// Load the saved theme, falling back to the classic look if none is
+    /// saved or it can't be parsed or fails validation.
+    pub fn load(conn: &Connection) -> Self {
+        conn.query_row(
+            "SELECT value FROM settings WHERE key = 'pdf_theme'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|json| serde_json::from_str::<PdfTheme>(&json).ok())
+        // Themes saved before a check was added may not pass it
+        .filter(|theme| match theme.validate() {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("Ignoring saved PDF theme: {}", e);
+                false
+            }
+        })
+        .unwrap_or_default()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let colors = [
+            ("Text color", &self.colors.text),
+            ("Heading color", &self.colors.heading),
+            ("Muted color", &self.colors.muted),
+            ("Accent color", &self.colors.accent),
+            ("Rule color", &self.colors.rule),
+            ("Divider color", &self.colors.divider),
+            ("Photo border color", &self.photos.border_color),
+        ];
+        for (label, value) in colors {
+            let hex = value.trim_start_matches('#');
+            if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("{} must be a hex color like #1F3A5F", label));
+            }
+        }
+
+        if !(0.0..=0.5).contains(&self.photos.corner_radius) {
+            return Err("Photo corner radius must be between 0 and 0.5".to_string());
+        }
+
+        let sizes = [
+            self.footer.font_size,
+            self.cover.title_size,
+            self.cover.date_size,
+            self.cover.name_size,
+            self.cover.contact_size,
+            self.grid.caption_size,
+            self.cards.name_size,
+            self.cards.text_size,
+            self.celebrations.title_size,
+            self.celebrations.month_size,
+            self.celebrations.section_size,
+            self.celebrations.text_size,
+            self.markdown.h1_size,
+            self.markdown.h2_size,
+            self.markdown.h3_size,
+            self.markdown.body_size,
+            self.staff.section_title_size,
+            self.staff.name_size,
+            self.staff.title_size,
+            self.toc.title_size,
+            self.toc.entry_size,
+            self.leadership.title_size,
+            self.leadership.ministry_size,
+            self.leadership.names_size,
+            self.leadership.contact_size,
+        ];
+        if sizes.iter().any(|s| !(1.0..=96.0).contains(s)) {
+            return Err("Font sizes must be between 1 and 96 pt".to_string());
+        }
+
+        if !(0.0..=5.0).contains(&self.photos.border_width) {
+            return Err("Photo border width must be between 0 and 5 mm".to_string());
+        }
+
+        let spacing = [
+            self.footer.margin_x,
+            self.footer.baseline,
+            self.cover.image_inset,
+            self.cover.margin,
+            self.grid.margin_x,
+            self.grid.margin_top,
+            self.grid.margin_bottom,
+            self.grid.col_spacing,
+            self.grid.row_spacing,
+            self.grid.cell_padding,
+            self.cards.margin,
+            self.cards.col_gap,
+            self.cards.spacing,
+            self.celebrations.margin_x,
+            self.celebrations.margin_top,
+            self.celebrations.margin_bottom,
+            self.celebrations.month_spacing,
+            self.celebrations.section_spacing,
+            self.celebrations.col_gap,
+            self.markdown.margin_x,
+            self.markdown.margin_top,
+            self.markdown.paragraph_spacing,
+            self.markdown.heading_spacing_before,
+            self.markdown.list_indent,
+            self.staff.margin_x,
+            self.staff.margin_top,
+            self.toc.margin_x,
+            self.toc.margin_top,
+            self.toc.row_spacing,
+            self.leadership.margin_x,
+            self.leadership.margin_top,
+            self.leadership.row_spacing,
+        ];
+        if spacing.iter().any(|s| !(0.0..=60.0).contains(s)) {
+            return Err("Margins and spacing must be between 0 and 60 mm".to_string());
+        }
+
+        let line_heights = [
+            self.cover.line_spacing,
+            self.cover.contact_line_height,
+            self.grid.caption_line_spacing,
+            self.cards.line_height,
+            self.celebrations.line_height,
+            self.markdown.h1_line_height,
+            self.markdown.h2_line_height,
+            self.markdown.h3_line_height,
+            self.markdown.body_line_height,
+            self.leadership.names_line_height,
+            self.leadership.contact_line_height,
+        ];
+        if line_heights.iter().any(|h| !(1.0..=50.0).contains(h)) {
+            return Err("Line heights must be between 1 and 50 mm".to_string());
+        }
+
+        let boxes = [
+            self.cover.logo_height,
+            self.grid.caption_height,
+            self.cards.member_photo_size,
+            self.staff.photo_width,
+            self.staff.photo_height,
+            self.leadership.contact_column_width,
+        ];
+        if boxes.iter().any(|b| !(1.0..=150.0).contains(b)) {
+            return Err("Photo, logo, caption and column sizes must be between 1 and 150 mm".to_string());
+        }
+
+        let offsets = [self.cover.title_offset, self.leadership.names_indent];
+        if offsets.iter().any(|o| !(0.0..=150.0).contains(o)) {
+            return Err("Title offset and names indent must be between 0 and 150 mm".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub fn get_pdf_theme(db: State<'_, Database>) -> Result<PdfTheme, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    Ok(PdfTheme::load(&conn))
+}
+
+#[tauri::command]
+pub fn save_pdf_theme(db: State<'_, Database>, theme: PdfTheme) -> Result<(), String> {
+    theme.validate()?;
+    let json = serde_json::to_string(&theme).map_err(|e| e.to_string())?;
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('pdf_theme', ?)",
+        params![json],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_pdf_theme_presets() -> Vec<PdfThemePreset> {
+    PdfTheme::presets()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_are_valid() {
+        for preset in PdfTheme::presets() {
+            assert_eq!(preset.theme.validate(), Ok(()), "{}", preset.name);
+        }
+    }
+
+    #[test]
+    fn rejects_layout_that_cannot_paginate() {
+        let mut theme = PdfTheme::default();
+        theme.markdown.body_line_height = 0.0;
+        assert_eq!(theme.validate(), Err("Line heights must be between 1 and 50 mm".to_string()));
+
+        let mut theme = PdfTheme::default();
+        theme.grid.margin_top = -5.0;
+        assert_eq!(theme.validate(), Err("Margins and spacing must be between 0 and 60 mm".to_string()));
+
+        let mut theme = PdfTheme::default();
+        theme.grid.caption_height = 0.0;
+        assert!(theme.validate().is_err());
+    }
+}
@@ -0,0 +1,98 @@
+-- Directory names, family sort order, photo opt-out and staff titles added.
+-- directory_adults was filled by the one-off v2 migration and then edited.
+
+CREATE TABLE families (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    family_id TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    mailing_name TEXT,
+    address TEXT,
+    city TEXT,
+    state TEXT,
+    zip TEXT,
+    phone TEXT,
+    email TEXT,
+    photo_path TEXT,
+    notes TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    children TEXT,
+    alt_address TEXT,
+    alt_city TEXT,
+    alt_state TEXT,
+    alt_zip TEXT,
+    directory_adults TEXT,
+    directory_children TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    include_photo_in_directory INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE TABLE members (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    family_id INTEGER NOT NULL,
+    first_name TEXT NOT NULL,
+    last_name TEXT NOT NULL,
+    role TEXT,
+    birth_date TEXT,
+    wedding_date TEXT,
+    phone TEXT,
+    email TEXT,
+    photo_path TEXT,
+    notes TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (family_id) REFERENCES families(id) ON DELETE CASCADE
+);
+
+CREATE TABLE import_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    filename TEXT NOT NULL,
+    imported_at TEXT NOT NULL DEFAULT (datetime('now')),
+    families_created INTEGER NOT NULL DEFAULT 0,
+    families_updated INTEGER NOT NULL DEFAULT 0,
+    members_created INTEGER NOT NULL DEFAULT 0,
+    members_updated INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+CREATE TABLE leadership (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ministry TEXT NOT NULL,
+    names TEXT NOT NULL DEFAULT '',
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE staff (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    title TEXT NOT NULL DEFAULT '',
+    role TEXT NOT NULL DEFAULT 'staff',
+    photo_path TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_families_family_id ON families(family_id);
+CREATE INDEX idx_families_name ON families(name);
+CREATE INDEX idx_members_family_id ON members(family_id);
+CREATE INDEX idx_members_last_name ON members(last_name);
+
+INSERT INTO families (id, family_id, name, children, directory_adults, directory_children, sort_order) VALUES
+    (1, 'F001', 'Brown', 'Tim', 'Robert & Ann', 'Tim', 0);
+
+INSERT INTO members (family_id, first_name, last_name, role, sort_order) VALUES
+    (1, 'Bob', 'Brown', 'Head', 0),
+    (1, 'Ann', 'Brown', 'Spouse', 1),
+    (1, 'Tim', 'Brown', 'Child', 2);
+
+INSERT INTO staff (name, title, role, sort_order) VALUES ('Pat Lee', 'Senior Pastor', 'pastor', 0);
+
+INSERT INTO settings (key, value) VALUES ('migration_adults_from_members_v2', 'done');
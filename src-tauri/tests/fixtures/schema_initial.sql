@@ -0,0 +1,67 @@
+-- The first released schema: families, members, import history and settings.
+-- Includes a member whose family was deleted while foreign keys were off.
+
+CREATE TABLE families (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    family_id TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    address TEXT,
+    city TEXT,
+    state TEXT,
+    zip TEXT,
+    phone TEXT,
+    email TEXT,
+    photo_path TEXT,
+    notes TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE members (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    family_id INTEGER NOT NULL,
+    first_name TEXT NOT NULL,
+    last_name TEXT NOT NULL,
+    role TEXT,
+    birth_date TEXT,
+    phone TEXT,
+    email TEXT,
+    photo_path TEXT,
+    notes TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (family_id) REFERENCES families(id) ON DELETE CASCADE
+);
+
+CREATE TABLE import_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    filename TEXT NOT NULL,
+    imported_at TEXT NOT NULL DEFAULT (datetime('now')),
+    families_created INTEGER NOT NULL DEFAULT 0,
+    families_updated INTEGER NOT NULL DEFAULT 0,
+    members_created INTEGER NOT NULL DEFAULT 0,
+    members_updated INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+CREATE INDEX idx_families_family_id ON families(family_id);
+CREATE INDEX idx_families_name ON families(name);
+CREATE INDEX idx_members_family_id ON members(family_id);
+CREATE INDEX idx_members_last_name ON members(last_name);
+
+INSERT INTO families (id, family_id, name, city, state) VALUES
+    (1, 'F001', 'Smith', 'Springfield', 'IL'),
+    (2, 'F002', 'Jones', 'Springfield', 'IL');
+
+INSERT INTO members (family_id, first_name, last_name, role, birth_date) VALUES
+    (1, 'John', 'Smith', 'Head', '1970-03-14'),
+    (1, 'Jane', 'Smith', 'Spouse', 'July 1'),
+    (2, 'Mary', 'Jones', 'Head', 'sometime in May'),
+    (3, 'Lost', 'Member', 'Head', NULL);
+
+INSERT INTO settings (key, value) VALUES ('church_name', 'First Church');
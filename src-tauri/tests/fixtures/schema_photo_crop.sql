@@ -0,0 +1,98 @@
+-- The last schema before migrations were numbered: family photo framing
+-- added and orphaned members already removed by the one-off v1 repair.
+
+CREATE TABLE families (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    family_id TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    mailing_name TEXT,
+    address TEXT,
+    city TEXT,
+    state TEXT,
+    zip TEXT,
+    phone TEXT,
+    email TEXT,
+    photo_path TEXT,
+    notes TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    children TEXT,
+    alt_address TEXT,
+    alt_city TEXT,
+    alt_state TEXT,
+    alt_zip TEXT,
+    directory_adults TEXT,
+    directory_children TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    include_photo_in_directory INTEGER NOT NULL DEFAULT 1,
+    photo_crop TEXT
+);
+
+CREATE TABLE members (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    family_id INTEGER NOT NULL,
+    first_name TEXT NOT NULL,
+    last_name TEXT NOT NULL,
+    role TEXT,
+    birth_date TEXT,
+    wedding_date TEXT,
+    phone TEXT,
+    email TEXT,
+    photo_path TEXT,
+    notes TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (family_id) REFERENCES families(id) ON DELETE CASCADE
+);
+
+CREATE TABLE import_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    filename TEXT NOT NULL,
+    imported_at TEXT NOT NULL DEFAULT (datetime('now')),
+    families_created INTEGER NOT NULL DEFAULT 0,
+    families_updated INTEGER NOT NULL DEFAULT 0,
+    members_created INTEGER NOT NULL DEFAULT 0,
+    members_updated INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+CREATE TABLE leadership (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ministry TEXT NOT NULL,
+    names TEXT NOT NULL DEFAULT '',
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE staff (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    title TEXT NOT NULL DEFAULT '',
+    role TEXT NOT NULL DEFAULT 'staff',
+    photo_path TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_families_family_id ON families(family_id);
+CREATE INDEX idx_families_name ON families(name);
+CREATE INDEX idx_members_family_id ON members(family_id);
+CREATE INDEX idx_members_last_name ON members(last_name);
+
+INSERT INTO families (id, family_id, name, photo_path, photo_crop, directory_adults) VALUES
+    (1, 'F001', 'Garcia', 'family_1.jpg', '{"mode":"focus","x":0.5,"y":0.25}', 'Luis, Rosa');
+
+INSERT INTO members (family_id, first_name, last_name, role, sort_order) VALUES
+    (1, 'Luis', 'Garcia', 'Head', 0),
+    (1, 'Rosa', 'Garcia', 'Spouse', 1);
+
+INSERT INTO settings (key, value) VALUES
+    ('migration_adults_from_members_v2', 'done'),
+    ('migration_orphaned_members_v1', 'done');
@@ -0,0 +1,97 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { JobFinished, JobProgress } from "./types";
+
+export class JobCancelledError extends Error {
+  constructor() {
+    super("Cancelled");
+  }
+}
+
+export interface JobHandlers {
+  onStart?: (jobId: string) => void;
+  onProgress?: (progress: JobProgress) => void;
+}
+
+/**
+ * Invoke a command that runs as a background job and wait for it to finish.
+ * Resolves with the job's result, rejects with the error string on failure,
+ * and with a JobCancelledError if the job was cancelled.
+ */
+export async function runJob<T>(
+  command: string,
+  args: Record<string, unknown>,
+  handlers: JobHandlers = {}
+): Promise<T> {
+  let jobId: string | null = null;
+  let onFinished: ((finished: JobFinished) => void) | null = null;
+  // A fast job can finish before invoke() returns its id, so keep
+  // finished events until the id is known
+  const finishedJobs = new Map<string, JobFinished>();
+
+  const unlistenProgress = await listen<JobProgress>("job-progress", (event) => {
+    if (event.payload.job_id === jobId) {
+      handlers.onProgress?.(event.payload);
+    }
+  });
+  const unlistenFinished = await listen<JobFinished>("job-finished", (event) => {
+    if (event.payload.job_id === jobId && onFinished) {
+      onFinished(event.payload);
+    } else {
+      finishedJobs.set(event.payload.job_id, event.payload);
+    }
+  });
+
+  try {
+    const id = await invoke<string>(command, args);
+    jobId = id;
+    handlers.onStart?.(id);
+
+    const finished =
+      finishedJobs.get(id) ??
+      (await new Promise<JobFinished>((resolve) => {
+        onFinished = resolve;
+      }));
+
+    if (finished.status === "completed") {
+      return finished.result as T;
+    }
+    if (finished.status === "cancelled") {
+      throw new JobCancelledError();
+    }
+    throw finished.error ?? "Job failed";
+  } finally {
+    unlistenProgress();
+    unlistenFinished();
+  }
+}
+
+export function cancelJob(jobId: string): Promise<void> {
+  return invoke("cancel_job", { jobId });
+}
+
+const STAGE_LABELS: Record<string, string> = {
+  pages: "Rendering pages",
+  images: "Processing images",
+  bytes: "Writing file",
+  files: "Archiving files",
+  families: "Importing families",
+};
+
+function formatBytes(bytes: number): string {
+  if (bytes < 1024 * 1024) {
+    return `${Math.round(bytes / 1024)} KB`;
+  }
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+export function describeProgress(progress: JobProgress): string {
+  const label = STAGE_LABELS[progress.stage] ?? progress.stage;
+  if (progress.stage === "bytes") {
+    return `${label} (${formatBytes(progress.current)})`;
+  }
+  if (progress.total !== null) {
+    return `${label} ${progress.current} of ${progress.total}`;
+  }
+  return `${label} ${progress.current}`;
+}
@@ -0,0 +1,13 @@
+import type { PhotoCrop } from "./types";
+
+// CSS object-position that keeps a family's framing in view when a photo is
+// shown with object-cover (list avatars, previews). Approximates the PDF
+// crop, which centers the focus point as nearly as the photo allows.
+export function framingObjectPosition(crop?: PhotoCrop | null): string | undefined {
+  if (!crop) return undefined;
+  const [x, y] =
+    crop.mode === "focus"
+      ? [crop.x, crop.y]
+      : [crop.x + crop.width / 2, crop.y + crop.height / 2];
+  return `${Math.round(x * 100)}% ${Math.round(y * 100)}%`;
+}
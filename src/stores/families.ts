@@ -1,7 +1,7 @@
 import { defineStore } from "pinia";
 import { ref, computed } from "vue";
 import { invoke } from "@tauri-apps/api/core";
-import type { Family, Member, FamilyWithMembers } from "../types";
+import type { Family, Member, FamilyWithMembers, PhotoCrop } from "../types";
 
 export const useFamiliesStore = defineStore("families", () => {
   const families = ref<Family[]>([]);
@@ -225,6 +225,14 @@ export const useFamiliesStore = defineStore("families", () => {
     return await invoke<string>("get_photo_path", { relativePath: "families" });
   }
 
+  async function setFamilyPhotoCrop(familyId: number, photoCrop: PhotoCrop | null) {
+    await invoke("update_family", { id: familyId, family: { photo_crop: photoCrop } });
+    if (currentFamily.value?.id === familyId) {
+      await fetchFamily(familyId);
+    }
+    await fetchFamilies();
+  }
+
   async function cropFamilyPhotoToMember(
     familyId: number,
     memberId: number,
@@ -265,6 +273,7 @@ export const useFamiliesStore = defineStore("families", () => {
     getMember,
     saveFamilyPhoto,
     removeFamilyPhoto,
+    setFamilyPhotoCrop,
     getPhotosDir,
     cropFamilyPhotoToMember,
     lastViewedFamilyId,
@@ -1,7 +1,7 @@
 import { defineStore } from "pinia";
 import { ref } from "vue";
 import { invoke } from "@tauri-apps/api/core";
-import type { Leadership } from "../types";
+import type { Leadership, LeadershipInput } from "../types";
 
 export const useLeadershipStore = defineStore("leadership", () => {
   const entries = ref<Leadership[]>([]);
@@ -20,12 +20,10 @@ export const useLeadershipStore = defineStore("leadership", () => {
     }
   }
 
-  async function createEntry(ministry: string, names: string, sortOrder: number) {
+  async function createEntry(input: LeadershipInput) {
     error.value = null;
     try {
-      const entry = await invoke<Leadership>("create_leadership", {
-        input: { ministry, names, sort_order: sortOrder },
-      });
+      const entry = await invoke<Leadership>("create_leadership", { input });
       entries.value.push(entry);
       return entry;
     } catch (e) {
@@ -34,13 +32,10 @@ export const useLeadershipStore = defineStore("leadership", () => {
     }
   }
 
-  async function updateEntry(id: number, ministry: string, names: string, sortOrder: number) {
+  async function updateEntry(id: number, input: LeadershipInput) {
     error.value = null;
     try {
-      const entry = await invoke<Leadership>("update_leadership", {
-        id,
-        input: { ministry, names, sort_order: sortOrder },
-      });
+      const entry = await invoke<Leadership>("update_leadership", { id, input });
       const idx = entries.value.findIndex((e) => e.id === id);
       if (idx !== -1) entries.value[idx] = entry;
       return entry;
@@ -61,6 +56,22 @@ export const useLeadershipStore = defineStore("leadership", () => {
     }
   }
 
+  async function reorderEntries(ids: number[]) {
+    error.value = null;
+    try {
+      await invoke("reorder_leadership", { leadershipIds: ids });
+      entries.value = ids
+        .map((id, index) => {
+          const entry = entries.value.find((e) => e.id === id);
+          return entry ? { ...entry, sort_order: index } : undefined;
+        })
+        .filter((e): e is Leadership => e !== undefined);
+    } catch (e) {
+      error.value = String(e);
+      throw e;
+    }
+  }
+
   return {
     entries,
     loading,
@@ -69,5 +80,6 @@ export const useLeadershipStore = defineStore("leadership", () => {
     createEntry,
     updateEntry,
     deleteEntry,
+    reorderEntries,
   };
 });
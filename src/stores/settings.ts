@@ -1,7 +1,7 @@
 import { defineStore } from "pinia";
 import { ref } from "vue";
 import { invoke } from "@tauri-apps/api/core";
-import type { Settings, Theme } from "../types";
+import type { PdfFontVariant, PdfTheme, PdfThemePreset, Settings, Theme } from "../types";
 
 const defaultSettings: Settings = {
   church_name: "Our Church",
@@ -18,6 +18,7 @@ const defaultSettings: Settings = {
   cover_title_color: "#FFFFFF",
   first_page_markdown: undefined,
   back_cover_image_path: undefined,
+  locale: "en",
 };
 
 function applyTheme(theme: Theme) {
@@ -42,6 +43,8 @@ export const useSettingsStore = defineStore("settings", () => {
   const settings = ref<Settings>({ ...defaultSettings });
   const loading = ref(false);
   const error = ref<string | null>(null);
+  const pdfTheme = ref<PdfTheme | null>(null);
+  const pdfThemePresets = ref<PdfThemePreset[]>([]);
 
   async function fetchSettings() {
     loading.value = true;
@@ -106,6 +109,59 @@ export const useSettingsStore = defineStore("settings", () => {
     }
   }
 
+  async function setPdfFont(filePath: string, variant: PdfFontVariant) {
+    loading.value = true;
+    error.value = null;
+    try {
+      const savedName = await invoke<string>("save_pdf_font", { filePath, variant });
+      settings.value[`pdf_font_${variant}` as const] = savedName;
+    } catch (e) {
+      error.value = String(e);
+      throw e;
+    } finally {
+      loading.value = false;
+    }
+  }
+
+  async function clearPdfFont(variant: PdfFontVariant) {
+    loading.value = true;
+    error.value = null;
+    try {
+      await invoke("clear_pdf_font", { variant });
+      settings.value[`pdf_font_${variant}` as const] = undefined;
+    } catch (e) {
+      error.value = String(e);
+      throw e;
+    } finally {
+      loading.value = false;
+    }
+  }
+
+  async function fetchPdfTheme() {
+    error.value = null;
+    try {
+      const [theme, presets] = await Promise.all([
+        invoke<PdfTheme>("get_pdf_theme"),
+        invoke<PdfThemePreset[]>("get_pdf_theme_presets"),
+      ]);
+      pdfTheme.value = theme;
+      pdfThemePresets.value = presets;
+    } catch (e) {
+      error.value = String(e);
+    }
+  }
+
+  async function savePdfTheme(theme: PdfTheme) {
+    error.value = null;
+    try {
+      await invoke("save_pdf_theme", { theme });
+      pdfTheme.value = theme;
+    } catch (e) {
+      error.value = String(e);
+      throw e;
+    }
+  }
+
   function resetToDefaults() {
     settings.value = { ...defaultSettings };
   }
@@ -124,10 +180,16 @@ export const useSettingsStore = defineStore("settings", () => {
     settings,
     loading,
     error,
+    pdfTheme,
+    pdfThemePresets,
     fetchSettings,
     saveSettings,
     setChurchLogo,
     setDirectoryImage,
+    setPdfFont,
+    clearPdfFont,
+    fetchPdfTheme,
+    savePdfTheme,
     resetToDefaults,
     initTheme,
   };
@@ -1,22 +1,42 @@
 import { defineStore } from "pinia";
 import { ref, computed } from "vue";
 import { invoke } from "@tauri-apps/api/core";
-import type { Staff } from "../types";
+import type { Staff, StaffCategory, StaffCategoryInput, StaffInput } from "../types";
 
 export const useStaffStore = defineStore("staff", () => {
   const entries = ref<Staff[]>([]);
+  const categories = ref<StaffCategory[]>([]);
   const loading = ref(false);
   const error = ref<string | null>(null);
 
-  const pastor = computed(() => entries.value.find((e) => e.role === "pastor"));
-  const elders = computed(() => entries.value.filter((e) => e.role === "elder"));
-  const staffMembers = computed(() => entries.value.filter((e) => e.role === "staff"));
+  const entriesByCategory = computed(() => {
+    const groups: Record<string, Staff[]> = {};
+    for (const category of categories.value) groups[category.key] = [];
+    for (const entry of entries.value) (groups[entry.role] ??= []).push(entry);
+    return groups;
+  });
+
+  function toInput(entry: Staff): StaffInput {
+    return {
+      name: entry.name,
+      title: entry.title,
+      role: entry.role,
+      email: entry.email,
+      phone: entry.phone,
+      office_hours: entry.office_hours,
+      bio: entry.bio,
+      sort_order: entry.sort_order,
+    };
+  }
 
   async function fetchStaff() {
     loading.value = true;
     error.value = null;
     try {
-      entries.value = await invoke<Staff[]>("get_staff");
+      [entries.value, categories.value] = await Promise.all([
+        invoke<Staff[]>("get_staff"),
+        invoke<StaffCategory[]>("get_staff_categories"),
+      ]);
     } catch (e) {
       error.value = String(e);
     } finally {
@@ -24,12 +44,10 @@ export const useStaffStore = defineStore("staff", () => {
     }
   }
 
-  async function createEntry(name: string, title: string, role: string, sortOrder: number) {
+  async function createEntry(input: StaffInput) {
     error.value = null;
     try {
-      const entry = await invoke<Staff>("create_staff", {
-        input: { name, title, role, sort_order: sortOrder },
-      });
+      const entry = await invoke<Staff>("create_staff", { input });
       entries.value.push(entry);
       return entry;
     } catch (e) {
@@ -38,13 +56,10 @@ export const useStaffStore = defineStore("staff", () => {
     }
   }
 
-  async function updateEntry(id: number, name: string, title: string, role: string, sortOrder: number) {
+  async function updateEntry(id: number, input: StaffInput) {
     error.value = null;
     try {
-      const entry = await invoke<Staff>("update_staff", {
-        id,
-        input: { name, title, role, sort_order: sortOrder },
-      });
+      const entry = await invoke<Staff>("update_staff", { id, input });
       const idx = entries.value.findIndex((e) => e.id === id);
       if (idx !== -1) entries.value[idx] = entry;
       return entry;
@@ -88,10 +103,7 @@ export const useStaffStore = defineStore("staff", () => {
       if (entry?.photo_path) {
         await invoke("delete_photo", { photoPath: `staff/${entry.photo_path}` });
       }
-      await invoke("update_staff", {
-        id,
-        input: { name: entry!.name, title: entry!.title, role: entry!.role, sort_order: entry!.sort_order },
-      });
+      await invoke("update_staff", { id, input: toInput(entry!) });
       // Clear photo_path locally — the update_staff doesn't clear it, so we do it via a direct approach
       const idx = entries.value.findIndex((e) => e.id === id);
       if (idx !== -1) entries.value[idx].photo_path = undefined;
@@ -101,18 +113,74 @@ export const useStaffStore = defineStore("staff", () => {
     }
   }
 
+  async function createCategory(input: StaffCategoryInput) {
+    error.value = null;
+    try {
+      const category = await invoke<StaffCategory>("create_staff_category", { input });
+      categories.value.push(category);
+      return category;
+    } catch (e) {
+      error.value = String(e);
+      throw e;
+    }
+  }
+
+  async function updateCategory(id: number, input: StaffCategoryInput) {
+    error.value = null;
+    try {
+      const category = await invoke<StaffCategory>("update_staff_category", { id, input });
+      const idx = categories.value.findIndex((c) => c.id === id);
+      if (idx !== -1) categories.value[idx] = category;
+      return category;
+    } catch (e) {
+      error.value = String(e);
+      throw e;
+    }
+  }
+
+  async function deleteCategory(id: number) {
+    error.value = null;
+    try {
+      await invoke("delete_staff_category", { id });
+      categories.value = categories.value.filter((c) => c.id !== id);
+    } catch (e) {
+      error.value = String(e);
+      throw e;
+    }
+  }
+
+  async function reorderCategories(ids: number[]) {
+    error.value = null;
+    try {
+      await invoke("reorder_staff_categories", { categoryIds: ids });
+      categories.value = ids
+        .map((id, index) => {
+          const category = categories.value.find((c) => c.id === id);
+          return category ? { ...category, sort_order: index } : undefined;
+        })
+        .filter((c): c is StaffCategory => c !== undefined);
+    } catch (e) {
+      error.value = String(e);
+      throw e;
+    }
+  }
+
   return {
     entries,
+    categories,
     loading,
     error,
-    pastor,
-    elders,
-    staffMembers,
+    entriesByCategory,
+    toInput,
     fetchStaff,
     createEntry,
     updateEntry,
     deleteEntry,
     uploadPhoto,
     removePhoto,
+    createCategory,
+    updateCategory,
+    deleteCategory,
+    reorderCategories,
   };
 });
@@ -18,10 +18,35 @@ export interface Family {
   directory_adults?: string;
   directory_children?: string;
   include_photo_in_directory: boolean;
+  photo_crop?: PhotoCrop | null;
   created_at: string;
   updated_at: string;
 }
 
+// How a family photo is framed wherever it gets cropped. Coordinates are
+// fractions (0-1) of the photo's width and height from the top-left.
+export type PhotoCrop =
+  | { mode: "focus"; x: number; y: number }
+  | { mode: "rect"; x: number; y: number; width: number; height: number };
+
+// Non-destructive edits applied to a photo's original
+export interface PhotoEdits {
+  auto_orient: boolean;
+  rotation: 0 | 90 | 180 | 270;
+  flip_horizontal: boolean;
+  flip_vertical: boolean;
+  crop: CropRegion | null;
+  exposure: number;
+  contrast: number;
+}
+
+export interface CropRegion {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
 export interface Member {
   id: number;
   family_id: number;
@@ -47,27 +72,96 @@ export interface Staff {
   id: number;
   name: string;
   title: string;
-  role: "pastor" | "elder" | "staff";
+  // Key of the staff category the person is listed under
+  role: string;
   photo_path?: string;
+  email: string | null;
+  phone: string | null;
+  office_hours: string | null;
+  // Markdown, printed on the "Meet Our Staff" pages
+  bio: string | null;
+  sort_order: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface StaffInput {
+  name: string;
+  title: string;
+  role: string;
+  email: string | null;
+  phone: string | null;
+  office_hours: string | null;
+  bio: string | null;
+  sort_order: number;
+}
+
+export type StaffCategoryLayout = "featured" | "photo_grid" | "list";
+
+export interface StaffCategory {
+  id: number;
+  key: string;
+  title: string;
   sort_order: number;
+  photo_scale: number;
+  layout: StaffCategoryLayout;
   created_at: string;
   updated_at: string;
 }
 
+export interface StaffCategoryInput {
+  title: string;
+  photo_scale: number;
+  layout: StaffCategoryLayout;
+  sort_order: number;
+}
+
+export interface LeadershipPerson {
+  member_id: number | null;
+  name: string;
+}
+
 export interface Leadership {
   id: number;
   ministry: string;
-  names: string;
+  people: LeadershipPerson[];
+  contact: string | null;
   sort_order: number;
   created_at: string;
   updated_at: string;
 }
 
+export interface LeadershipInput {
+  ministry: string;
+  people: LeadershipPerson[];
+  contact: string | null;
+  sort_order: number;
+}
+
 export interface ImportPreview {
   families: ImportFamilyPreview[];
   total_families: number;
   total_members: number;
   duplicates: DuplicateMatch[];
+  date_issues: ImportDateIssue[];
+}
+
+export type DateOrder = "month_first" | "day_first";
+
+export interface ImportDateOptions {
+  order: DateOrder;
+  /** Two-digit years at or below this are read as 20xx, the rest as 19xx */
+  century_pivot: number;
+}
+
+export interface ImportDateIssue {
+  family_id: string;
+  member_name: string;
+  field: "birth_date" | "wedding_date";
+  value: string;
+  kind: "ambiguous" | "invalid";
+  imported_as?: string;
+  alternative?: string;
 }
 
 export interface ImportFamilyPreview {
@@ -125,8 +219,17 @@ export interface Settings {
   church_phone?: string;
   church_email?: string;
   church_website?: string;
+  pdf_font_regular?: string;
+  pdf_font_bold?: string;
+  pdf_font_italic?: string;
+  locale: DirectoryLocale;
 }
 
+/** Language the directory is printed in */
+export type DirectoryLocale = "en" | "es" | "ko";
+
+export type PdfFontVariant = "regular" | "bold" | "italic";
+
 export interface BackupManifest {
   version: string;
   created_at: string;
@@ -136,6 +239,48 @@ export interface BackupManifest {
   photo_count: number;
 }
 
+export interface ForeignKeyViolation {
+  table: string;
+  row_id: number | null;
+  parent_table: string;
+}
+
+export interface DatabaseIntegrityReport {
+  ok: boolean;
+  foreign_keys_enabled: boolean;
+  integrity_errors: string[];
+  foreign_key_violations: ForeignKeyViolation[];
+}
+
+export interface StoredPhotoFile {
+  path: string;
+  size_bytes: number;
+}
+
+export interface MissingPhotoReference {
+  source: "family" | "member" | "staff" | "setting";
+  record_id: number | null;
+  setting_key: string | null;
+  subject: string;
+  photo_path: string;
+}
+
+export interface PhotoStorageReport {
+  file_count: number;
+  total_bytes: number;
+  orphaned_files: StoredPhotoFile[];
+  orphaned_bytes: number;
+  missing_files: MissingPhotoReference[];
+}
+
+export interface PhotoCleanupResult {
+  dry_run: boolean;
+  removed_files: StoredPhotoFile[];
+  bytes_reclaimed: number;
+}
+
+export type CoverTemplate = "centered" | "left_aligned" | "none";
+
 export interface PdfOptions {
   church_name: string;
   cover_image_path?: string;
@@ -147,5 +292,162 @@ export interface PdfOptions {
   first_page_markdown?: string;
   back_cover_image_path?: string;
   celebration_image_path?: string;
+  cover_template?: CoverTemplate;
+  back_cover_template?: CoverTemplate;
   photo_grid_rows?: number;
+  include_table_of_contents?: boolean;
+  include_staff_bios?: boolean;
+  show_anniversary_years?: boolean;
+  highlight_birthday_milestones?: boolean;
+  celebrations_year?: number;
+  image_quality?: "print" | "screen" | "email";
+  bleed_mm?: number;
+  crop_marks?: boolean;
+  pdf_x?: boolean;
+  imposition?: "saddle_stitch" | "perfect_bound" | "two_up" | "four_up";
+  signature_sheets?: number;
+  duplex?: boolean;
+}
+
+export interface PdfPagePreview {
+  page_number: number;
+  image: string;
+}
+
+export interface PdfPreview {
+  page_count: number;
+  pages: PdfPagePreview[];
+}
+
+export interface PreflightIssue {
+  severity: "error" | "warning";
+  category: string;
+  family_id?: number;
+  member_id?: number;
+  staff_id?: number;
+  subject: string;
+  message: string;
+}
+
+export interface PreflightReport {
+  family_count: number;
+  issues: PreflightIssue[];
+}
+
+export type PdfThemeBorderStyle = "none" | "solid";
+
+export interface PdfTheme {
+  colors: {
+    text: string;
+    heading: string;
+    muted: string;
+    accent: string;
+    rule: string;
+    divider: string;
+  };
+  photos: {
+    corner_radius: number;
+    border_style: PdfThemeBorderStyle;
+    border_width: number;
+    border_color: string;
+  };
+  footer: Record<"font_size" | "margin_x" | "baseline", number>;
+  cover: Record<
+    | "title_size"
+    | "date_size"
+    | "line_spacing"
+    | "title_offset"
+    | "image_inset"
+    | "margin"
+    | "logo_height"
+    | "name_size"
+    | "contact_size"
+    | "contact_line_height",
+    number
+  >;
+  grid: Record<
+    | "margin_x"
+    | "margin_top"
+    | "margin_bottom"
+    | "col_spacing"
+    | "row_spacing"
+    | "cell_padding"
+    | "caption_height"
+    | "caption_size"
+    | "caption_line_spacing",
+    number
+  >;
+  cards: Record<
+    "margin" | "col_gap" | "spacing" | "name_size" | "text_size" | "line_height" | "member_photo_size",
+    number
+  >;
+  celebrations: Record<
+    | "margin_x"
+    | "margin_top"
+    | "margin_bottom"
+    | "title_size"
+    | "month_size"
+    | "section_size"
+    | "text_size"
+    | "line_height"
+    | "month_spacing"
+    | "section_spacing"
+    | "col_gap",
+    number
+  >;
+  markdown: Record<
+    | "margin_x"
+    | "margin_top"
+    | "h1_size"
+    | "h2_size"
+    | "h3_size"
+    | "body_size"
+    | "h1_line_height"
+    | "h2_line_height"
+    | "h3_line_height"
+    | "body_line_height"
+    | "paragraph_spacing"
+    | "heading_spacing_before"
+    | "list_indent",
+    number
+  >;
+  staff: Record<
+    "margin_x" | "margin_top" | "section_title_size" | "name_size" | "title_size" | "photo_width" | "photo_height",
+    number
+  >;
+  toc: Record<"margin_x" | "margin_top" | "title_size" | "entry_size" | "row_spacing", number>;
+  leadership: Record<
+    | "margin_x"
+    | "margin_top"
+    | "title_size"
+    | "ministry_size"
+    | "names_size"
+    | "names_line_height"
+    | "row_spacing"
+    | "names_indent"
+    | "contact_column_width"
+    | "contact_size"
+    | "contact_line_height",
+    number
+  >;
+}
+
+export interface PdfThemePreset {
+  id: string;
+  name: string;
+  theme: PdfTheme;
+}
+
+export interface JobProgress {
+  job_id: string;
+  stage: string;
+  current: number;
+  total: number | null;
+}
+
+export interface JobFinished {
+  job_id: string;
+  status: "completed" | "failed" | "cancelled";
+  result: unknown;
+  error: string | null;
 }